@@ -0,0 +1,35 @@
+#!/bin/sh
+# Scripted UCI engine: answers the handshake and reports the same analysis for any position
+searching=false
+while read -r line; do
+    case "$line" in
+        uci)
+            echo "id name Fake Engine"
+            echo "id author pigin"
+            echo "option name MultiPV type spin default 1 min 1 max 500"
+            echo "uciok"
+            ;;
+        isready)
+            echo "readyok"
+            ;;
+        go*)
+            echo "info string searching"
+            echo "info depth 1 seldepth 1 multipv 1 score cp 20 nodes 20 pv e2e4"
+            echo "info depth 2 seldepth 2 multipv 1 score cp 35 nodes 400 pv e2e4 e7e5"
+            echo "info depth 2 seldepth 2 multipv 2 score cp -10 nodes 400 pv d2d4 d7d5"
+            case "$line" in
+                *infinite*) searching=true ;;
+                *) echo "bestmove e2e4 ponder e7e5" ;;
+            esac
+            ;;
+        stop)
+            if [ "$searching" = true ]; then
+                echo "bestmove e2e4 ponder e7e5"
+                searching=false
+            fi
+            ;;
+        quit)
+            exit 0
+            ;;
+    esac
+done
@@ -0,0 +1,255 @@
+use crate::engine::{self, Score};
+use crate::model::{Board, Game, PieceColour, Ply};
+use crate::uci::{UciEngine, UciError};
+use crate::write::{self, Annotation};
+
+// Centipawns lost compared to the engine's best move
+const INACCURACY: i32 = 50;
+const MISTAKE: i32 = 100;
+const BLUNDER: i32 = 300;
+
+const INACCURACY_NAG: u8 = 6;
+const MISTAKE_NAG: u8 = 2;
+const BLUNDER_NAG: u8 = 4;
+
+const MATE_VALUE: i32 = 10_000;
+
+struct Evaluation {
+    // From white's point of view; absent once the game is over
+    score: Option<Score>,
+    centipawns: i32,
+    best_line: Vec<String>,
+}
+
+pub fn annotate(
+    game: &Game,
+    engine: &mut UciEngine,
+    depth: usize,
+) -> Result<Vec<Annotation>, UciError> {
+    let boards = game.boards();
+    let evaluations = boards
+        .iter()
+        .map(|board| evaluate(board, engine, depth))
+        .collect::<Result<Vec<Evaluation>, UciError>>()?;
+
+    Ok(boards
+        .iter()
+        .zip(game.pgn().ply())
+        .zip(evaluations.windows(2))
+        .map(|((board, ply), evaluations)| annotation(board, ply, &evaluations[0], &evaluations[1]))
+        .collect())
+}
+
+pub fn annotated_pgn(
+    game: &Game,
+    engine: &mut UciEngine,
+    depth: usize,
+) -> Result<String, UciError> {
+    let annotations = annotate(game, engine, depth)?;
+    Ok(write::write_annotated(game.pgn(), &annotations))
+}
+
+fn evaluate(board: &Board, engine: &mut UciEngine, depth: usize) -> Result<Evaluation, UciError> {
+    let colour = *board.active_colour();
+    if engine::legal_moves(board).is_empty() {
+        let centipawns = if engine::checkers(board).is_empty() {
+            0
+        } else {
+            match colour {
+                PieceColour::White => -MATE_VALUE,
+                PieceColour::Black => MATE_VALUE,
+            }
+        };
+        return Ok(Evaluation {
+            score: None,
+            centipawns,
+            best_line: Vec::new(),
+        });
+    }
+
+    let lines = engine.go_depth(&board.fen(), depth)?;
+    let info = lines
+        .first()
+        .ok_or_else(|| UciError::new(format!("No analysis returned for '{}'", board.fen())))?;
+    let score = info.score().from_side_to_move(colour);
+    Ok(Evaluation {
+        score: Some(score),
+        centipawns: centipawns(score),
+        best_line: info.pv().to_vec(),
+    })
+}
+
+fn annotation(board: &Board, ply: &Ply, before: &Evaluation, after: &Evaluation) -> Annotation {
+    let colour = *board.active_colour();
+    let loss = match colour {
+        PieceColour::White => before.centipawns - after.centipawns,
+        PieceColour::Black => after.centipawns - before.centipawns,
+    };
+
+    let best_move = before
+        .best_line
+        .first()
+        .and_then(|text| engine::from_uci(board, text));
+    let played = engine::resolve(board, ply.movement()).ok();
+    let played_best = best_move.is_some() && best_move == played;
+
+    let eval = after.score.map(|score| format!("[%eval {}]", eval(score)));
+    let nag = if played_best { None } else { classify(loss) };
+    let Some(nag) = nag else {
+        return Annotation::new(None, eval, Vec::new());
+    };
+
+    let variation = engine::san_line(board, &before.best_line);
+    let judgement = match nag {
+        BLUNDER_NAG => "Blunder.",
+        MISTAKE_NAG => "Mistake.",
+        _ => "Inaccuracy.",
+    };
+    let advice = variation
+        .first()
+        .map_or(String::new(), |best| format!(" {best} was best."));
+    let comment = [eval, Some(format!("{judgement}{advice}"))]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    Annotation::new(Some(nag), Some(comment), variation)
+}
+
+fn classify(loss: i32) -> Option<u8> {
+    if loss >= BLUNDER {
+        Some(BLUNDER_NAG)
+    } else if loss >= MISTAKE {
+        Some(MISTAKE_NAG)
+    } else if loss >= INACCURACY {
+        Some(INACCURACY_NAG)
+    } else {
+        None
+    }
+}
+
+fn centipawns(score: Score) -> i32 {
+    match score {
+        Score::Centipawns(centipawns) => centipawns,
+        Score::Mate(moves) => moves.signum() * MATE_VALUE - moves,
+    }
+}
+
+// %eval uses pawns without a leading '+'
+fn eval(score: Score) -> String {
+    match score {
+        Score::Centipawns(centipawns) => format!("{:.2}", f64::from(centipawns) / 100.0),
+        Score::Mate(moves) => format!("#{moves}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+
+    const FAKE_ENGINE: &str = "resources/test/engine/fake_engine.sh";
+
+    mod annotate_tests {
+        use super::*;
+
+        // The fake engine always prefers e2e4 and rates every position +0.35 for the side to move
+        #[test]
+        fn marks_moves_which_lose_ground() {
+            let game = game("1. d4 d5 *");
+            let mut engine = UciEngine::spawn(FAKE_ENGINE).unwrap();
+            let annotations = annotate(&game, &mut engine, 2).unwrap();
+
+            assert_eq!(
+                annotations,
+                vec![
+                    Annotation::new(
+                        Some(INACCURACY_NAG),
+                        Some("[%eval -0.35] Inaccuracy. e4 was best.".to_string()),
+                        vec!["e4".to_string(), "e5".to_string()]
+                    ),
+                    Annotation::new(
+                        Some(INACCURACY_NAG),
+                        Some("[%eval 0.35] Inaccuracy.".to_string()),
+                        Vec::new()
+                    ),
+                ]
+            )
+        }
+
+        #[test]
+        fn does_not_mark_best_move() {
+            let game = game("1. e4 *");
+            let mut engine = UciEngine::spawn(FAKE_ENGINE).unwrap();
+            let annotations = annotate(&game, &mut engine, 2).unwrap();
+
+            assert_eq!(
+                annotations,
+                vec![Annotation::new(
+                    None,
+                    Some("[%eval -0.35]".to_string()),
+                    Vec::new()
+                )]
+            )
+        }
+
+        #[test]
+        fn does_not_evaluate_checkmate() {
+            let game = game("1. f3 e5 2. g4 Qh4# 0-1");
+            let mut engine = UciEngine::spawn(FAKE_ENGINE).unwrap();
+            let annotations = annotate(&game, &mut engine, 2).unwrap();
+
+            assert_eq!(annotations[3].comment(), None)
+        }
+
+        #[test]
+        fn writes_annotated_pgn() {
+            let game = game("1. d4 *");
+            let mut engine = UciEngine::spawn(FAKE_ENGINE).unwrap();
+            let pgn = annotated_pgn(&game, &mut engine, 2).unwrap();
+
+            assert!(
+                pgn.ends_with("1. d4 $6 {[%eval -0.35] Inaccuracy. e4 was best.} (1. e4 e5) *\n")
+            );
+            assert!(parse(&pgn).is_ok())
+        }
+
+        #[test]
+        fn replaces_existing_evaluation() {
+            let game = game("1. d4 { [%eval 0.17] [%clk 0:03:00] } *");
+            let mut engine = UciEngine::spawn(FAKE_ENGINE).unwrap();
+            let pgn = annotated_pgn(&game, &mut engine, 2).unwrap();
+
+            assert!(pgn.ends_with(
+                "1. d4 $6 {[%eval -0.35] Inaccuracy. e4 was best. [%clk 0:03:00]} (1. e4 e5) *\n"
+            ));
+            assert_eq!(pgn.matches("[%eval").count(), 1)
+        }
+    }
+
+    mod classify_tests {
+        use super::*;
+
+        #[test]
+        fn classifies_loss() {
+            assert_eq!(classify(20), None);
+            assert_eq!(classify(50), Some(INACCURACY_NAG));
+            assert_eq!(classify(150), Some(MISTAKE_NAG));
+            assert_eq!(classify(900), Some(BLUNDER_NAG))
+        }
+
+        #[test]
+        fn converts_mate_to_centipawns() {
+            assert_eq!(centipawns(Score::Mate(3)), 9_997);
+            assert_eq!(centipawns(Score::Mate(-1)), -9_999)
+        }
+    }
+
+    fn game(movetext: &str) -> Game {
+        let mut pgns = parse(&format!("[Result \"*\"]\n\n{movetext}\n")).unwrap();
+        let pgn = pgns.remove(0);
+        let boards = engine::execute_moves(pgn.fen().starting_board(), pgn.ply()).unwrap();
+        Game::new(pgn, boards)
+    }
+}
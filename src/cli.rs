@@ -1,4 +1,8 @@
-use clap::{Arg, Command};
+use std::path::PathBuf;
+
+use clap::{value_parser, Arg, ArgAction, Command};
+
+use pigin::DEFAULT_FEN;
 
 pub fn pigin() -> Command {
     Command::new("pigin")
@@ -12,6 +16,238 @@ pub fn pigin() -> Command {
                 .num_args(1..)
                 .help("File or files to visualise"),
         )
+        .arg(
+            Arg::new("engine")
+                .long("engine")
+                .value_parser(value_parser!(PathBuf))
+                .help("UCI engine binary to analyse positions with"),
+        )
+        .arg(
+            Arg::new("opening")
+                .long("opening")
+                .help("Only show games whose opening matches an ECO code prefix or name"),
+        )
+        .subcommand_negates_reqs(true)
+        .subcommand(perft())
+        .subcommand(annotate())
+        .subcommand(search())
+        .subcommand(fmt())
+        .subcommand(show())
+        .subcommand(export())
+}
+
+fn perft() -> Command {
+    Command::new("perft")
+        .about("Count move generation leaf nodes from a position")
+        .arg(
+            Arg::new("depth")
+                .short('d')
+                .long("depth")
+                .required(true)
+                .value_parser(value_parser!(usize))
+                .help("Number of ply to search"),
+        )
+        .arg(
+            Arg::new("fen")
+                .long("fen")
+                .default_value(DEFAULT_FEN)
+                .help("Position to search from"),
+        )
+        .arg(
+            Arg::new("divide")
+                .long("divide")
+                .action(ArgAction::SetTrue)
+                .help("Break the count down by root move"),
+        )
+}
+
+fn annotate() -> Command {
+    Command::new("annotate")
+        .about("Annotate games with engine evaluations")
+        .arg(
+            Arg::new("input")
+                .required(true)
+                .help("File of games to annotate"),
+        )
+        .arg(
+            Arg::new("engine")
+                .long("engine")
+                .required(true)
+                .value_parser(value_parser!(PathBuf))
+                .help("UCI engine binary to evaluate positions with"),
+        )
+        .arg(
+            Arg::new("depth")
+                .short('d')
+                .long("depth")
+                .default_value("18")
+                .value_parser(value_parser!(usize))
+                .help("Depth to search each position to"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_parser(value_parser!(PathBuf))
+                .help("File to write annotated games to; defaults to stdout"),
+        )
+}
+
+fn search() -> Command {
+    Command::new("search")
+        .about("List games reaching a position described by a query")
+        .arg(
+            Arg::new("query")
+                .required(true)
+                .help("Query such as \"material KRPvKR\" or \"white queen sacrificed\""),
+        )
+        .arg(
+            Arg::new("input")
+                .required(true)
+                .num_args(1..)
+                .help("File or files of games to search"),
+        )
+}
+
+fn fmt() -> Command {
+    Command::new("fmt")
+        .about("Rewrite files of games in canonical export format")
+        .arg(
+            Arg::new("input")
+                .required(true)
+                .num_args(1..)
+                .help("File or files of games to format"),
+        )
+        .arg(
+            Arg::new("check")
+                .long("check")
+                .action(ArgAction::SetTrue)
+                .help("List files which aren't formatted, without rewriting them"),
+        )
+}
+
+fn show() -> Command {
+    Command::new("show")
+        .about("Print a diagram of a position from a game")
+        .arg(
+            Arg::new("input")
+                .required(true)
+                .help("File of games to show a position from"),
+        )
+        .arg(
+            Arg::new("game")
+                .short('g')
+                .long("game")
+                .default_value("1")
+                .value_parser(value_parser!(usize))
+                .help("Number of the game in the file, counting from 1"),
+        )
+        .arg(
+            Arg::new("ply")
+                .short('p')
+                .long("ply")
+                .value_parser(value_parser!(usize))
+                .help("Number of ply played before the position; defaults to the final position"),
+        )
+        .arg(
+            Arg::new("unicode")
+                .long("unicode")
+                .action(ArgAction::SetTrue)
+                .help("Draw pieces as figurines rather than letters"),
+        )
+        .arg(
+            Arg::new("flip")
+                .long("flip")
+                .action(ArgAction::SetTrue)
+                .help("Draw the board from black's side"),
+        )
+        .arg(
+            Arg::new("no-coordinates")
+                .long("no-coordinates")
+                .action(ArgAction::SetTrue)
+                .help("Leave out rank and file labels"),
+        )
+        .arg(
+            Arg::new("colour")
+                .long("colour")
+                .action(ArgAction::SetTrue)
+                .help("Shade squares and pieces with ANSI colours"),
+        )
+}
+
+fn export() -> Command {
+    Command::new("export")
+        .about("Export positions from a game")
+        .subcommand_required(true)
+        .subcommand(svg())
+}
+
+fn svg() -> Command {
+    Command::new("svg")
+        .about("Write SVG diagrams of a game's positions, one file per ply")
+        .arg(
+            Arg::new("input")
+                .required(true)
+                .help("File of games to export from"),
+        )
+        .arg(
+            Arg::new("game")
+                .short('g')
+                .long("game")
+                .default_value("1")
+                .value_parser(value_parser!(usize))
+                .help("Number of the game in the file, counting from 1"),
+        )
+        .arg(
+            Arg::new("ply")
+                .short('p')
+                .long("ply")
+                .num_args(1..)
+                .value_parser(value_parser!(usize))
+                .help("Numbers of ply played before each position; defaults to every position"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .default_value(".")
+                .value_parser(value_parser!(PathBuf))
+                .help("Directory to write diagrams to"),
+        )
+        .arg(
+            Arg::new("size")
+                .long("size")
+                .default_value("45")
+                .value_parser(value_parser!(u32).range(1..))
+                .help("Width of each square in pixels"),
+        )
+        .arg(
+            Arg::new("light")
+                .long("light")
+                .help("Colour of the light squares, such as \"#f0d9b5\""),
+        )
+        .arg(
+            Arg::new("dark")
+                .long("dark")
+                .help("Colour of the dark squares, such as \"#b58863\""),
+        )
+        .arg(
+            Arg::new("highlight")
+                .long("highlight")
+                .help("Colour drawn over the last move's squares"),
+        )
+        .arg(
+            Arg::new("flip")
+                .long("flip")
+                .action(ArgAction::SetTrue)
+                .help("Draw the board from black's side"),
+        )
+        .arg(
+            Arg::new("no-coordinates")
+                .long("no-coordinates")
+                .action(ArgAction::SetTrue)
+                .help("Leave out rank and file labels"),
+        )
 }
 
 #[cfg(test)]
@@ -20,22 +256,167 @@ mod tests {
 
     #[test]
     fn returns_err_if_no_file_provided() {
-        let matches = pigin().try_get_matches_from(&["pigin"]);
+        let matches = pigin().try_get_matches_from(["pigin"]);
         assert!(matches.is_err())
     }
 
     #[test]
     fn parses_file() {
-        let matches = pigin().get_matches_from(&["pigin", "--file", "example.pgn"]);
+        let matches = pigin().get_matches_from(["pigin", "--file", "example.pgn"]);
         let files: Vec<_> = matches.get_many::<String>("file").unwrap().collect();
         assert_eq!(files, vec!["example.pgn"])
     }
 
     #[test]
     fn parses_multiple_files() {
-        let matches =
-            pigin().get_matches_from(&["pigin", "--file", "example1.pgn", "example2.pgn"]);
+        let matches = pigin().get_matches_from(["pigin", "--file", "example1.pgn", "example2.pgn"]);
         let files: Vec<_> = matches.get_many::<String>("file").unwrap().collect();
         assert_eq!(files, vec!["example1.pgn", "example2.pgn"])
     }
+
+    #[test]
+    fn parses_engine() {
+        let matches = pigin().get_matches_from([
+            "pigin",
+            "--file",
+            "example.pgn",
+            "--engine",
+            "/usr/bin/stockfish",
+        ]);
+        assert_eq!(
+            matches.get_one::<PathBuf>("engine"),
+            Some(&PathBuf::from("/usr/bin/stockfish"))
+        )
+    }
+
+    #[test]
+    fn parses_opening() {
+        let matches =
+            pigin().get_matches_from(["pigin", "--file", "example.pgn", "--opening", "B9"]);
+        assert_eq!(matches.get_one::<String>("opening").unwrap(), "B9")
+    }
+
+    #[test]
+    fn parses_perft_without_file() {
+        let matches = pigin().get_matches_from(["pigin", "perft", "--depth", "3", "--divide"]);
+        let (name, perft) = matches.subcommand().unwrap();
+        assert_eq!(name, "perft");
+        assert_eq!(perft.get_one::<usize>("depth"), Some(&3));
+        assert_eq!(perft.get_one::<String>("fen").unwrap(), DEFAULT_FEN);
+        assert!(perft.get_flag("divide"))
+    }
+
+    #[test]
+    fn parses_annotate() {
+        let matches = pigin().get_matches_from([
+            "pigin",
+            "annotate",
+            "--engine",
+            "/usr/bin/stockfish",
+            "--depth",
+            "12",
+            "in.pgn",
+            "-o",
+            "out.pgn",
+        ]);
+        let (name, annotate) = matches.subcommand().unwrap();
+        assert_eq!(name, "annotate");
+        assert_eq!(annotate.get_one::<String>("input").unwrap(), "in.pgn");
+        assert_eq!(annotate.get_one::<usize>("depth"), Some(&12));
+        assert_eq!(
+            annotate.get_one::<PathBuf>("output"),
+            Some(&PathBuf::from("out.pgn"))
+        )
+    }
+
+    #[test]
+    fn parses_search() {
+        let matches = pigin().get_matches_from([
+            "pigin",
+            "search",
+            "opposite-bishops and check",
+            "a.pgn",
+            "b.pgn",
+        ]);
+        let (name, search) = matches.subcommand().unwrap();
+        assert_eq!(name, "search");
+        assert_eq!(
+            search.get_one::<String>("query").unwrap(),
+            "opposite-bishops and check"
+        );
+        let inputs: Vec<_> = search.get_many::<String>("input").unwrap().collect();
+        assert_eq!(inputs, vec!["a.pgn", "b.pgn"])
+    }
+
+    #[test]
+    fn parses_fmt() {
+        let matches = pigin().get_matches_from(["pigin", "fmt", "--check", "a.pgn", "b.pgn"]);
+        let (name, fmt) = matches.subcommand().unwrap();
+        assert_eq!(name, "fmt");
+        assert!(fmt.get_flag("check"));
+        let inputs: Vec<_> = fmt.get_many::<String>("input").unwrap().collect();
+        assert_eq!(inputs, vec!["a.pgn", "b.pgn"])
+    }
+
+    #[test]
+    fn parses_show() {
+        let matches = pigin().get_matches_from([
+            "pigin",
+            "show",
+            "--game",
+            "3",
+            "--ply",
+            "20",
+            "--unicode",
+            "--flip",
+            "a.pgn",
+        ]);
+        let (name, show) = matches.subcommand().unwrap();
+        assert_eq!(name, "show");
+        assert_eq!(show.get_one::<String>("input").unwrap(), "a.pgn");
+        assert_eq!(show.get_one::<usize>("game"), Some(&3));
+        assert_eq!(show.get_one::<usize>("ply"), Some(&20));
+        assert!(show.get_flag("unicode"));
+        assert!(show.get_flag("flip"));
+        assert!(!show.get_flag("colour"))
+    }
+
+    #[test]
+    fn parses_export_svg() {
+        let matches = pigin().get_matches_from([
+            "pigin", "export", "svg", "a.pgn", "--ply", "0", "12", "--dark", "#8ca2ad", "-o", "out",
+        ]);
+        let (name, export) = matches.subcommand().unwrap();
+        assert_eq!(name, "export");
+        let (name, svg) = export.subcommand().unwrap();
+        assert_eq!(name, "svg");
+        assert_eq!(svg.get_one::<String>("input").unwrap(), "a.pgn");
+        assert_eq!(svg.get_one::<usize>("game"), Some(&1));
+        let plies: Vec<_> = svg.get_many::<usize>("ply").unwrap().collect();
+        assert_eq!(plies, vec![&0, &12]);
+        assert_eq!(svg.get_one::<String>("dark").unwrap(), "#8ca2ad");
+        assert_eq!(svg.get_one::<u32>("size"), Some(&45));
+        assert_eq!(
+            svg.get_one::<PathBuf>("output"),
+            Some(&PathBuf::from("out"))
+        )
+    }
+
+    #[test]
+    fn returns_err_if_export_format_not_provided() {
+        let matches = pigin().try_get_matches_from(["pigin", "export", "a.pgn"]);
+        assert!(matches.is_err())
+    }
+
+    #[test]
+    fn returns_err_if_annotate_engine_not_provided() {
+        let matches = pigin().try_get_matches_from(["pigin", "annotate", "in.pgn"]);
+        assert!(matches.is_err())
+    }
+
+    #[test]
+    fn returns_err_if_perft_depth_not_provided() {
+        let matches = pigin().try_get_matches_from(["pigin", "perft"]);
+        assert!(matches.is_err())
+    }
 }
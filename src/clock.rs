@@ -0,0 +1,278 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use crate::model::{Pgn, PieceColour, TimeControl};
+
+// Remaining time below a tenth of the base time, or half a minute without a time control
+const TIME_TROUBLE_DIVISOR: u32 = 10;
+const TIME_TROUBLE_DEFAULT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PlyTime {
+    colour: PieceColour,
+    clock: Option<Duration>,
+    spent: Option<Duration>,
+    time_trouble: bool,
+}
+
+impl PlyTime {
+    pub fn colour(&self) -> PieceColour {
+        self.colour
+    }
+
+    // Time left after the move, as recorded by `[%clk]`
+    pub fn clock(&self) -> Option<Duration> {
+        self.clock
+    }
+
+    pub fn spent(&self) -> Option<Duration> {
+        self.spent
+    }
+
+    pub fn is_time_trouble(&self) -> bool {
+        self.time_trouble
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Summary {
+    moves: u32,
+    total: Duration,
+    longest: Option<(usize, Duration)>,
+    time_trouble: Option<usize>,
+}
+
+impl Summary {
+    // Moves with a known time spent
+    pub fn moves(&self) -> u32 {
+        self.moves
+    }
+
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    pub fn average(&self) -> Option<Duration> {
+        (self.moves > 0).then(|| self.total / self.moves)
+    }
+
+    // Index of the ply with the longest think, and its duration
+    pub fn longest(&self) -> Option<(usize, Duration)> {
+        self.longest
+    }
+
+    // Index of the first ply played in time trouble
+    pub fn time_trouble(&self) -> Option<usize> {
+        self.time_trouble
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.average(), self.longest) {
+            (Some(average), Some((_, longest))) => write!(
+                f,
+                "avg {}, longest {}",
+                format_duration(average),
+                format_duration(longest)
+            ),
+            _ => write!(f, "no times"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct TimeUsage {
+    plies: Vec<PlyTime>,
+    summaries: [Summary; 2],
+}
+
+impl TimeUsage {
+    pub fn plies(&self) -> &[PlyTime] {
+        &self.plies
+    }
+
+    pub fn summary(&self, colour: PieceColour) -> &Summary {
+        &self.summaries[colour.index()]
+    }
+
+    pub fn has_times(&self) -> bool {
+        self.plies.iter().any(|ply| ply.spent.is_some())
+    }
+}
+
+pub fn time_usage(pgn: &Pgn) -> TimeUsage {
+    let control = pgn.tags().time_control().unwrap_or(TimeControl::Unknown);
+    let initial = control.initial();
+    let threshold = initial.map_or(TIME_TROUBLE_DEFAULT, |initial| {
+        initial / TIME_TROUBLE_DIVISOR
+    });
+
+    let mut previous = [initial, initial];
+    let mut move_counts = [0, 0];
+    let mut summaries = [Summary::default(); 2];
+    let mut colour = *pgn.fen().active_colour();
+
+    let plies = pgn
+        .ply()
+        .iter()
+        .enumerate()
+        .map(|(idx, ply)| {
+            let side = colour.index();
+            move_counts[side] += 1;
+            let clock = ply.command("clk").and_then(clock_time);
+            let spent = ply.command("emt").and_then(clock_time).or_else(|| {
+                let (before, after) = (previous[side]?, clock?);
+                Some((before + control.added_after(move_counts[side])).saturating_sub(after))
+            });
+            if clock.is_some() {
+                previous[side] = clock;
+            }
+            let time_trouble = clock.is_some_and(|clock| clock < threshold);
+
+            let summary = &mut summaries[side];
+            if let Some(spent) = spent {
+                summary.moves += 1;
+                summary.total += spent;
+                if summary.longest.is_none_or(|(_, longest)| spent > longest) {
+                    summary.longest = Some((idx, spent));
+                }
+            }
+            if time_trouble && summary.time_trouble.is_none() {
+                summary.time_trouble = Some(idx);
+            }
+
+            let ply_time = PlyTime {
+                colour,
+                clock,
+                spent,
+                time_trouble,
+            };
+            colour = colour.opposite();
+            ply_time
+        })
+        .collect();
+
+    TimeUsage { plies, summaries }
+}
+
+// "1:05" for a minute or more, otherwise "4.2s"
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds {
+        3600.. => format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        ),
+        60.. => format!("{}:{:02}", seconds / 60, seconds % 60),
+        _ => format!("{seconds}.{}s", duration.subsec_millis() / 100),
+    }
+}
+
+// "H:MM:SS.s", as written by `[%clk]` and `[%emt]`
+fn clock_time(text: &str) -> Option<Duration> {
+    text.split(':').try_fold(Duration::ZERO, |total, part| {
+        Some(total * 60 + seconds(part)?)
+    })
+}
+
+fn seconds(text: &str) -> Option<Duration> {
+    text.parse::<f64>()
+        .ok()
+        .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+        // Whole milliseconds, so tenths like "58.9" subtract exactly
+        .map(|seconds| Duration::from_millis((seconds * 1000.0).round() as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+
+    mod time_usage_tests {
+        use super::*;
+
+        #[test]
+        fn derives_time_spent_from_clocks() {
+            let usage = usage(
+                "300+2",
+                "1. e4 {[%clk 0:05:01]} e5 {[%clk 0:04:58.5]} 2. Nf3 {[%clk 0:04:50]} *",
+            );
+            let spent: Vec<Option<Duration>> = usage.plies().iter().map(PlyTime::spent).collect();
+            assert_eq!(
+                spent,
+                vec![
+                    Some(Duration::from_secs(1)),
+                    Some(Duration::from_millis(3500)),
+                    Some(Duration::from_secs(13))
+                ]
+            );
+            assert_eq!(usage.plies()[1].colour(), PieceColour::Black)
+        }
+
+        #[test]
+        fn prefers_elapsed_move_time() {
+            let usage = usage("-", "1. e4 {[%emt 0:00:07]} e5 {[%clk 0:04:58]} *");
+            assert_eq!(usage.plies()[0].spent(), Some(Duration::from_secs(7)));
+            assert_eq!(usage.plies()[1].spent(), None);
+            assert!(usage.has_times())
+        }
+
+        #[test]
+        fn summarises_each_player() {
+            let usage = usage(
+                "60",
+                "1. e4 {[%clk 0:00:58]} e5 {[%clk 0:00:59]} 2. Nf3 {[%clk 0:00:48]} \
+                 Nc6 {[%clk 0:00:50]} 3. Bb5 {[%clk 0:00:05]} *",
+            );
+            let white = usage.summary(PieceColour::White);
+            assert_eq!(white.moves(), 3);
+            assert_eq!(white.average(), Some(Duration::from_secs(55) / 3));
+            assert_eq!(white.longest(), Some((4, Duration::from_secs(43))));
+            assert_eq!(white.time_trouble(), Some(4));
+            assert!(usage.plies()[4].is_time_trouble());
+            assert_eq!(usage.summary(PieceColour::Black).time_trouble(), None);
+            assert_eq!(
+                usage.summary(PieceColour::Black).to_string(),
+                "avg 5.0s, longest 9.0s"
+            )
+        }
+
+        #[test]
+        fn adds_base_time_after_each_period() {
+            let usage = usage(
+                "1/86400",
+                "1. e4 {[%clk 23:00:00]} e5 {[%clk 12:00:00]} 2. Nf3 {[%clk 20:00:00]} *",
+            );
+            assert_eq!(
+                usage.plies()[2].spent(),
+                Some(Duration::from_secs(27 * 3600))
+            )
+        }
+
+        #[test]
+        fn has_no_times_without_clocks() {
+            let usage = usage("600", "1. e4 e5 *");
+            assert!(!usage.has_times());
+            assert_eq!(usage.summary(PieceColour::White).to_string(), "no times")
+        }
+
+        fn usage(time_control: &str, movetext: &str) -> TimeUsage {
+            let text = format!("[TimeControl \"{time_control}\"]\n[Result \"*\"]\n\n{movetext}\n");
+            time_usage(&parse(&text).unwrap().remove(0))
+        }
+    }
+
+    mod format_duration_tests {
+        use super::*;
+
+        #[test]
+        fn formats_short_and_long_durations() {
+            assert_eq!(format_duration(Duration::from_millis(4250)), "4.2s");
+            assert_eq!(format_duration(Duration::from_secs(65)), "1:05");
+            assert_eq!(format_duration(Duration::from_secs(3725)), "1:02:05")
+        }
+    }
+}
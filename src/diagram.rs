@@ -0,0 +1,267 @@
+use crate::model::{Board, Piece, PieceColour, PieceType, Position, COLUMNS, MAX_POSITION, ROWS};
+
+const RESET: &str = "\x1b[0m";
+const LIGHT_SQUARE: &str = "\x1b[48;5;180m";
+const DARK_SQUARE: &str = "\x1b[48;5;137m";
+const WHITE_PIECE: &str = "\x1b[1;97m";
+const BLACK_PIECE: &str = "\x1b[1;30m";
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Glyphs {
+    // FEN letters, with '.' for empty squares
+    #[default]
+    Ascii,
+    // Chess figurines, with '·' for empty squares
+    Unicode,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DiagramOptions {
+    glyphs: Glyphs,
+    coordinates: bool,
+    perspective: PieceColour,
+    colour: bool,
+}
+
+impl Default for DiagramOptions {
+    fn default() -> Self {
+        DiagramOptions {
+            glyphs: Glyphs::Ascii,
+            coordinates: true,
+            perspective: PieceColour::White,
+            colour: false,
+        }
+    }
+}
+
+impl DiagramOptions {
+    pub fn glyphs(mut self, glyphs: Glyphs) -> Self {
+        self.glyphs = glyphs;
+        self
+    }
+
+    pub fn coordinates(mut self, coordinates: bool) -> Self {
+        self.coordinates = coordinates;
+        self
+    }
+
+    // The side shown at the bottom of the diagram
+    pub fn perspective(mut self, perspective: PieceColour) -> Self {
+        self.perspective = perspective;
+        self
+    }
+
+    // Shade squares and pieces with ANSI escape codes
+    pub fn colour(mut self, colour: bool) -> Self {
+        self.colour = colour;
+        self
+    }
+}
+
+// One line per rank, ending in a newline, with pockets above and below in drop variants
+pub fn diagram(board: &Board, options: DiagramOptions) -> String {
+    let perspective = options.perspective;
+    let ordered = |i: i8| match perspective {
+        PieceColour::White => i,
+        PieceColour::Black => MAX_POSITION - i,
+    };
+    // Shaded squares are padded, so sit side by side
+    let separator = if options.colour { "" } else { " " };
+
+    let mut lines: Vec<String> = (0..=MAX_POSITION)
+        .rev()
+        .map(|i| {
+            let row = ordered(i);
+            let squares: Vec<String> = (0..=MAX_POSITION)
+                .map(|j| square(board, Position::new(row, ordered(j)), options))
+                .collect();
+            let rank = squares.join(separator);
+            if options.coordinates {
+                format!("{} {rank}", label(ROWS, row))
+            } else {
+                rank
+            }
+        })
+        .collect();
+
+    if options.coordinates {
+        let files: Vec<String> = (0..=MAX_POSITION)
+            .map(|j| label(COLUMNS, ordered(j)))
+            .map(|file| {
+                if options.colour {
+                    format!(" {file} ")
+                } else {
+                    file
+                }
+            })
+            .collect();
+        lines.push(format!("  {}", files.join(separator)));
+    }
+
+    if board.pocket(perspective).is_some() {
+        lines.insert(0, pocket(board, perspective.opposite(), options.glyphs));
+        lines.push(pocket(board, perspective, options.glyphs));
+    }
+
+    lines.into_iter().map(|line| line + "\n").collect()
+}
+
+fn square(board: &Board, position: Position, options: DiagramOptions) -> String {
+    let occupant = board.occupant(position);
+    let glyph = occupant.map_or_else(
+        || empty(options.glyphs).to_string(),
+        |piece| glyph(piece, options.glyphs),
+    );
+    if !options.colour {
+        return glyph;
+    }
+
+    let background = if (position.row() + position.col()) % 2 == 0 {
+        DARK_SQUARE
+    } else {
+        LIGHT_SQUARE
+    };
+    let foreground = match occupant.map(Piece::colour) {
+        Some(PieceColour::White) => WHITE_PIECE,
+        Some(PieceColour::Black) => BLACK_PIECE,
+        None => "",
+    };
+    // Empty squares are left blank, as the shading already shows them
+    let glyph = if occupant.is_some() {
+        glyph
+    } else {
+        " ".to_string()
+    };
+    format!("{background}{foreground} {glyph} {RESET}")
+}
+
+fn glyph(piece: &Piece, glyphs: Glyphs) -> String {
+    match glyphs {
+        Glyphs::Ascii => piece.to_string(),
+        Glyphs::Unicode => figurine(piece).to_string(),
+    }
+}
+
+fn figurine(piece: &Piece) -> char {
+    match (piece.colour(), piece.piece_type()) {
+        (PieceColour::White, PieceType::King) => '♔',
+        (PieceColour::White, PieceType::Queen) => '♕',
+        (PieceColour::White, PieceType::Rook) => '♖',
+        (PieceColour::White, PieceType::Bishop) => '♗',
+        (PieceColour::White, PieceType::Knight) => '♘',
+        (PieceColour::White, PieceType::Pawn) => '♙',
+        (PieceColour::Black, PieceType::King) => '♚',
+        (PieceColour::Black, PieceType::Queen) => '♛',
+        (PieceColour::Black, PieceType::Rook) => '♜',
+        (PieceColour::Black, PieceType::Bishop) => '♝',
+        (PieceColour::Black, PieceType::Knight) => '♞',
+        (PieceColour::Black, PieceType::Pawn) => '♟',
+    }
+}
+
+fn empty(glyphs: Glyphs) -> char {
+    match glyphs {
+        Glyphs::Ascii => '.',
+        Glyphs::Unicode => '·',
+    }
+}
+
+fn label(labels: &str, index: i8) -> String {
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| labels.chars().nth(index))
+        .map_or_else(String::new, String::from)
+}
+
+fn pocket(board: &Board, colour: PieceColour, glyphs: Glyphs) -> String {
+    let pieces: Vec<String> = board
+        .pocket(colour)
+        .map(|pocket| pocket.pieces())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(piece_type, count)| {
+            let glyph = glyph(&Piece::new(colour, piece_type), glyphs);
+            match count {
+                1 => glyph,
+                count => format!("{glyph}x{count}"),
+            }
+        })
+        .collect();
+    let held = if pieces.is_empty() {
+        "-".to_string()
+    } else {
+        pieces.join(" ")
+    };
+    format!("{colour:?} pocket: {held}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{parse_fen, DEFAULT_FEN};
+
+    mod diagram_tests {
+        use super::*;
+
+        #[test]
+        fn draws_ascii_board_with_coordinates() {
+            assert_eq!(
+                diagram(&board(DEFAULT_FEN), DiagramOptions::default()),
+                "8 r n b q k b n r\n\
+                 7 p p p p p p p p\n\
+                 6 . . . . . . . .\n\
+                 5 . . . . . . . .\n\
+                 4 . . . . . . . .\n\
+                 3 . . . . . . . .\n\
+                 2 P P P P P P P P\n\
+                 1 R N B Q K B N R\n  \
+                 a b c d e f g h\n"
+            )
+        }
+
+        #[test]
+        fn draws_from_black_side() {
+            let board = board("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1");
+            let options = DiagramOptions::default().perspective(PieceColour::Black);
+            let lines: Vec<String> = diagram(&board, options).lines().map(String::from).collect();
+            assert_eq!(lines[0], "1 . . . K . . . R");
+            assert_eq!(lines[7], "8 . . . k . . . .");
+            assert_eq!(lines[8], "  h g f e d c b a")
+        }
+
+        #[test]
+        fn draws_figurines_without_coordinates() {
+            let board = board("4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+            let options = DiagramOptions::default()
+                .glyphs(Glyphs::Unicode)
+                .coordinates(false);
+            let text = diagram(&board, options);
+            assert_eq!(text.lines().count(), 8);
+            assert_eq!(text.lines().next(), Some("· · · · ♚ · · ·"));
+            assert_eq!(text.lines().last(), Some("· · · · ♔ · · ♖"))
+        }
+
+        #[test]
+        fn shades_squares_with_colour() {
+            let options = DiagramOptions::default().colour(true);
+            let text = diagram(&board(DEFAULT_FEN), options);
+            let first = text.lines().next().unwrap();
+            assert!(first.starts_with(&format!("8 {LIGHT_SQUARE}{BLACK_PIECE} r {RESET}")));
+            assert_eq!(first.matches(RESET).count(), 8);
+            assert_eq!(text.lines().last(), Some("   a  b  c  d  e  f  g  h "))
+        }
+
+        #[test]
+        fn draws_pockets_in_drop_variants() {
+            let board = board("4k3/8/8/8/8/8/8/4K3[Qnn] w - - 0 1");
+            let text = diagram(&board, DiagramOptions::default());
+            let lines: Vec<&str> = text.lines().collect();
+            assert_eq!(lines.first(), Some(&"Black pocket: nx2"));
+            assert_eq!(lines.last(), Some(&"White pocket: Q"))
+        }
+
+        fn board(fen: &str) -> Board {
+            parse_fen(fen).unwrap().starting_board().clone()
+        }
+    }
+}
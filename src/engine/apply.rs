@@ -0,0 +1,475 @@
+use crate::model::{
+    AvailableCastle, Bitboard, Board, Move, Piece, PieceColour, PieceType, Pocket, Position,
+    Variant,
+};
+
+use super::{
+    active_colour,
+    castle::{
+        BLACK_KINGS_ROOK_POSITION, BLACK_QUEENS_ROOK_POSITION, WHITE_KINGS_ROOK_POSITION,
+        WHITE_QUEENS_ROOK_POSITION,
+    },
+    clocks, en_passant, update_available_castles, variant,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Undo {
+    movement: Move,
+    captured: Option<(Piece, Position)>,
+    available_castles: Vec<AvailableCastle>,
+    en_passant_square: Option<Position>,
+    halfmove_clock: usize,
+    fullmove_clock: usize,
+    pockets: Option<[Pocket; 2]>,
+    promoted: Bitboard,
+    // Atomic: every piece removed by the explosion, the capturing piece included
+    exploded: Vec<(Piece, Position)>,
+    checks: (u8, u8),
+}
+
+impl Undo {
+    pub fn movement(&self) -> &Move {
+        &self.movement
+    }
+
+    pub fn captured(&self) -> Option<Piece> {
+        self.captured.map(|(piece, _)| piece)
+    }
+}
+
+pub fn apply(board: &Board, movement: &Move) -> Board {
+    let mut next_board = board.clone();
+    make_move(&mut next_board, movement);
+    next_board
+}
+
+pub fn make_move(board: &mut Board, movement: &Move) -> Undo {
+    let piece = movement.piece();
+    let (from, to) = (movement.from(), movement.to());
+    let mut undo = Undo {
+        movement: *movement,
+        captured: captured(board, movement),
+        available_castles: board.available_castles().to_vec(),
+        en_passant_square: board.en_passant_square().copied(),
+        halfmove_clock: board.halfmove_clock(),
+        fullmove_clock: board.fullmove_clock(),
+        pockets: board.pockets(),
+        promoted: board.promoted(),
+        exploded: Vec::new(),
+        checks: (
+            board.checks(PieceColour::White),
+            board.checks(PieceColour::Black),
+        ),
+    };
+
+    if movement.is_drop() {
+        board.remove_from_pocket(piece);
+        board.remove_en_passant_square();
+    } else {
+        board.remove(from);
+        if let Some(en_passant_square) = undo.en_passant_square {
+            en_passant::current(piece, to, en_passant_square, board);
+        }
+        board.capture(to);
+    }
+
+    match movement.promotes_to() {
+        None => board.add(piece, to),
+        Some(other) => board.add(Piece::new(*piece.colour(), other), to),
+    };
+    if movement.promotes_to().is_some() || (!movement.is_drop() && undo.promoted.contains(from)) {
+        board.mark_promoted(to);
+    }
+
+    if movement.is_castle() {
+        let (rook_before, rook_after) = castle_rook_positions(movement);
+        board.remove(rook_before);
+        board.add(Piece::new(*piece.colour(), PieceType::Rook), rook_after);
+    }
+
+    if undo.captured.is_some() && board.variant() == Variant::Atomic {
+        undo.exploded = variant::explode(board, movement);
+        for &(_, position) in &undo.exploded {
+            remove_captured_rook_castle(position, board);
+        }
+    }
+
+    active_colour::update(board);
+    update_available_castles(piece, from, board);
+    remove_captured_rook_castle(to, board);
+    en_passant::next(piece, from, to, board);
+    clocks::halfmove(board, *piece.piece_type(), undo.captured.is_some());
+    clocks::fullmove(board, *piece.colour());
+    variant::count_check(board);
+
+    undo
+}
+
+pub fn unmake_move(board: &mut Board, undo: Undo) {
+    let movement = undo.movement;
+    let piece = movement.piece();
+
+    for &(exploded, position) in &undo.exploded {
+        board.add(exploded, position);
+    }
+    board.remove(movement.to());
+    if !movement.is_drop() {
+        board.add(piece, movement.from());
+    }
+
+    if movement.is_castle() {
+        let (rook_before, rook_after) = castle_rook_positions(&movement);
+        board.remove(rook_after);
+        board.add(Piece::new(*piece.colour(), PieceType::Rook), rook_before);
+    }
+
+    if let Some((captured, position)) = undo.captured {
+        board.add(captured, position);
+    }
+    if let Some(pockets) = undo.pockets {
+        board.update_pockets(pockets, undo.promoted);
+    }
+
+    board.update_active_colour(*piece.colour());
+    board.update_available_castles(&undo.available_castles);
+    match undo.en_passant_square {
+        Some(position) => board.update_en_passant_square(position),
+        None => board.remove_en_passant_square(),
+    }
+    board.update_halfmove_clock(undo.halfmove_clock);
+    board.update_fullmove_clock(undo.fullmove_clock);
+    board.update_checks(undo.checks.0, undo.checks.1);
+}
+
+pub fn is_capture(board: &Board, movement: &Move) -> bool {
+    board.occupant(movement.to()).is_some()
+        || (*movement.piece().piece_type() == PieceType::Pawn
+            && movement.from().col() != movement.to().col())
+}
+
+fn captured(board: &Board, movement: &Move) -> Option<(Piece, Position)> {
+    let to = movement.to();
+    if let Some(&piece) = board.occupant(to) {
+        return Some((piece, to));
+    }
+
+    if !is_capture(board, movement) {
+        return None;
+    }
+    let position = Position::new(movement.from().row(), to.col());
+    board.occupant(position).map(|&piece| (piece, position))
+}
+
+fn castle_rook_positions(movement: &Move) -> (Position, Position) {
+    let (from, to) = (movement.from(), movement.to());
+    if to.col() > from.col() {
+        (Position::new(from.row(), 7), Position::new(from.row(), 5))
+    } else {
+        (Position::new(from.row(), 0), Position::new(from.row(), 3))
+    }
+}
+
+fn remove_captured_rook_castle(position: Position, board: &mut Board) -> &mut Board {
+    if position == *WHITE_KINGS_ROOK_POSITION {
+        board.remove_available_castle(AvailableCastle::WhiteKingside);
+    } else if position == *WHITE_QUEENS_ROOK_POSITION {
+        board.remove_available_castle(AvailableCastle::WhiteQueenside);
+    } else if position == *BLACK_KINGS_ROOK_POSITION {
+        board.remove_available_castle(AvailableCastle::BlackKingside);
+    } else if position == *BLACK_QUEENS_ROOK_POSITION {
+        board.remove_available_castle(AvailableCastle::BlackQueenside);
+    }
+    board
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PieceColour;
+
+    #[test]
+    fn moves_piece() {
+        let board = board();
+        let next_board = apply(
+            &board,
+            &Move::new(
+                Piece::new(PieceColour::White, PieceType::Knight),
+                Position::new(0, 1),
+                Position::new(2, 2),
+                None,
+            ),
+        );
+        assert!(next_board.occupant(Position::new(0, 1)).is_none());
+        assert_eq!(
+            next_board.occupant(Position::new(2, 2)),
+            Some(&Piece::new(PieceColour::White, PieceType::Knight))
+        );
+        assert_eq!(next_board.active_colour(), &PieceColour::Black);
+        assert_eq!(next_board.halfmove_clock(), 1);
+    }
+
+    #[test]
+    fn updates_hash_incrementally() {
+        let next_board = apply(
+            &board(),
+            &Move::new(
+                Piece::new(PieceColour::White, PieceType::King),
+                Position::new(0, 4),
+                Position::new(0, 6),
+                None,
+            ),
+        );
+
+        let mut builder = Board::builder();
+        builder
+            .available_castles(vec![AvailableCastle::BlackKingside])
+            .active_colour(PieceColour::Black)
+            .piece(
+                Piece::new(PieceColour::White, PieceType::King),
+                Position::new(0, 6),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Rook),
+                Position::new(0, 5),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Knight),
+                Position::new(0, 1),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Pawn),
+                Position::new(6, 0),
+            )
+            .piece(
+                Piece::new(PieceColour::Black, PieceType::Rook),
+                Position::new(7, 7),
+            );
+        let expected = builder.build();
+
+        assert_eq!(next_board.zobrist(), expected.zobrist());
+        assert_eq!(next_board, expected);
+    }
+
+    #[test]
+    fn moves_rook_when_castling() {
+        let board = board();
+        let next_board = apply(
+            &board,
+            &Move::new(
+                Piece::new(PieceColour::White, PieceType::King),
+                Position::new(0, 4),
+                Position::new(0, 6),
+                None,
+            ),
+        );
+        assert_eq!(
+            next_board.occupant(Position::new(0, 5)),
+            Some(&Piece::new(PieceColour::White, PieceType::Rook))
+        );
+        assert!(next_board.occupant(Position::new(0, 7)).is_none());
+        assert_eq!(
+            next_board.available_castles(),
+            vec![AvailableCastle::BlackKingside]
+        );
+    }
+
+    #[test]
+    fn removes_castle_if_rook_is_captured() {
+        let board = board();
+        let next_board = apply(
+            &board,
+            &Move::new(
+                Piece::new(PieceColour::White, PieceType::Rook),
+                Position::new(0, 7),
+                Position::new(7, 7),
+                None,
+            ),
+        );
+        assert!(next_board.available_castles().is_empty());
+        assert_eq!(next_board.halfmove_clock(), 0);
+    }
+
+    #[test]
+    fn promotes_pawn() {
+        let board = board();
+        let next_board = apply(
+            &board,
+            &Move::new(
+                Piece::new(PieceColour::White, PieceType::Pawn),
+                Position::new(6, 0),
+                Position::new(7, 0),
+                Some(PieceType::Queen),
+            ),
+        );
+        assert_eq!(
+            next_board.occupant(Position::new(7, 0)),
+            Some(&Piece::new(PieceColour::White, PieceType::Queen))
+        );
+    }
+
+    mod unmake_move_tests {
+        use super::*;
+
+        #[test]
+        fn restores_captured_piece() {
+            assert_restores(
+                board(),
+                Move::new(
+                    Piece::new(PieceColour::White, PieceType::Rook),
+                    Position::new(0, 7),
+                    Position::new(7, 7),
+                    None,
+                ),
+            );
+        }
+
+        #[test]
+        fn restores_castle() {
+            assert_restores(
+                board(),
+                Move::new(
+                    Piece::new(PieceColour::White, PieceType::King),
+                    Position::new(0, 4),
+                    Position::new(0, 6),
+                    None,
+                ),
+            );
+        }
+
+        #[test]
+        fn restores_promotion() {
+            assert_restores(
+                board(),
+                Move::new(
+                    Piece::new(PieceColour::White, PieceType::Pawn),
+                    Position::new(6, 0),
+                    Position::new(7, 0),
+                    Some(PieceType::Knight),
+                ),
+            );
+        }
+
+        #[test]
+        fn restores_en_passant_capture() {
+            let mut builder = Board::builder();
+            builder
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::Pawn),
+                    Position::new(4, 4),
+                )
+                .piece(
+                    Piece::new(PieceColour::Black, PieceType::Pawn),
+                    Position::new(4, 3),
+                )
+                .en_passant_square(Position::new(5, 3))
+                .halfmove_clock(0);
+            let board = builder.build();
+            let movement = Move::new(
+                Piece::new(PieceColour::White, PieceType::Pawn),
+                Position::new(4, 4),
+                Position::new(5, 3),
+                None,
+            );
+
+            let mut next_board = board.clone();
+            let undo = make_move(&mut next_board, &movement);
+            assert_eq!(
+                undo.captured(),
+                Some(Piece::new(PieceColour::Black, PieceType::Pawn))
+            );
+            assert!(next_board.occupant(Position::new(4, 3)).is_none());
+
+            assert_restores(board, movement);
+        }
+
+        #[test]
+        fn restores_drop_and_pocketed_capture() {
+            let knight = Piece::new(PieceColour::White, PieceType::Knight);
+            let mut board = board();
+            board.enable_pockets(false);
+            board.add_to_pocket(knight);
+
+            assert_restores(board.clone(), Move::drop(knight, Position::new(2, 2)));
+            assert_restores(
+                board,
+                Move::new(
+                    Piece::new(PieceColour::White, PieceType::Rook),
+                    Position::new(0, 7),
+                    Position::new(7, 7),
+                    None,
+                ),
+            );
+        }
+
+        #[test]
+        fn restores_atomic_explosion_and_checks() {
+            let mut board = board();
+            board.update_variant(Variant::Atomic);
+            assert_restores(
+                board.clone(),
+                Move::new(
+                    Piece::new(PieceColour::White, PieceType::Rook),
+                    Position::new(0, 7),
+                    Position::new(7, 7),
+                    None,
+                ),
+            );
+
+            board.update_variant(Variant::ThreeCheck);
+            assert_restores(
+                board,
+                Move::new(
+                    Piece::new(PieceColour::White, PieceType::Rook),
+                    Position::new(0, 7),
+                    Position::new(7, 7),
+                    None,
+                ),
+            );
+        }
+
+        fn assert_restores(board: Board, movement: Move) {
+            let mut next_board = board.clone();
+            let undo = make_move(&mut next_board, &movement);
+            assert_eq!(next_board, apply(&board, &movement));
+
+            unmake_move(&mut next_board, undo);
+            assert_eq!(next_board, board);
+            assert_eq!(next_board.zobrist(), board.zobrist());
+            assert_eq!(next_board.available_castles(), board.available_castles());
+            assert_eq!(next_board.en_passant_square(), board.en_passant_square());
+            assert_eq!(next_board.halfmove_clock(), board.halfmove_clock());
+            assert_eq!(next_board.fullmove_clock(), board.fullmove_clock());
+        }
+    }
+
+    fn board() -> Board {
+        let mut builder = Board::builder();
+        builder
+            .available_castles(vec![
+                AvailableCastle::WhiteKingside,
+                AvailableCastle::BlackKingside,
+            ])
+            .piece(
+                Piece::new(PieceColour::White, PieceType::King),
+                Position::new(0, 4),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Rook),
+                Position::new(0, 7),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Knight),
+                Position::new(0, 1),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Pawn),
+                Position::new(6, 0),
+            )
+            .piece(
+                Piece::new(PieceColour::Black, PieceType::Rook),
+                Position::new(7, 7),
+            )
+            .halfmove_clock(0);
+        builder.build()
+    }
+}
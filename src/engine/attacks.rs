@@ -0,0 +1,555 @@
+use crate::model::{Bitboard, Board, Piece, PieceColour, PieceType, Position};
+
+type Metric = (i8, i8);
+
+const KING_METRICS: &[Metric] = &[
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+];
+const KNIGHT_METRICS: &[Metric] = &[
+    (1, -2),
+    (2, -1),
+    (2, 1),
+    (1, 2),
+    (-1, 2),
+    (-2, 1),
+    (-2, -1),
+    (-1, -2),
+];
+const WHITE_PAWN_CAPTURES: &[Metric] = &[(1, -1), (1, 1)];
+const BLACK_PAWN_CAPTURES: &[Metric] = &[(-1, -1), (-1, 1)];
+
+// Rays which move towards higher square indices are blocked by their lowest set bit, the rest by
+// their highest
+const POSITIVE_RAYS: &[Metric] = &[(1, 0), (0, 1), (1, 1), (1, -1)];
+const NEGATIVE_RAYS: &[Metric] = &[(-1, 0), (0, -1), (-1, -1), (-1, 1)];
+
+static KING_ATTACKS: [u64; 64] = leaper_table(KING_METRICS);
+static KNIGHT_ATTACKS: [u64; 64] = leaper_table(KNIGHT_METRICS);
+static WHITE_PAWN_ATTACKS: [u64; 64] = leaper_table(WHITE_PAWN_CAPTURES);
+static BLACK_PAWN_ATTACKS: [u64; 64] = leaper_table(BLACK_PAWN_CAPTURES);
+static POSITIVE_RAY_TABLE: [[u64; 64]; 4] = ray_tables(POSITIVE_RAYS);
+static NEGATIVE_RAY_TABLE: [[u64; 64]; 4] = ray_tables(NEGATIVE_RAYS);
+
+const fn leaper_table(metrics: &[Metric]) -> [u64; 64] {
+    let mut table = [0; 64];
+    let mut square = 0;
+    while square < 64 {
+        let mut i = 0;
+        while i < metrics.len() {
+            table[square] |= ray(square, metrics[i], 1);
+            i += 1;
+        }
+        square += 1;
+    }
+    table
+}
+
+const fn ray_tables(metrics: &[Metric]) -> [[u64; 64]; 4] {
+    let mut tables = [[0; 64]; 4];
+    let mut direction = 0;
+    while direction < 4 {
+        let mut square = 0;
+        while square < 64 {
+            tables[direction][square] = ray(square, metrics[direction], 8);
+            square += 1;
+        }
+        direction += 1;
+    }
+    tables
+}
+
+const fn ray(square: usize, metric: Metric, max_steps: i8) -> u64 {
+    let mut bits = 0;
+    let mut row = (square / 8) as i8 + metric.0;
+    let mut col = (square % 8) as i8 + metric.1;
+    let mut steps = 0;
+    while steps < max_steps && row >= 0 && row < 8 && col >= 0 && col < 8 {
+        bits |= 1 << (row * 8 + col);
+        row += metric.0;
+        col += metric.1;
+        steps += 1;
+    }
+    bits
+}
+
+pub fn king(position: Position) -> Bitboard {
+    Bitboard::new(KING_ATTACKS[position.index()])
+}
+
+pub fn knight(position: Position) -> Bitboard {
+    Bitboard::new(KNIGHT_ATTACKS[position.index()])
+}
+
+pub fn pawn(position: Position, colour: PieceColour) -> Bitboard {
+    match colour {
+        PieceColour::White => Bitboard::new(WHITE_PAWN_ATTACKS[position.index()]),
+        PieceColour::Black => Bitboard::new(BLACK_PAWN_ATTACKS[position.index()]),
+    }
+}
+
+pub fn bishop(position: Position, occupied: Bitboard) -> Bitboard {
+    sliding(position, occupied, 2..4)
+}
+
+pub fn rook(position: Position, occupied: Bitboard) -> Bitboard {
+    sliding(position, occupied, 0..2)
+}
+
+pub fn queen(position: Position, occupied: Bitboard) -> Bitboard {
+    sliding(position, occupied, 0..4)
+}
+
+pub fn from(piece: Piece, position: Position, occupied: Bitboard) -> Bitboard {
+    match piece.piece_type() {
+        PieceType::Pawn => pawn(position, *piece.colour()),
+        PieceType::Knight => knight(position),
+        PieceType::Bishop => bishop(position, occupied),
+        PieceType::Rook => rook(position, occupied),
+        PieceType::Queen => queen(position, occupied),
+        PieceType::King => king(position),
+    }
+}
+
+fn sliding(position: Position, occupied: Bitboard, directions: std::ops::Range<usize>) -> Bitboard {
+    let square = position.index();
+    let occupied = occupied.bits();
+
+    directions
+        .map(|direction| {
+            let positive = POSITIVE_RAY_TABLE[direction][square];
+            let positive = match positive & occupied {
+                0 => positive,
+                blockers => {
+                    positive ^ POSITIVE_RAY_TABLE[direction][blockers.trailing_zeros() as usize]
+                }
+            };
+
+            let negative = NEGATIVE_RAY_TABLE[direction][square];
+            let negative = match negative & occupied {
+                0 => negative,
+                blockers => {
+                    negative ^ NEGATIVE_RAY_TABLE[direction][63 - blockers.leading_zeros() as usize]
+                }
+            };
+
+            Bitboard::new(positive | negative)
+        })
+        .fold(Bitboard::EMPTY, |attacks, ray| attacks | ray)
+}
+
+pub fn attackers(board: &Board, position: Position, colour: PieceColour) -> Bitboard {
+    let occupied = board.occupied();
+    let piece = |piece_type| board.bitboard(Piece::new(colour, piece_type));
+    let queens = piece(PieceType::Queen);
+
+    (pawn(position, colour.opposite()) & piece(PieceType::Pawn))
+        | (knight(position) & piece(PieceType::Knight))
+        | (king(position) & piece(PieceType::King))
+        | (bishop(position, occupied) & (piece(PieceType::Bishop) | queens))
+        | (rook(position, occupied) & (piece(PieceType::Rook) | queens))
+}
+
+pub fn is_attacked(board: &Board, position: Position, colour: PieceColour) -> bool {
+    !attackers(board, position, colour).is_empty()
+}
+
+pub fn checkers(board: &Board) -> Bitboard {
+    let colour = *board.active_colour();
+    board
+        .bitboard(Piece::new(colour, PieceType::King))
+        .positions()
+        .fold(Bitboard::EMPTY, |checkers, position| {
+            checkers | attackers(board, position, colour.opposite())
+        })
+}
+
+pub fn pinned_pieces(board: &Board, colour: PieceColour) -> Bitboard {
+    let Some(king_position) = board.bitboard(Piece::new(colour, PieceType::King)).first() else {
+        return Bitboard::EMPTY;
+    };
+    let opposition = colour.opposite();
+    let piece = |piece_type| board.bitboard(Piece::new(opposition, piece_type));
+    let queens = piece(PieceType::Queen);
+
+    // Only opposition pieces block, so that the king can see through to a pinning piece
+    let opposition_occupied = board.occupied_by(opposition);
+    let pinners = (bishop(king_position, opposition_occupied)
+        & (piece(PieceType::Bishop) | queens))
+        | (rook(king_position, opposition_occupied) & (piece(PieceType::Rook) | queens));
+
+    pinners
+        .positions()
+        .map(|pinner| between(king_position, pinner) & board.occupied())
+        .filter(|blockers| blockers.count() == 1)
+        .fold(Bitboard::EMPTY, |pinned, blockers| pinned | blockers)
+        & board.occupied_by(colour)
+}
+
+pub fn between(from: Position, to: Position) -> Bitboard {
+    let (from_bit, to_bit) = (Bitboard::from_position(from), Bitboard::from_position(to));
+    if rook(from, to_bit).contains(to) {
+        rook(from, to_bit) & rook(to, from_bit)
+    } else if bishop(from, to_bit).contains(to) {
+        bishop(from, to_bit) & bishop(to, from_bit)
+    } else {
+        Bitboard::EMPTY
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ControlMap([[u32; 2]; 64]);
+
+impl ControlMap {
+    pub fn attackers(&self, position: Position, colour: PieceColour) -> u32 {
+        self.0[position.index()][colour.index()]
+    }
+
+    pub fn controller(&self, position: Position) -> Option<PieceColour> {
+        let white = self.attackers(position, PieceColour::White);
+        let black = self.attackers(position, PieceColour::Black);
+        match white.cmp(&black) {
+            std::cmp::Ordering::Greater => Some(PieceColour::White),
+            std::cmp::Ordering::Less => Some(PieceColour::Black),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+}
+
+pub fn control_map(board: &Board) -> ControlMap {
+    let mut control = [[0; 2]; 64];
+    for (index, counts) in control.iter_mut().enumerate() {
+        let position = Position::from_index(index);
+        for colour in [PieceColour::White, PieceColour::Black] {
+            counts[colour.index()] = attackers(board, position, colour).count();
+        }
+    }
+    ControlMap(control)
+}
+
+pub fn in_check(board: &Board, colour: PieceColour) -> bool {
+    board
+        .bitboard(Piece::new(colour, PieceType::King))
+        .positions()
+        .any(|position| is_attacked(board, position, colour.opposite()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod is_attacked_tests {
+        use super::*;
+
+        #[test]
+        fn returns_true_if_attacked_by_pawn() {
+            assert!(is_attacked(
+                &board(),
+                Position::new(3, 2),
+                PieceColour::White
+            ));
+            assert!(!is_attacked(
+                &board(),
+                Position::new(1, 2),
+                PieceColour::White
+            ));
+        }
+
+        #[test]
+        fn returns_true_if_attacked_by_knight() {
+            assert!(is_attacked(
+                &board(),
+                Position::new(5, 6),
+                PieceColour::Black
+            ))
+        }
+
+        #[test]
+        fn returns_true_if_attacked_along_open_line() {
+            assert!(is_attacked(
+                &board(),
+                Position::new(6, 6),
+                PieceColour::White
+            ))
+        }
+
+        #[test]
+        fn returns_false_if_line_is_blocked() {
+            assert!(!is_attacked(
+                &board(),
+                Position::new(4, 3),
+                PieceColour::White
+            ))
+        }
+    }
+
+    mod sliding_tests {
+        use super::*;
+
+        #[test]
+        fn stops_at_edge_of_board() {
+            let attacks = rook(Position::new(0, 0), Bitboard::EMPTY);
+            assert_eq!(attacks.count(), 14);
+            assert!(attacks.contains(Position::new(7, 0)));
+            assert!(attacks.contains(Position::new(0, 7)));
+        }
+
+        #[test]
+        fn includes_first_blocker_in_each_direction() {
+            let occupied = Bitboard::from_position(Position::new(2, 3))
+                | Bitboard::from_position(Position::new(0, 2))
+                | Bitboard::from_position(Position::new(5, 3));
+            let attacks: Vec<Position> = rook(Position::new(0, 3), occupied).positions().collect();
+            assert_eq!(
+                attacks,
+                vec![
+                    Position::new(0, 2),
+                    Position::new(0, 4),
+                    Position::new(0, 5),
+                    Position::new(0, 6),
+                    Position::new(0, 7),
+                    Position::new(1, 3),
+                    Position::new(2, 3),
+                ]
+            )
+        }
+
+        #[test]
+        fn finds_diagonals_in_both_directions() {
+            let occupied = Bitboard::from_position(Position::new(5, 5))
+                | Bitboard::from_position(Position::new(1, 1));
+            let attacks: Vec<Position> =
+                bishop(Position::new(3, 3), occupied).positions().collect();
+            assert_eq!(
+                attacks,
+                vec![
+                    Position::new(0, 6),
+                    Position::new(1, 1),
+                    Position::new(1, 5),
+                    Position::new(2, 2),
+                    Position::new(2, 4),
+                    Position::new(4, 2),
+                    Position::new(4, 4),
+                    Position::new(5, 1),
+                    Position::new(5, 5),
+                    Position::new(6, 0),
+                ]
+            )
+        }
+    }
+
+    mod leaper_tests {
+        use super::*;
+
+        #[test]
+        fn finds_knight_attacks_from_corner() {
+            let attacks: Vec<Position> = knight(Position::new(0, 0)).positions().collect();
+            assert_eq!(attacks, vec![Position::new(1, 2), Position::new(2, 1)])
+        }
+
+        #[test]
+        fn finds_pawn_attacks_by_colour() {
+            let white: Vec<Position> = pawn(Position::new(1, 0), PieceColour::White)
+                .positions()
+                .collect();
+            let black: Vec<Position> = pawn(Position::new(6, 4), PieceColour::Black)
+                .positions()
+                .collect();
+            assert_eq!(white, vec![Position::new(2, 1)]);
+            assert_eq!(black, vec![Position::new(5, 3), Position::new(5, 5)]);
+        }
+    }
+
+    mod in_check_tests {
+        use super::*;
+
+        #[test]
+        fn returns_true_if_king_is_attacked() {
+            let mut builder = Board::builder();
+            builder
+                .piece(
+                    Piece::new(PieceColour::Black, PieceType::King),
+                    Position::new(7, 4),
+                )
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::Queen),
+                    Position::new(4, 1),
+                );
+            assert!(in_check(&builder.build(), PieceColour::Black))
+        }
+
+        #[test]
+        fn returns_false_if_king_is_not_attacked() {
+            assert!(!in_check(&board(), PieceColour::Black))
+        }
+    }
+
+    mod checkers_tests {
+        use super::*;
+
+        #[test]
+        fn finds_every_checking_piece() {
+            let mut builder = Board::builder();
+            builder
+                .active_colour(PieceColour::Black)
+                .piece(
+                    Piece::new(PieceColour::Black, PieceType::King),
+                    Position::new(7, 4),
+                )
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::Rook),
+                    Position::new(0, 4),
+                )
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::Knight),
+                    Position::new(5, 3),
+                )
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::Bishop),
+                    Position::new(4, 0),
+                );
+            assert_eq!(
+                checkers(&builder.build())
+                    .positions()
+                    .collect::<Vec<Position>>(),
+                vec![Position::new(0, 4), Position::new(5, 3)]
+            )
+        }
+
+        #[test]
+        fn returns_empty_if_not_in_check() {
+            assert!(checkers(&board()).is_empty())
+        }
+    }
+
+    mod pinned_pieces_tests {
+        use super::*;
+
+        #[test]
+        fn finds_pieces_pinned_to_king() {
+            let board = pinned_board(vec![(PieceType::Knight, Position::new(5, 4))]);
+            assert_eq!(
+                pinned_pieces(&board, PieceColour::Black)
+                    .positions()
+                    .collect::<Vec<Position>>(),
+                vec![Position::new(5, 4), Position::new(6, 3)]
+            )
+        }
+
+        #[test]
+        fn ignores_piece_shielded_by_another() {
+            let board = pinned_board(vec![
+                (PieceType::Knight, Position::new(5, 4)),
+                (PieceType::Pawn, Position::new(6, 4)),
+            ]);
+            assert_eq!(
+                pinned_pieces(&board, PieceColour::Black)
+                    .positions()
+                    .collect::<Vec<Position>>(),
+                vec![Position::new(6, 3)]
+            )
+        }
+
+        fn pinned_board(black_pieces: Vec<(PieceType, Position)>) -> Board {
+            let mut builder = Board::builder();
+            builder
+                .piece(
+                    Piece::new(PieceColour::Black, PieceType::King),
+                    Position::new(7, 4),
+                )
+                .piece(
+                    Piece::new(PieceColour::Black, PieceType::Bishop),
+                    Position::new(6, 3),
+                )
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::Rook),
+                    Position::new(0, 4),
+                )
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::Queen),
+                    Position::new(3, 0),
+                );
+            for (piece_type, position) in black_pieces {
+                builder.piece(Piece::new(PieceColour::Black, piece_type), position);
+            }
+            builder.build()
+        }
+    }
+
+    mod between_tests {
+        use super::*;
+
+        #[test]
+        fn finds_squares_on_shared_line() {
+            assert_eq!(
+                between(Position::new(0, 0), Position::new(3, 3))
+                    .positions()
+                    .collect::<Vec<Position>>(),
+                vec![Position::new(1, 1), Position::new(2, 2)]
+            )
+        }
+
+        #[test]
+        fn returns_empty_if_not_aligned() {
+            assert!(between(Position::new(0, 0), Position::new(2, 1)).is_empty())
+        }
+    }
+
+    mod control_map_tests {
+        use super::*;
+
+        #[test]
+        fn counts_attackers_of_each_colour() {
+            let control = control_map(&board());
+            assert_eq!(
+                control.attackers(Position::new(3, 2), PieceColour::White),
+                1
+            );
+            assert_eq!(
+                control.attackers(Position::new(0, 0), PieceColour::White),
+                2
+            );
+            assert_eq!(
+                control.attackers(Position::new(5, 6), PieceColour::Black),
+                1
+            );
+            assert_eq!(
+                control.controller(Position::new(5, 6)),
+                Some(PieceColour::Black)
+            );
+            assert_eq!(control.controller(Position::new(4, 5)), None);
+        }
+    }
+
+    fn board() -> Board {
+        let mut builder = Board::builder();
+        builder
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Pawn),
+                Position::new(2, 3),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Rook),
+                Position::new(0, 3),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Bishop),
+                Position::new(3, 3),
+            )
+            .piece(
+                Piece::new(PieceColour::Black, PieceType::Knight),
+                Position::new(7, 7),
+            )
+            .piece(
+                Piece::new(PieceColour::Black, PieceType::King),
+                Position::new(7, 0),
+            );
+        builder.build()
+    }
+}
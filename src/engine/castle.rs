@@ -1,10 +1,11 @@
 use std::sync::LazyLock;
 
 use crate::model::{
-    AvailableCastle, Board, Piece, PieceColour, PieceType, Position, MAX_POSITION, MIN_POSITION,
+    AvailableCastle, Board, Move, Piece, PieceColour, PieceType, Position, MAX_POSITION,
+    MIN_POSITION,
 };
 
-use super::{active_colour, clocks, error::EngineError};
+use super::{attacks, error::Rejection};
 
 // White King castling postitions
 static WHITE_KING_POSITION: LazyLock<Position> = LazyLock::new(|| Position::new(MIN_POSITION, 4));
@@ -19,16 +20,10 @@ static WHITE_QUEENSIDE_CASTLE_KING_POSITION: LazyLock<Position> =
 pub static WHITE_KINGS_ROOK_POSITION: LazyLock<Position> =
     LazyLock::new(|| Position::new(MIN_POSITION, MAX_POSITION));
 
-static WHITE_KINGSIDE_CASTLE_ROOK_POSITION: LazyLock<Position> =
-    LazyLock::new(|| Position::new(MIN_POSITION, MAX_POSITION - 2));
-
 // White queenside rook castling positions
 pub static WHITE_QUEENS_ROOK_POSITION: LazyLock<Position> =
     LazyLock::new(|| Position::new(MIN_POSITION, MIN_POSITION));
 
-static WHITE_QUEENSIDE_CASTLE_ROOK_POSITION: LazyLock<Position> =
-    LazyLock::new(|| Position::new(MIN_POSITION, MIN_POSITION + 3));
-
 // Black King castling positions
 static BLACK_KING_POSITION: LazyLock<Position> = LazyLock::new(|| Position::new(MAX_POSITION, 4));
 
@@ -42,112 +37,93 @@ static BLACK_QUEENSIDE_CASTLE_KING_POSITION: LazyLock<Position> =
 pub static BLACK_KINGS_ROOK_POSITION: LazyLock<Position> =
     LazyLock::new(|| Position::new(MAX_POSITION, MAX_POSITION));
 
-static BLACK_KINGSIDE_CASTLE_ROOK_POSITION: LazyLock<Position> =
-    LazyLock::new(|| Position::new(MAX_POSITION, MAX_POSITION - 2));
-
 // Black queenside rook castling positions
 pub static BLACK_QUEENS_ROOK_POSITION: LazyLock<Position> =
     LazyLock::new(|| Position::new(MAX_POSITION, MIN_POSITION));
 
-static BLACK_QUEENSIDE_CASTLE_ROOK_POSITION: LazyLock<Position> =
-    LazyLock::new(|| Position::new(MAX_POSITION, MIN_POSITION + 3));
-
-pub fn kingside(board: &Board, colour: PieceColour) -> Result<Board, EngineError> {
-    let castle_type = match colour {
-        PieceColour::White => AvailableCastle::WhiteKingside,
-        PieceColour::Black => AvailableCastle::BlackKingside,
+pub fn moves(board: &Board, colour: PieceColour) -> Vec<Move> {
+    let castles = match colour {
+        PieceColour::White => [
+            AvailableCastle::WhiteKingside,
+            AvailableCastle::WhiteQueenside,
+        ],
+        PieceColour::Black => [
+            AvailableCastle::BlackKingside,
+            AvailableCastle::BlackQueenside,
+        ],
     };
 
-    if !board.available_castles().contains(&castle_type) {
-        return Err(EngineError::new(format!(
-            "Kingside castle for {colour:?} is not a legal move"
-        )));
-    }
-
-    let (king_position_before, rook_position_before, king_position_after, rook_position_after) =
-        match colour {
-            PieceColour::White => (
-                *WHITE_KING_POSITION,
-                *WHITE_KINGS_ROOK_POSITION,
-                *WHITE_KINGSIDE_CASTLE_KING_POSITION,
-                *WHITE_KINGSIDE_CASTLE_ROOK_POSITION,
-            ),
-            PieceColour::Black => (
-                *BLACK_KING_POSITION,
-                *BLACK_KINGS_ROOK_POSITION,
-                *BLACK_KINGSIDE_CASTLE_KING_POSITION,
-                *BLACK_KINGSIDE_CASTLE_ROOK_POSITION,
-            ),
-        };
-
-    let mut next_board = board.clone();
-    next_board.remove(king_position_before);
-    next_board.remove(rook_position_before);
-    next_board.add(Piece::new(colour, PieceType::King), king_position_after);
-    next_board.add(Piece::new(colour, PieceType::Rook), rook_position_after);
-
-    active_colour::update(&mut next_board);
-    remove_castling_for_colour(&mut next_board, colour);
-    clocks::halfmove(&mut next_board, PieceType::King, false);
-    clocks::fullmove(&mut next_board, colour);
-
-    Ok(next_board)
+    castles
+        .into_iter()
+        .filter(|&castle| rejection(board, castle).is_none())
+        .map(|castle| {
+            let (_, king_position, _, king_position_after) = squares(castle);
+            Move::new(
+                Piece::new(colour, PieceType::King),
+                king_position,
+                king_position_after,
+                None,
+            )
+        })
+        .collect()
 }
 
-pub fn queenside(board: &Board, colour: PieceColour) -> Result<Board, EngineError> {
-    let castle_type = match colour {
-        PieceColour::White => AvailableCastle::WhiteQueenside,
-        PieceColour::Black => AvailableCastle::BlackQueenside,
-    };
-
-    if !board.available_castles().contains(&castle_type) {
-        return Err(EngineError::new(format!(
-            "Queenside castle for {colour:?} is not a legal move"
-        )));
+// Why a castle cannot be made, or None if it can
+pub fn rejection(board: &Board, castle: AvailableCastle) -> Option<Rejection> {
+    let (colour, king_position, rook_position, king_position_after) = squares(castle);
+    let row = king_position.row();
+    let between = |a: i8, b: i8| (a.min(b) + 1)..a.max(b);
+
+    if !board.available_castles().contains(&castle)
+        || board.occupant(king_position) != Some(&Piece::new(colour, PieceType::King))
+        || board.occupant(rook_position) != Some(&Piece::new(colour, PieceType::Rook))
+    {
+        return Some(Rejection::CastlingUnavailable);
     }
 
-    let (king_position_before, rook_position_before, king_position_after, rook_position_after) =
-        match colour {
-            PieceColour::White => (
-                *WHITE_KING_POSITION,
-                *WHITE_QUEENS_ROOK_POSITION,
-                *WHITE_QUEENSIDE_CASTLE_KING_POSITION,
-                *WHITE_QUEENSIDE_CASTLE_ROOK_POSITION,
-            ),
-            PieceColour::Black => (
-                *BLACK_KING_POSITION,
-                *BLACK_QUEENS_ROOK_POSITION,
-                *BLACK_QUEENSIDE_CASTLE_KING_POSITION,
-                *BLACK_QUEENSIDE_CASTLE_ROOK_POSITION,
-            ),
-        };
-
-    let mut next_board = board.clone();
-    next_board.remove(king_position_before);
-    next_board.remove(rook_position_before);
-    next_board.add(Piece::new(colour, PieceType::King), king_position_after);
-    next_board.add(Piece::new(colour, PieceType::Rook), rook_position_after);
-
-    active_colour::update(&mut next_board);
-    remove_castling_for_colour(&mut next_board, colour);
-    clocks::halfmove(&mut next_board, PieceType::King, false);
-    clocks::fullmove(&mut next_board, colour);
+    let unoccupied = between(king_position.col(), rook_position.col())
+        .all(|col| board.occupant(Position::new(row, col)).is_none());
+    if !unoccupied {
+        return Some(Rejection::Blocked);
+    }
 
-    Ok(next_board)
+    // The king may not castle out of, through or into check
+    let attacked = between(king_position.col(), king_position_after.col())
+        .chain([king_position.col(), king_position_after.col()])
+        .any(|col| attacks::is_attacked(board, Position::new(row, col), colour.opposite()));
+    if attacked {
+        return Some(Rejection::LeavesKingInCheck);
+    }
+    None
 }
 
-fn remove_castling_for_colour(board: &mut Board, colour: PieceColour) -> &mut Board {
-    match colour {
-        PieceColour::White => {
-            board.remove_available_castle(AvailableCastle::WhiteKingside);
-            board.remove_available_castle(AvailableCastle::WhiteQueenside);
-            board
-        }
-        PieceColour::Black => {
-            board.remove_available_castle(AvailableCastle::BlackKingside);
-            board.remove_available_castle(AvailableCastle::BlackQueenside);
-            board
-        }
+// The castling colour, king and rook home squares, and the king's destination
+fn squares(castle: AvailableCastle) -> (PieceColour, Position, Position, Position) {
+    match castle {
+        AvailableCastle::WhiteKingside => (
+            PieceColour::White,
+            *WHITE_KING_POSITION,
+            *WHITE_KINGS_ROOK_POSITION,
+            *WHITE_KINGSIDE_CASTLE_KING_POSITION,
+        ),
+        AvailableCastle::WhiteQueenside => (
+            PieceColour::White,
+            *WHITE_KING_POSITION,
+            *WHITE_QUEENS_ROOK_POSITION,
+            *WHITE_QUEENSIDE_CASTLE_KING_POSITION,
+        ),
+        AvailableCastle::BlackKingside => (
+            PieceColour::Black,
+            *BLACK_KING_POSITION,
+            *BLACK_KINGS_ROOK_POSITION,
+            *BLACK_KINGSIDE_CASTLE_KING_POSITION,
+        ),
+        AvailableCastle::BlackQueenside => (
+            PieceColour::Black,
+            *BLACK_KING_POSITION,
+            *BLACK_QUEENS_ROOK_POSITION,
+            *BLACK_QUEENSIDE_CASTLE_KING_POSITION,
+        ),
     }
 }
 
@@ -155,204 +131,145 @@ fn remove_castling_for_colour(board: &mut Board, colour: PieceColour) -> &mut Bo
 mod tests {
     use super::*;
 
-    mod kingside_tests {
+    mod rejection_tests {
         use super::*;
 
         #[test]
-        fn returns_err_if_castle_is_not_in_available_castles() {
-            let mut board_builder = Board::builder();
-            board_builder.available_castles(vec![
-                AvailableCastle::WhiteKingside,
-                AvailableCastle::BlackQueenside,
-            ]);
-            let board = board_builder.build();
-
-            assert!(kingside(&board, PieceColour::Black).is_err())
+        fn rejects_castle_which_is_not_in_available_castles() {
+            let board = board(vec![]);
+            assert_eq!(
+                rejection(&board, AvailableCastle::BlackKingside),
+                Some(Rejection::CastlingUnavailable)
+            )
         }
 
         #[test]
-        fn performs_kingside_castle_for_white() {
-            let mut board_builder = Board::builder();
-            board_builder
-                .available_castles(vec![
-                    AvailableCastle::WhiteKingside,
-                    AvailableCastle::WhiteQueenside,
-                    AvailableCastle::BlackKingside,
-                ])
-                .piece(
-                    Piece::new(PieceColour::White, PieceType::King),
-                    *WHITE_KING_POSITION,
-                )
-                .piece(
-                    Piece::new(PieceColour::White, PieceType::Rook),
-                    *WHITE_KINGS_ROOK_POSITION,
-                );
-
-            let board = board_builder.build();
-            let new_board = kingside(&board, PieceColour::White).unwrap();
-
-            assert!(new_board.occupant(*WHITE_KING_POSITION).is_none());
-            assert!(new_board.occupant(*WHITE_KINGS_ROOK_POSITION).is_none());
+        fn rejects_castle_without_rook_on_home_square() {
+            let mut board = board(vec![]);
+            board.remove(*WHITE_QUEENS_ROOK_POSITION);
             assert_eq!(
-                new_board.available_castles(),
-                vec![AvailableCastle::BlackKingside]
-            );
-            assert_eq!(
-                new_board.occupant(*WHITE_KINGSIDE_CASTLE_KING_POSITION),
-                Some(&Piece::new(PieceColour::White, PieceType::King))
-            );
-            assert_eq!(
-                new_board.occupant(*WHITE_KINGSIDE_CASTLE_ROOK_POSITION),
-                Some(&Piece::new(PieceColour::White, PieceType::Rook))
-            );
+                rejection(&board, AvailableCastle::WhiteQueenside),
+                Some(Rejection::CastlingUnavailable)
+            )
         }
 
         #[test]
-        fn performs_kingside_castle_for_black() {
-            let mut board_builder = Board::builder();
-            board_builder
-                .available_castles(vec![
-                    AvailableCastle::BlackKingside,
-                    AvailableCastle::BlackQueenside,
-                    AvailableCastle::WhiteKingside,
-                ])
-                .piece(
-                    Piece::new(PieceColour::Black, PieceType::King),
-                    *BLACK_KING_POSITION,
-                )
-                .piece(
-                    Piece::new(PieceColour::Black, PieceType::Rook),
-                    *BLACK_KINGS_ROOK_POSITION,
-                );
-
-            let board = board_builder.build();
-            let new_board = kingside(&board, PieceColour::Black).unwrap();
-
-            assert!(new_board.occupant(*BLACK_KING_POSITION).is_none());
-            assert!(new_board.occupant(*BLACK_KINGS_ROOK_POSITION).is_none());
-            assert_eq!(
-                new_board.available_castles(),
-                vec![AvailableCastle::WhiteKingside]
-            );
+        fn rejects_castle_through_occupied_square() {
+            let board = board(vec![(
+                Piece::new(PieceColour::White, PieceType::Knight),
+                Position::new(MIN_POSITION, 6),
+            )]);
             assert_eq!(
-                new_board.occupant(*BLACK_KINGSIDE_CASTLE_KING_POSITION),
-                Some(&Piece::new(PieceColour::Black, PieceType::King))
-            );
+                rejection(&board, AvailableCastle::WhiteKingside),
+                Some(Rejection::Blocked)
+            )
+        }
+
+        #[test]
+        fn rejects_castle_through_attacked_square() {
+            let board = board(vec![(
+                Piece::new(PieceColour::Black, PieceType::Rook),
+                Position::new(4, 5),
+            )]);
             assert_eq!(
-                new_board.occupant(*BLACK_KINGSIDE_CASTLE_ROOK_POSITION),
-                Some(&Piece::new(PieceColour::Black, PieceType::Rook))
+                rejection(&board, AvailableCastle::WhiteKingside),
+                Some(Rejection::LeavesKingInCheck)
             );
+            assert_eq!(rejection(&board, AvailableCastle::WhiteQueenside), None)
         }
     }
 
-    mod queenside_tests {
+    mod moves_tests {
         use super::*;
 
         #[test]
-        fn returns_err_if_castle_is_not_in_available_castles() {
-            let mut board_builder = Board::builder();
-            board_builder.available_castles(vec![
-                AvailableCastle::WhiteQueenside,
-                AvailableCastle::BlackKingside,
-            ]);
-            let board = board_builder.build();
-
-            assert!(queenside(&board, PieceColour::Black).is_err())
+        fn finds_both_castles_if_available() {
+            let board = board(vec![]);
+            assert_eq!(
+                moves(&board, PieceColour::White),
+                vec![
+                    Move::new(
+                        Piece::new(PieceColour::White, PieceType::King),
+                        *WHITE_KING_POSITION,
+                        *WHITE_KINGSIDE_CASTLE_KING_POSITION,
+                        None
+                    ),
+                    Move::new(
+                        Piece::new(PieceColour::White, PieceType::King),
+                        *WHITE_KING_POSITION,
+                        *WHITE_QUEENSIDE_CASTLE_KING_POSITION,
+                        None
+                    ),
+                ]
+            )
         }
 
         #[test]
-        fn performs_queenside_castle_for_white() {
-            let mut board_builder = Board::builder();
-            board_builder
-                .available_castles(vec![
-                    AvailableCastle::WhiteKingside,
-                    AvailableCastle::WhiteQueenside,
-                    AvailableCastle::BlackKingside,
-                ])
-                .piece(
+        fn excludes_castle_if_path_is_blocked() {
+            let board = board(vec![(
+                Piece::new(PieceColour::Black, PieceType::Knight),
+                Position::new(MIN_POSITION, 1),
+            )]);
+            assert_eq!(
+                moves(&board, PieceColour::White),
+                vec![Move::new(
                     Piece::new(PieceColour::White, PieceType::King),
                     *WHITE_KING_POSITION,
-                )
-                .piece(
-                    Piece::new(PieceColour::White, PieceType::Rook),
-                    *WHITE_QUEENS_ROOK_POSITION,
-                );
-
-            let board = board_builder.build();
-            let new_board = queenside(&board, PieceColour::White).unwrap();
+                    *WHITE_KINGSIDE_CASTLE_KING_POSITION,
+                    None
+                )]
+            )
+        }
 
-            assert!(new_board.occupant(*WHITE_KING_POSITION).is_none());
-            assert!(new_board.occupant(*WHITE_KINGS_ROOK_POSITION).is_none());
-            assert_eq!(
-                new_board.available_castles(),
-                vec![AvailableCastle::BlackKingside]
-            );
-            assert_eq!(
-                new_board.occupant(*WHITE_QUEENSIDE_CASTLE_KING_POSITION),
-                Some(&Piece::new(PieceColour::White, PieceType::King))
-            );
+        #[test]
+        fn excludes_castle_if_king_passes_through_attacked_square() {
+            let board = board(vec![(
+                Piece::new(PieceColour::Black, PieceType::Rook),
+                Position::new(4, 5),
+            )]);
             assert_eq!(
-                new_board.occupant(*WHITE_QUEENSIDE_CASTLE_ROOK_POSITION),
-                Some(&Piece::new(PieceColour::White, PieceType::Rook))
-            );
+                moves(&board, PieceColour::White),
+                vec![Move::new(
+                    Piece::new(PieceColour::White, PieceType::King),
+                    *WHITE_KING_POSITION,
+                    *WHITE_QUEENSIDE_CASTLE_KING_POSITION,
+                    None
+                )]
+            )
         }
 
         #[test]
-        fn performs_kingside_castle_for_black() {
-            let mut board_builder = Board::builder();
-            board_builder
-                .available_castles(vec![
-                    AvailableCastle::BlackKingside,
-                    AvailableCastle::BlackQueenside,
-                    AvailableCastle::WhiteKingside,
-                ])
-                .piece(
-                    Piece::new(PieceColour::Black, PieceType::King),
-                    *BLACK_KING_POSITION,
-                )
-                .piece(
-                    Piece::new(PieceColour::Black, PieceType::Rook),
-                    *BLACK_QUEENS_ROOK_POSITION,
-                );
-
-            let board = board_builder.build();
-            let new_board = queenside(&board, PieceColour::Black).unwrap();
-
-            assert!(new_board.occupant(*BLACK_KING_POSITION).is_none());
-            assert!(new_board.occupant(*BLACK_KINGS_ROOK_POSITION).is_none());
-            assert_eq!(
-                new_board.available_castles(),
-                vec![AvailableCastle::WhiteKingside]
-            );
-            assert_eq!(
-                new_board.occupant(*BLACK_QUEENSIDE_CASTLE_KING_POSITION),
-                Some(&Piece::new(PieceColour::Black, PieceType::King))
-            );
-            assert_eq!(
-                new_board.occupant(*BLACK_QUEENSIDE_CASTLE_ROOK_POSITION),
-                Some(&Piece::new(PieceColour::Black, PieceType::Rook))
-            );
+        fn excludes_castles_if_king_in_check() {
+            let board = board(vec![(
+                Piece::new(PieceColour::Black, PieceType::Rook),
+                Position::new(4, 4),
+            )]);
+            assert!(moves(&board, PieceColour::White).is_empty())
         }
     }
 
-    mod remove_castling_for_colour_tests {
-        use super::*;
-
-        #[test]
-        fn removes_castling_for_colour() {
-            let mut board_builder = Board::builder();
-            board_builder.available_castles(vec![
+    fn board(others: Vec<(Piece, Position)>) -> Board {
+        let mut board_builder = Board::builder();
+        board_builder
+            .available_castles(vec![
                 AvailableCastle::WhiteKingside,
                 AvailableCastle::WhiteQueenside,
-                AvailableCastle::BlackKingside,
-            ]);
-            let mut board = board_builder.build();
-
-            remove_castling_for_colour(&mut board, PieceColour::White);
-            assert_eq!(
-                board.available_castles(),
-                vec![AvailableCastle::BlackKingside]
+            ])
+            .piece(
+                Piece::new(PieceColour::White, PieceType::King),
+                *WHITE_KING_POSITION,
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Rook),
+                *WHITE_KINGS_ROOK_POSITION,
             )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Rook),
+                *WHITE_QUEENS_ROOK_POSITION,
+            );
+        for (piece, position) in others {
+            board_builder.piece(piece, position);
         }
+        board_builder.build()
     }
 }
@@ -29,8 +29,8 @@ pub fn current(
 ) -> &mut Board {
     if position == en_passant_square && piece.piece_type() == &PieceType::Pawn {
         match *piece.colour() {
-            PieceColour::White => board.remove(Position::new(position.row() - 1, position.col())),
-            PieceColour::Black => board.remove(Position::new(position.row() + 1, position.col())),
+            PieceColour::White => board.capture(Position::new(position.row() - 1, position.col())),
+            PieceColour::Black => board.capture(Position::new(position.row() + 1, position.col())),
         }
     }
 
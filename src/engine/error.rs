@@ -1,11 +1,126 @@
 use std::{error::Error, fmt::Display};
 
-#[derive(Debug)]
-pub struct EngineError(String);
+use crate::model::{PieceColour, Position};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Rejection {
+    WrongGeometry,
+    Blocked,
+    Pinned,
+    LeavesKingInCheck,
+    QualifierMismatch,
+    CastlingUnavailable,
+    NotInPocket,
+    Occupied,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Candidate {
+    position: Position,
+    rejection: Option<Rejection>,
+}
+
+impl Candidate {
+    pub fn new(position: Position, rejection: Option<Rejection>) -> Self {
+        Candidate {
+            position,
+            rejection,
+        }
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    pub fn rejection(&self) -> Option<Rejection> {
+        self.rejection
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MoveContext {
+    game_number: Option<usize>,
+    ply_number: Option<usize>,
+    san: String,
+    fen: String,
+}
+
+impl MoveContext {
+    pub fn new(san: impl Into<String>, fen: impl Into<String>) -> Self {
+        MoveContext {
+            game_number: None,
+            ply_number: None,
+            san: san.into(),
+            fen: fen.into(),
+        }
+    }
+
+    pub fn game_number(&self) -> Option<usize> {
+        self.game_number
+    }
+
+    pub fn ply_number(&self) -> Option<usize> {
+        self.ply_number
+    }
+
+    pub fn san(&self) -> &str {
+        &self.san
+    }
+
+    pub fn fen(&self) -> &str {
+        &self.fen
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum EngineError {
+    IllegalMove {
+        context: MoveContext,
+        candidates: Vec<Candidate>,
+    },
+    AmbiguousMove {
+        context: MoveContext,
+        candidates: Vec<Candidate>,
+    },
+    MissingKing {
+        context: MoveContext,
+        colour: PieceColour,
+    },
+}
 
 impl EngineError {
-    pub fn new(message: impl Into<String>) -> Self {
-        EngineError(message.into())
+    pub fn context(&self) -> &MoveContext {
+        match self {
+            EngineError::IllegalMove { context, .. }
+            | EngineError::AmbiguousMove { context, .. }
+            | EngineError::MissingKing { context, .. } => context,
+        }
+    }
+
+    pub fn candidates(&self) -> &[Candidate] {
+        match self {
+            EngineError::IllegalMove { candidates, .. }
+            | EngineError::AmbiguousMove { candidates, .. } => candidates,
+            EngineError::MissingKing { .. } => &[],
+        }
+    }
+
+    pub fn in_game(mut self, game_number: usize) -> Self {
+        self.context_mut().game_number = Some(game_number);
+        self
+    }
+
+    pub fn at_ply(mut self, ply_number: usize) -> Self {
+        self.context_mut().ply_number = Some(ply_number);
+        self
+    }
+
+    fn context_mut(&mut self) -> &mut MoveContext {
+        match self {
+            EngineError::IllegalMove { context, .. }
+            | EngineError::AmbiguousMove { context, .. }
+            | EngineError::MissingKing { context, .. } => context,
+        }
     }
 }
 
@@ -13,6 +128,82 @@ impl Error for EngineError {}
 
 impl Display for EngineError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        let context = self.context();
+        let location = match (context.game_number, context.ply_number) {
+            (Some(game), Some(ply)) => format!("Game {game}, ply {ply}: "),
+            (Some(game), None) => format!("Game {game}: "),
+            (None, Some(ply)) => format!("Ply {ply}: "),
+            (None, None) => String::new(),
+        };
+        let problem = match self {
+            EngineError::IllegalMove { .. } => "illegal move".to_string(),
+            EngineError::AmbiguousMove { .. } => "ambiguous move".to_string(),
+            EngineError::MissingKing { colour, .. } => format!("no {colour:?} king for move"),
+        };
+        let candidates = match self.candidates() {
+            [] => "none".to_string(),
+            candidates => candidates
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+                .join(", "),
+        };
+
+        write!(
+            f,
+            "{location}{problem} '{}' in position '{}'; candidates: {candidates}",
+            context.san, context.fen
+        )
+    }
+}
+
+impl Display for Candidate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.rejection {
+            None => write!(f, "{}", self.position),
+            Some(rejection) => write!(f, "{} ({rejection})", self.position),
+        }
+    }
+}
+
+impl Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Rejection::WrongGeometry => "wrong geometry",
+                Rejection::Blocked => "blocked",
+                Rejection::Pinned => "pinned",
+                Rejection::LeavesKingInCheck => "leaves king in check",
+                Rejection::QualifierMismatch => "does not match qualifier",
+                Rejection::CastlingUnavailable => "castling unavailable",
+                Rejection::NotInPocket => "not in pocket",
+                Rejection::Occupied => "square occupied",
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_failing_move() {
+        let error = EngineError::IllegalMove {
+            context: MoveContext::new("Nf3", "8/8/8/8/8/8/8/4K1N1 b - - 0 1"),
+            candidates: vec![
+                Candidate::new(Position::new(0, 6), Some(Rejection::Pinned)),
+                Candidate::new(Position::new(4, 4), Some(Rejection::WrongGeometry)),
+            ],
+        }
+        .at_ply(12)
+        .in_game(3);
+
+        assert_eq!(
+            error.to_string(),
+            "Game 3, ply 12: illegal move 'Nf3' in position '8/8/8/8/8/8/8/4K1N1 b - - 0 1'; candidates: g1 (pinned), e5 (wrong geometry)"
+        )
     }
 }
@@ -0,0 +1,178 @@
+use crate::model::{Board, Piece, PieceColour, PieceType};
+
+// Piece-square tables are written from white's perspective with the eighth rank first, as they
+// would appear on a diagram
+#[rustfmt::skip]
+const PAWN_TABLE: [i32; 64] = [
+     0,   0,   0,   0,   0,   0,   0,   0,
+    50,  50,  50,  50,  50,  50,  50,  50,
+    10,  10,  20,  30,  30,  20,  10,  10,
+     5,   5,  10,  25,  25,  10,   5,   5,
+     0,   0,   0,  20,  20,   0,   0,   0,
+     5,  -5, -10,   0,   0, -10,  -5,   5,
+     5,  10,  10, -20, -20,  10,  10,   5,
+     0,   0,   0,   0,   0,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const KNIGHT_TABLE: [i32; 64] = [
+    -50, -40, -30, -30, -30, -30, -40, -50,
+    -40, -20,   0,   0,   0,   0, -20, -40,
+    -30,   0,  10,  15,  15,  10,   0, -30,
+    -30,   5,  15,  20,  20,  15,   5, -30,
+    -30,   0,  15,  20,  20,  15,   0, -30,
+    -30,   5,  10,  15,  15,  10,   5, -30,
+    -40, -20,   0,   5,   5,   0, -20, -40,
+    -50, -40, -30, -30, -30, -30, -40, -50,
+];
+
+#[rustfmt::skip]
+const BISHOP_TABLE: [i32; 64] = [
+    -20, -10, -10, -10, -10, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,  10,  10,   5,   0, -10,
+    -10,   5,   5,  10,  10,   5,   5, -10,
+    -10,   0,  10,  10,  10,  10,   0, -10,
+    -10,  10,  10,  10,  10,  10,  10, -10,
+    -10,   5,   0,   0,   0,   0,   5, -10,
+    -20, -10, -10, -10, -10, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const ROOK_TABLE: [i32; 64] = [
+     0,   0,   0,   0,   0,   0,   0,   0,
+     5,  10,  10,  10,  10,  10,  10,   5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+    -5,   0,   0,   0,   0,   0,   0,  -5,
+     0,   0,   0,   5,   5,   0,   0,   0,
+];
+
+#[rustfmt::skip]
+const QUEEN_TABLE: [i32; 64] = [
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+    -10,   0,   0,   0,   0,   0,   0, -10,
+    -10,   0,   5,   5,   5,   5,   0, -10,
+     -5,   0,   5,   5,   5,   5,   0,  -5,
+      0,   0,   5,   5,   5,   5,   0,  -5,
+    -10,   5,   5,   5,   5,   5,   0, -10,
+    -10,   0,   5,   0,   0,   0,   0, -10,
+    -20, -10, -10,  -5,  -5, -10, -10, -20,
+];
+
+#[rustfmt::skip]
+const KING_TABLE: [i32; 64] = [
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -30, -40, -40, -50, -50, -40, -40, -30,
+    -20, -30, -30, -40, -40, -30, -30, -20,
+    -10, -20, -20, -20, -20, -20, -20, -10,
+     20,  20,   0,   0,   0,   0,  20,  20,
+     20,  30,  10,   0,   0,  10,  30,  20,
+];
+
+const PIECE_TYPES: [PieceType; 6] = [
+    PieceType::Pawn,
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::Queen,
+    PieceType::King,
+];
+
+pub fn value(piece_type: PieceType) -> i32 {
+    match piece_type {
+        PieceType::Pawn => 100,
+        PieceType::Knight => 320,
+        PieceType::Bishop => 330,
+        PieceType::Rook => 500,
+        PieceType::Queen => 900,
+        PieceType::King => 0,
+    }
+}
+
+// Centipawns from the point of view of the side to move
+pub fn evaluate(board: &Board) -> i32 {
+    let white = material(board, PieceColour::White);
+    let black = material(board, PieceColour::Black);
+
+    match board.active_colour() {
+        PieceColour::White => white - black,
+        PieceColour::Black => black - white,
+    }
+}
+
+fn material(board: &Board, colour: PieceColour) -> i32 {
+    PIECE_TYPES
+        .iter()
+        .map(|&piece_type| {
+            board
+                .bitboard(Piece::new(colour, piece_type))
+                .positions()
+                .map(|position| {
+                    let row = match colour {
+                        PieceColour::White => 7 - position.row(),
+                        PieceColour::Black => position.row(),
+                    };
+                    let square = row as usize * 8 + position.col() as usize;
+                    value(piece_type) + table(piece_type)[square]
+                })
+                .sum::<i32>()
+        })
+        .sum()
+}
+
+fn table(piece_type: PieceType) -> &'static [i32; 64] {
+    match piece_type {
+        PieceType::Pawn => &PAWN_TABLE,
+        PieceType::Knight => &KNIGHT_TABLE,
+        PieceType::Bishop => &BISHOP_TABLE,
+        PieceType::Rook => &ROOK_TABLE,
+        PieceType::Queen => &QUEEN_TABLE,
+        PieceType::King => &KING_TABLE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Position;
+
+    #[test]
+    fn scores_symmetrical_position_as_equal() {
+        let mut builder = Board::builder();
+        builder
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Knight),
+                Position::new(0, 1),
+            )
+            .piece(
+                Piece::new(PieceColour::Black, PieceType::Knight),
+                Position::new(7, 1),
+            );
+        assert_eq!(evaluate(&builder.build()), 0)
+    }
+
+    #[test]
+    fn scores_from_side_to_move() {
+        let mut builder = Board::builder();
+        builder.active_colour(PieceColour::Black).piece(
+            Piece::new(PieceColour::White, PieceType::Rook),
+            Position::new(0, 0),
+        );
+        assert_eq!(evaluate(&builder.build()), -500)
+    }
+
+    #[test]
+    fn prefers_central_squares() {
+        let knight = |position| {
+            let mut builder = Board::builder();
+            builder.piece(Piece::new(PieceColour::White, PieceType::Knight), position);
+            evaluate(&builder.build())
+        };
+        assert!(knight(Position::new(3, 3)) > knight(Position::new(0, 0)))
+    }
+}
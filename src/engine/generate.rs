@@ -0,0 +1,158 @@
+use crate::model::{Board, Move, PieceType, Variant, MAX_POSITION, MIN_POSITION};
+
+use super::{apply, castle, moves, pocket, variant};
+
+const PROMOTIONS: &[PieceType] = &[
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+];
+
+const ANTICHESS_PROMOTIONS: &[PieceType] = &[
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+    PieceType::King,
+];
+
+pub fn legal_moves(board: &Board) -> Vec<Move> {
+    let colour = *board.active_colour();
+    if variant::winner(board).is_some() {
+        return Vec::new();
+    }
+    // Antichess kings are ordinary pieces, so pawns may promote to them
+    let promotions = match board.variant() {
+        Variant::Antichess => ANTICHESS_PROMOTIONS,
+        _ => PROMOTIONS,
+    };
+
+    let mut candidates: Vec<Move> = Vec::new();
+    for from in board.occupied_by(colour).positions() {
+        let Some(&piece) = board.occupant(from) else {
+            continue;
+        };
+
+        for to in moves::targets(piece, from, board).positions() {
+            let promotes = *piece.piece_type() == PieceType::Pawn
+                && (to.row() == MIN_POSITION || to.row() == MAX_POSITION);
+            if promotes {
+                candidates.extend(
+                    promotions
+                        .iter()
+                        .map(|&promotes_to| Move::new(piece, from, to, Some(promotes_to))),
+                );
+            } else {
+                candidates.push(Move::new(piece, from, to, None));
+            }
+        }
+    }
+    candidates.append(&mut castle::moves(board, colour));
+    candidates.append(&mut pocket::moves(board, colour));
+
+    let legal = candidates
+        .into_iter()
+        .filter(|movement| variant::is_safe(&apply::apply(board, movement), colour))
+        .collect();
+    variant::restrict(board, legal)
+}
+
+pub fn has_legal_move(board: &Board) -> bool {
+    !legal_moves(board).is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{AvailableCastle, Piece, PieceColour, Position};
+
+    #[test]
+    fn finds_all_moves_from_starting_position() {
+        assert_eq!(legal_moves(&starting_board()).len(), 20)
+    }
+
+    #[test]
+    fn excludes_moves_which_leave_king_in_check() {
+        let mut builder = Board::builder();
+        builder
+            .piece(
+                Piece::new(PieceColour::White, PieceType::King),
+                Position::new(0, 4),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Bishop),
+                Position::new(1, 4),
+            )
+            .piece(
+                Piece::new(PieceColour::Black, PieceType::Rook),
+                Position::new(7, 4),
+            );
+        let moves = legal_moves(&builder.build());
+
+        assert!(moves
+            .iter()
+            .all(|movement| *movement.piece().piece_type() == PieceType::King));
+        assert_eq!(moves.len(), 4)
+    }
+
+    #[test]
+    fn generates_each_promotion() {
+        let mut builder = Board::builder();
+        builder
+            .piece(
+                Piece::new(PieceColour::White, PieceType::King),
+                Position::new(0, 0),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Pawn),
+                Position::new(6, 7),
+            );
+        let promotions = legal_moves(&builder.build())
+            .into_iter()
+            .filter(|movement| movement.promotes_to().is_some())
+            .count();
+
+        assert_eq!(promotions, 4)
+    }
+
+    fn starting_board() -> Board {
+        let back_row = [
+            PieceType::Rook,
+            PieceType::Knight,
+            PieceType::Bishop,
+            PieceType::Queen,
+            PieceType::King,
+            PieceType::Bishop,
+            PieceType::Knight,
+            PieceType::Rook,
+        ];
+        let mut builder = Board::builder();
+        builder.available_castles(vec![
+            AvailableCastle::WhiteKingside,
+            AvailableCastle::WhiteQueenside,
+            AvailableCastle::BlackKingside,
+            AvailableCastle::BlackQueenside,
+        ]);
+        for (col, piece_type) in (MIN_POSITION..=MAX_POSITION).zip(back_row) {
+            builder
+                .piece(
+                    Piece::new(PieceColour::White, piece_type),
+                    Position::new(0, col),
+                )
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::Pawn),
+                    Position::new(1, col),
+                )
+                .piece(
+                    Piece::new(PieceColour::Black, PieceType::Pawn),
+                    Position::new(6, col),
+                )
+                .piece(
+                    Piece::new(PieceColour::Black, piece_type),
+                    Position::new(7, col),
+                );
+        }
+        builder.build()
+    }
+}
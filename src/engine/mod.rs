@@ -1,188 +1,266 @@
 mod active_colour;
+mod apply;
+mod attacks;
 mod castle;
 mod clocks;
 mod en_passant;
 mod error;
-mod legality;
+mod evaluate;
+mod generate;
 mod moves;
+mod perft;
+mod pocket;
+mod san;
+mod search;
+mod tactics;
+mod uci;
+mod variant;
 
 use crate::model::{
-    AvailableCastle, Board, MoveQualifier, Movement, Piece, PieceColour, PieceType, Ply,
+    AvailableCastle, Board, Move, MoveQualifier, Movement, Piece, PieceColour, PieceType, Ply,
     PlyMovement, Position,
 };
 
-use self::{
-    castle::{
-        BLACK_KINGS_ROOK_POSITION, BLACK_QUEENS_ROOK_POSITION, WHITE_KINGS_ROOK_POSITION,
-        WHITE_QUEENS_ROOK_POSITION,
-    },
-    error::EngineError,
+pub use self::apply::{make_move, unmake_move, Undo};
+pub use self::attacks::{attackers, checkers, control_map, is_attacked, pinned_pieces, ControlMap};
+pub use self::error::{Candidate, EngineError, MoveContext, Rejection};
+pub use self::evaluate::{evaluate, value};
+pub use self::generate::legal_moves;
+pub use self::perft::{divide, perft};
+pub use self::san::san;
+pub use self::search::{search, Limit, Score, SearchResult};
+pub use self::tactics::{motifs, Motif, Tactic};
+pub use self::uci::{from_uci, san_line, uci};
+pub use self::variant::outcome;
+
+use self::castle::{
+    BLACK_KINGS_ROOK_POSITION, BLACK_QUEENS_ROOK_POSITION, WHITE_KINGS_ROOK_POSITION,
+    WHITE_QUEENS_ROOK_POSITION,
 };
 
 pub fn execute_moves(board: &Board, ply: &[Ply]) -> Result<Vec<Board>, EngineError> {
     let mut boards: Vec<Board> = vec![board.clone()];
 
-    let mut current_board = board;
-    for ply in ply {
-        let next_board = execute_move(current_board, ply.movement())?;
+    for (index, ply) in ply.iter().enumerate() {
+        let movement = resolve(&boards[index], ply.movement()).map_err(|e| e.at_ply(index + 1))?;
+        let mut next_board = boards[index].clone();
+        make_move(&mut next_board, &movement);
         boards.push(next_board);
-        current_board = boards
-            .last()
-            .ok_or_else(|| EngineError::new("Failed to retrieve previously generated board"))?;
     }
     Ok(boards)
 }
 
-fn execute_move(board: &Board, ply: &PlyMovement) -> Result<Board, EngineError> {
-    match ply {
-        PlyMovement::KingsideCastle { colour, check: _ } => castle::kingside(board, *colour),
-        PlyMovement::QueensideCastle { colour, check: _ } => castle::queenside(board, *colour),
+pub fn resolve(board: &Board, ply: &PlyMovement) -> Result<Move, EngineError> {
+    let candidates: Vec<Move> = legal_moves(board)
+        .into_iter()
+        .filter(|movement| resolves_to(ply, movement))
+        .collect();
+
+    match candidates[..] {
+        [movement] => Ok(movement),
+        [] => Err(illegal_move(board, ply)),
+        _ => Err(EngineError::AmbiguousMove {
+            context: MoveContext::new(ply.to_string(), board.fen()),
+            candidates: candidates
+                .iter()
+                .map(|movement| Candidate::new(movement.from(), None))
+                .collect(),
+        }),
+    }
+}
+
+// Explains why no legal move matches the ply. Variant rules are applied through move
+// generation, so their candidates carry no rejection reasons
+fn illegal_move(board: &Board, ply: &PlyMovement) -> EngineError {
+    let context = || MoveContext::new(ply.to_string(), board.fen());
+    if !variant::has_standard_moves(board.variant()) {
+        return EngineError::IllegalMove {
+            context: context(),
+            candidates: Vec::new(),
+        };
+    }
+    if let PlyMovement::Move { movement, .. } | PlyMovement::Promotion { movement, .. } = ply {
+        let colour = *movement.piece().colour();
+        if board.search(Piece::new(colour, PieceType::King)).is_empty() {
+            return EngineError::MissingKing {
+                context: context(),
+                colour,
+            };
+        }
+    }
+
+    let candidates = match ply {
+        PlyMovement::KingsideCastle { colour, check: _ } => {
+            let castle = match colour {
+                PieceColour::White => AvailableCastle::WhiteKingside,
+                PieceColour::Black => AvailableCastle::BlackKingside,
+            };
+            castle_candidates(board, *colour, castle)
+        }
+        PlyMovement::QueensideCastle { colour, check: _ } => {
+            let castle = match colour {
+                PieceColour::White => AvailableCastle::WhiteQueenside,
+                PieceColour::Black => AvailableCastle::BlackQueenside,
+            };
+            castle_candidates(board, *colour, castle)
+        }
         PlyMovement::Move {
             movement,
             qualifier,
             check: _,
-            capture,
-        } => piece_move(board, movement, qualifier.as_ref(), None, *capture),
+            capture: _,
+        } => piece_candidates(board, movement, qualifier.as_ref(), None),
         PlyMovement::Promotion {
             movement,
             promotes_to,
             qualifier,
             check: _,
-            capture,
-        } => piece_move(
-            board,
-            movement,
-            qualifier.as_ref(),
-            Some(promotes_to),
-            *capture,
-        ),
+            capture: _,
+        } => piece_candidates(board, movement, qualifier.as_ref(), Some(*promotes_to)),
+        PlyMovement::Drop { movement, check: _ } => {
+            pocket::rejection(board, movement.piece(), movement.position())
+                .map(|rejection| Candidate::new(movement.position(), Some(rejection)))
+                .into_iter()
+                .collect()
+        }
+    };
+
+    let viable = candidates
+        .iter()
+        .filter(|candidate| candidate.rejection().is_none())
+        .count();
+    if viable > 1 {
+        return EngineError::AmbiguousMove {
+            context: context(),
+            candidates,
+        };
+    }
+    // A lone unrejected candidate is illegal for another reason, such as moving out of turn
+    EngineError::IllegalMove {
+        context: context(),
+        candidates: candidates
+            .into_iter()
+            .filter(|candidate| candidate.rejection().is_some())
+            .collect(),
     }
 }
 
-// TODO: write tests
-fn piece_move(
+fn castle_candidates(
+    board: &Board,
+    colour: PieceColour,
+    castle: AvailableCastle,
+) -> Vec<Candidate> {
+    let Some(rejection) = castle::rejection(board, castle) else {
+        return Vec::new();
+    };
+    board
+        .search(Piece::new(colour, PieceType::King))
+        .into_iter()
+        .map(|position| Candidate::new(position, Some(rejection)))
+        .collect()
+}
+
+// Every piece of the moving type, with the reason each cannot make the move
+fn piece_candidates(
     board: &Board,
     movement: &Movement,
     qualifier: Option<&MoveQualifier>,
-    promotes_to: Option<&PieceType>,
-    capture: bool,
-) -> Result<Board, EngineError> {
+    promotes_to: Option<PieceType>,
+) -> Vec<Candidate> {
     let piece = movement.piece();
-    let position = movement.position();
-
-    let candidates = board.search(piece);
-    let king_position = *board
-        .search(Piece::new(*piece.colour(), PieceType::King))
-        .first()
-        .ok_or_else(|| {
-            EngineError::new(format!(
-                "Could not locate king of colour {:?}",
-                piece.colour()
-            ))
-        })?;
-    let opposition_colour = match *piece.colour() {
-        PieceColour::White => PieceColour::Black,
-        PieceColour::Black => PieceColour::White,
-    };
-
-    if candidates.is_empty() {
-        return Err(EngineError::new(format!(
-            "No candidates found for piece {piece:?}"
-        )));
-    }
+    let to = movement.position();
+    let colour = *piece.colour();
 
-    let viable_candidates: Vec<Position> = candidates
+    let rejection = |from: Position| {
+        if !moves::geometry(piece, from).contains(to) {
+            Some(Rejection::WrongGeometry)
+        } else if !moves::targets(piece, from, board).contains(to) {
+            Some(Rejection::Blocked)
+        } else if !variant::is_safe(
+            &apply::apply(board, &Move::new(piece, from, to, promotes_to)),
+            colour,
+        ) {
+            if attacks::in_check(board, colour) {
+                Some(Rejection::LeavesKingInCheck)
+            } else {
+                Some(Rejection::Pinned)
+            }
+        } else {
+            None
+        }
+    };
+    let mut candidates: Vec<Candidate> = board
+        .search(piece)
         .into_iter()
-        .filter(|&candidate_position| {
-            moves::find(piece, candidate_position, board).contains(&position)
-                && legality::check(
-                    piece,
-                    candidate_position,
-                    position,
-                    king_position,
-                    opposition_colour,
-                    board.clone(),
-                )
-        })
+        .map(|from| Candidate::new(from, rejection(from)))
         .collect();
 
-    let candidate = match &viable_candidates[..] {
-        [] => Err(EngineError::new(format!(
-            "No piece {piece:?} can move to position {position:?}"
-        ))),
-        [candidate] => Ok(*candidate),
-        candidates => {
-            if let Some(qualifier) = qualifier {
-                qualified_position(candidates, qualifier)
-            } else {
-                Err(EngineError::new(format!(
-                    "Cannot determine candidate {piece:?} moving to {position:?} given {} candidates: no qualifier provided",
-                    candidates.len()
-                )))
+    let is_viable = |candidate: &Candidate| candidate.rejection().is_none();
+    if let Some(qualifier) = qualifier {
+        if candidates.iter().filter(|c| is_viable(c)).count() > 1 {
+            for candidate in candidates.iter_mut() {
+                if is_viable(candidate) && !matches_qualifier(qualifier, candidate.position()) {
+                    *candidate =
+                        Candidate::new(candidate.position(), Some(Rejection::QualifierMismatch));
+                }
             }
         }
-    }?;
-
-    let mut next_board = board.clone();
-    next_board.remove(candidate);
-
-    if let Some(&en_passant_square) = board.en_passant_square() {
-        en_passant::current(piece, position, en_passant_square, &mut next_board);
     }
+    candidates
+}
 
-    match promotes_to {
-        None => next_board.add(piece, position),
-        Some(&other) => next_board.add(Piece::new(*piece.colour(), other), position),
+fn resolves_to(ply: &PlyMovement, movement: &Move) -> bool {
+    let piece_matches = |expected: &Movement, qualifier: Option<&MoveQualifier>| {
+        !movement.is_castle()
+            && !movement.is_drop()
+            && movement.piece() == expected.piece()
+            && movement.to() == expected.position()
+            && qualifier.is_none_or(|qualifier| matches_qualifier(qualifier, movement.from()))
     };
 
-    active_colour::update(&mut next_board);
-    update_available_castles(piece, candidate, &mut next_board);
-    en_passant::next(piece, candidate, position, &mut next_board);
-    clocks::halfmove(&mut next_board, *piece.piece_type(), capture);
-    clocks::fullmove(&mut next_board, *piece.colour());
-
-    Ok(next_board)
+    match ply {
+        PlyMovement::KingsideCastle { colour, check: _ } => {
+            movement.is_castle()
+                && movement.piece().colour() == colour
+                && movement.to().col() > movement.from().col()
+        }
+        PlyMovement::QueensideCastle { colour, check: _ } => {
+            movement.is_castle()
+                && movement.piece().colour() == colour
+                && movement.to().col() < movement.from().col()
+        }
+        PlyMovement::Move {
+            movement: expected,
+            qualifier,
+            check: _,
+            capture: _,
+        } => piece_matches(expected, qualifier.as_ref()) && movement.promotes_to().is_none(),
+        PlyMovement::Promotion {
+            movement: expected,
+            promotes_to,
+            qualifier,
+            check: _,
+            capture: _,
+        } => {
+            piece_matches(expected, qualifier.as_ref())
+                && movement.promotes_to() == Some(*promotes_to)
+        }
+        PlyMovement::Drop {
+            movement: expected,
+            check: _,
+        } => {
+            movement.is_drop()
+                && movement.piece() == expected.piece()
+                && movement.to() == expected.position()
+        }
+    }
 }
 
-// TODO: write tests
-fn qualified_position(
-    candidates: &[Position],
-    qualifier: &MoveQualifier,
-) -> Result<Position, EngineError> {
+fn matches_qualifier(qualifier: &MoveQualifier, position: Position) -> bool {
     match qualifier {
-        MoveQualifier::Position(position) => Ok(*position),
-        MoveQualifier::Col(col) => {
-            let filtered_candidates: Vec<Position> = candidates
-                .iter()
-                .filter_map(|&position| {
-                    if position.col() == *col {
-                        Some(position)
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-            match filtered_candidates[..] {
-                [only] => Ok(only),
-                _ => Err(EngineError::new(format!("Cannot uniquely determine piece position from qualifier {qualifier:?}; candidates: {candidates:?}")))
-            }
-        }
-        MoveQualifier::Row(row) => {
-            let filtered_candidates: Vec<Position> = candidates
-                .iter()
-                .filter_map(|&position| {
-                    if position.row() == *row {
-                        Some(position)
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-            match filtered_candidates[..] {
-                [only] => Ok(only),
-                _ => Err(EngineError::new(format!("Cannot uniquely determine piece position from qualifier {qualifier:?}; candidates: {candidates:?}")))
-            }
-        }
+        MoveQualifier::Col(col) => position.col() == *col,
+        MoveQualifier::Row(row) => position.row() == *row,
+        MoveQualifier::Position(qualified) => position == *qualified,
     }
 }
 
@@ -214,3 +292,267 @@ fn update_available_castles(piece: Piece, position: Position, board: &mut Board)
     };
     board
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{parse_fen, parse_movetext};
+
+    mod execute_moves_tests {
+        use super::*;
+
+        #[test]
+        fn reports_pinned_and_unreachable_candidates() {
+            let board = board(&[
+                (
+                    Piece::new(PieceColour::White, PieceType::Knight),
+                    Position::new(1, 4),
+                ),
+                (
+                    Piece::new(PieceColour::Black, PieceType::Rook),
+                    Position::new(7, 4),
+                ),
+            ]);
+            let error = execute_moves(&board, &[knight_to(Position::new(3, 5))]).unwrap_err();
+
+            assert_eq!(
+                error,
+                EngineError::IllegalMove {
+                    context: MoveContext::new("Nf4", board.fen()),
+                    candidates: vec![
+                        Candidate::new(Position::new(0, 1), Some(Rejection::WrongGeometry)),
+                        Candidate::new(Position::new(1, 4), Some(Rejection::Pinned)),
+                    ],
+                }
+                .at_ply(1)
+            )
+        }
+
+        #[test]
+        fn reports_candidate_which_leaves_king_in_check() {
+            let board = board(&[(
+                Piece::new(PieceColour::Black, PieceType::Rook),
+                Position::new(7, 4),
+            )]);
+            let error = execute_moves(&board, &[knight_to(Position::new(2, 2))]).unwrap_err();
+
+            assert_eq!(
+                error.candidates(),
+                &[Candidate::new(
+                    Position::new(0, 1),
+                    Some(Rejection::LeavesKingInCheck)
+                )]
+            )
+        }
+
+        #[test]
+        fn reports_blocked_candidate() {
+            let mut builder = Board::builder();
+            builder
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::King),
+                    Position::new(0, 4),
+                )
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::Rook),
+                    Position::new(0, 0),
+                )
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::Pawn),
+                    Position::new(1, 0),
+                );
+            let ply = Ply::new(
+                1,
+                PlyMovement::Move {
+                    movement: Movement::new(
+                        Piece::new(PieceColour::White, PieceType::Rook),
+                        Position::new(2, 0),
+                    ),
+                    qualifier: None,
+                    check: None,
+                    capture: false,
+                },
+                None,
+            );
+            let error = execute_moves(&builder.build(), &[ply]).unwrap_err();
+
+            assert_eq!(
+                error.candidates(),
+                &[Candidate::new(
+                    Position::new(0, 0),
+                    Some(Rejection::Blocked)
+                )]
+            )
+        }
+
+        #[test]
+        fn reports_ambiguous_candidates() {
+            let board = board(&[(
+                Piece::new(PieceColour::White, PieceType::Knight),
+                Position::new(0, 5),
+            )]);
+            let error = execute_moves(&board, &[knight_to(Position::new(1, 3))]).unwrap_err();
+
+            assert!(matches!(error, EngineError::AmbiguousMove { .. }));
+            assert_eq!(
+                error.candidates(),
+                &[
+                    Candidate::new(Position::new(0, 1), None),
+                    Candidate::new(Position::new(0, 5), None)
+                ]
+            )
+        }
+
+        #[test]
+        fn reports_unavailable_castle() {
+            let board = board(&[]);
+            let ply = Ply::new(
+                1,
+                PlyMovement::KingsideCastle {
+                    colour: PieceColour::White,
+                    check: None,
+                },
+                None,
+            );
+            let error = execute_moves(&board, &[ply]).unwrap_err();
+
+            assert_eq!(error.context().san(), "O-O");
+            assert_eq!(
+                error.candidates(),
+                &[Candidate::new(
+                    Position::new(0, 4),
+                    Some(Rejection::CastlingUnavailable)
+                )]
+            )
+        }
+
+        #[test]
+        fn pockets_captures_and_drops_them() {
+            let board = crazyhouse("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 1");
+            let boards = execute_moves(
+                &board,
+                &parse_movetext("1. e4 d5 2. exd5 Qxd5 3. Nc3 Qa5 4. P@d4").unwrap(),
+            )
+            .unwrap();
+
+            let after_exchange = &boards[4];
+            assert_eq!(pocket_count(after_exchange, PieceColour::White), 1);
+            assert_eq!(pocket_count(after_exchange, PieceColour::Black), 1);
+
+            let last = boards.last().unwrap();
+            assert_eq!(
+                last.occupant(Position::new(3, 3)),
+                Some(&Piece::new(PieceColour::White, PieceType::Pawn))
+            );
+            assert_eq!(pocket_count(last, PieceColour::White), 0);
+            assert_eq!(
+                last.fen(),
+                "rnb1kbnr/ppp1pppp/8/q7/3P4/2N5/PPPP1PPP/R1BQKBNR[p] b KQkq - 0 4"
+            )
+        }
+
+        #[test]
+        fn pockets_promoted_piece_as_pawn() {
+            let board = crazyhouse("3qk3/8/8/8/8/8/8/3Q~K3[] b - - 0 1");
+            let boards =
+                execute_moves(&board, &parse_movetext("1... Qxd1+ 2. Kxd1").unwrap()).unwrap();
+
+            let black = boards[1].pocket(PieceColour::Black).unwrap();
+            assert_eq!(black.count(PieceType::Pawn), 1);
+            assert_eq!(black.count(PieceType::Queen), 0);
+            assert_eq!(
+                boards[2]
+                    .pocket(PieceColour::White)
+                    .unwrap()
+                    .count(PieceType::Queen),
+                1
+            )
+        }
+
+        #[test]
+        fn reports_drop_not_in_pocket() {
+            let board = crazyhouse("4k3/8/8/8/8/8/8/4K3[n] w - - 0 1");
+            let error = execute_moves(&board, &parse_movetext("1. N@f3").unwrap()).unwrap_err();
+
+            assert_eq!(
+                error.candidates(),
+                &[Candidate::new(
+                    Position::new(2, 5),
+                    Some(Rejection::NotInPocket)
+                )]
+            )
+        }
+
+        #[test]
+        fn agrees_with_make_move_after_rook_capture_on_home_square() {
+            let board = parse_fen("rnbqkbnr/ppppppp1/8/8/8/8/PPPPPPP1/RNBQKBNR w KQkq - 0 1")
+                .unwrap()
+                .starting_board()
+                .clone();
+            let plies = parse_movetext("1. Rxh8").unwrap();
+            let boards = execute_moves(&board, &plies).unwrap();
+
+            let mut expected = board.clone();
+            make_move(
+                &mut expected,
+                &resolve(&board, plies[0].movement()).unwrap(),
+            );
+            assert_eq!(
+                boards[1].available_castles(),
+                vec![
+                    AvailableCastle::WhiteQueenside,
+                    AvailableCastle::BlackQueenside
+                ]
+            );
+            assert_eq!(boards[1], expected);
+            assert_eq!(boards[1].fen(), expected.fen())
+        }
+
+        fn crazyhouse(fen: &str) -> Board {
+            parse_fen(fen).unwrap().starting_board().clone()
+        }
+
+        fn pocket_count(board: &Board, colour: PieceColour) -> usize {
+            board
+                .pocket(colour)
+                .unwrap()
+                .pieces()
+                .iter()
+                .map(|&(_, count)| usize::from(count))
+                .sum()
+        }
+
+        fn knight_to(position: Position) -> Ply {
+            Ply::new(
+                1,
+                PlyMovement::Move {
+                    movement: Movement::new(
+                        Piece::new(PieceColour::White, PieceType::Knight),
+                        position,
+                    ),
+                    qualifier: None,
+                    check: None,
+                    capture: false,
+                },
+                None,
+            )
+        }
+
+        fn board(others: &[(Piece, Position)]) -> Board {
+            let mut builder = Board::builder();
+            builder
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::King),
+                    Position::new(0, 4),
+                )
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::Knight),
+                    Position::new(0, 1),
+                );
+            for &(piece, position) in others {
+                builder.piece(piece, position);
+            }
+            builder.build()
+        }
+    }
+}
@@ -1,225 +1,113 @@
-use std::sync::LazyLock;
-
-use crate::model::{Board, Piece, PieceColour, PieceType, Position, MAX_POSITION, MIN_POSITION};
-
-type Metric = (i8, i8);
-
-const WHITE_PAWN_METRIC: Metric = (1, 0);
-const WHITE_DOUBLE_PAWN_METRIC: Metric = (2, 0);
-const BLACK_PAWN_METRIC: Metric = (-1, 0);
-const BLACK_DOUBLE_PAWN_METRIC: Metric = (-2, 0);
-
-const WHITE_PAWN_CAPTURES: &[Metric] = &[(1, -1), (1, 1)];
-const BLACK_PAWN_CAPTURES: &[Metric] = &[(-1, -1), (-1, 1)];
-
-const DIAGONAL_METRICS: &[Metric] = &[(1, -1), (1, 1), (-1, 1), (-1, -1)];
-const LATERAL_METRICS: &[Metric] = &[(1, 0), (0, 1), (0, -1), (-1, 0)];
-const KNIGHT_METRICS: &[Metric] = &[
-    (1, -2),
-    (2, -1),
-    (2, 1),
-    (1, 2),
-    (-1, 2),
-    (-2, 1),
-    (-2, -1),
-    (-1, -2),
-];
-
-static ALL_METRICS: LazyLock<Vec<Metric>> =
-    LazyLock::new(|| [DIAGONAL_METRICS, LATERAL_METRICS].concat());
-
-#[derive(Debug, PartialEq, Eq)]
-enum MoveOutcome {
-    Empty(Position),
-    OccupiedOppositeColour(Position),
-    OccupiedSameColour,
-    Invalid,
-}
+use crate::model::{
+    Bitboard, Board, Piece, PieceColour, PieceType, Position, Variant, MAX_POSITION, MIN_POSITION,
+};
+
+use super::attacks;
 
-pub fn find(piece: Piece, position: Position, board: &Board) -> Vec<Position> {
+pub fn targets(piece: Piece, position: Position, board: &Board) -> Bitboard {
     let colour = *piece.colour();
     match piece.piece_type() {
         PieceType::Pawn => pawn_moves(position, colour, board),
-        PieceType::Knight => apply_metrics_once(position, colour, KNIGHT_METRICS, board),
-        PieceType::Bishop => apply_metrics(position, colour, DIAGONAL_METRICS, board),
-        PieceType::Rook => apply_metrics(position, colour, LATERAL_METRICS, board),
-        PieceType::Queen => apply_metrics(position, colour, &ALL_METRICS, board),
-        PieceType::King => apply_metrics_once(position, colour, &ALL_METRICS, board),
+        _ => attacks::from(piece, position, board.occupied()) & !board.occupied_by(colour),
     }
 }
 
-fn pawn_moves(position: Position, colour: PieceColour, board: &Board) -> Vec<Position> {
-    let mut positions: Vec<Position> = Vec::new();
-    let move_metric = if colour == PieceColour::White {
-        WHITE_PAWN_METRIC
-    } else {
-        BLACK_PAWN_METRIC
-    };
-
-    if let MoveOutcome::Empty(new_position) =
-        apply_metric_once(position, colour, move_metric, board)
-    {
-        positions.push(new_position);
-    }
-
-    let on_home_row = (colour == PieceColour::White && position.row() == MIN_POSITION + 1)
-        | (colour == PieceColour::Black && position.row() == MAX_POSITION - 1);
-
-    if on_home_row && !positions.is_empty() {
-        let double_move_metric = if colour == PieceColour::White {
-            WHITE_DOUBLE_PAWN_METRIC
-        } else {
-            BLACK_DOUBLE_PAWN_METRIC
-        };
-        if let MoveOutcome::Empty(new_position) =
-            apply_metric_once(position, colour, double_move_metric, board)
-        {
-            positions.push(new_position);
+pub fn geometry(piece: Piece, position: Position) -> Bitboard {
+    let colour = *piece.colour();
+    match piece.piece_type() {
+        PieceType::Pawn => {
+            pawn_moves(position, colour, &Board::builder().build())
+                | attacks::pawn(position, colour)
         }
+        _ => attacks::from(piece, position, Bitboard::EMPTY),
     }
-
-    let capture_metrics = if colour == PieceColour::White {
-        WHITE_PAWN_CAPTURES
-    } else {
-        BLACK_PAWN_CAPTURES
-    };
-    let mut capture_moves: Vec<Position> = capture_metrics
-        .iter()
-        .filter_map(
-            |&metric| match apply_metric_once(position, colour, metric, board) {
-                MoveOutcome::OccupiedOppositeColour(new_position) => Some(new_position),
-                MoveOutcome::Empty(new_position)
-                    if Some(&new_position) == board.en_passant_square() =>
-                {
-                    Some(new_position)
-                }
-                _ => None,
-            },
-        )
-        .collect();
-
-    positions.append(&mut capture_moves);
-
-    positions
 }
 
-fn apply_metrics(
-    position: Position,
-    colour: PieceColour,
-    metrics: &[Metric],
-    board: &Board,
-) -> Vec<Position> {
-    metrics
-        .iter()
-        .flat_map(|&metric| apply_metric(position, colour, metric, board))
-        .collect()
-}
+fn pawn_moves(position: Position, colour: PieceColour, board: &Board) -> Bitboard {
+    let (direction, home_row) = match colour {
+        PieceColour::White => (1, MIN_POSITION + 1),
+        PieceColour::Black => (-1, MAX_POSITION - 1),
+    };
+    let empty = |row: i8| {
+        Position::try_from(row, position.col())
+            .ok()
+            .filter(|&p| !board.occupied().contains(p))
+    };
 
-fn apply_metric(
-    mut position: Position,
-    colour: PieceColour,
-    metric: Metric,
-    board: &Board,
-) -> Vec<Position> {
-    let mut positions: Vec<Position> = Vec::new();
-    loop {
-        match apply_metric_once(position, colour, metric, board) {
-            MoveOutcome::Invalid | MoveOutcome::OccupiedSameColour => return positions,
-            MoveOutcome::OccupiedOppositeColour(new_position) => {
-                positions.push(new_position);
-                return positions;
-            }
-            MoveOutcome::Empty(new_position) => {
-                positions.push(new_position);
-                position = new_position;
+    let mut positions = Bitboard::EMPTY;
+    if let Some(single) = empty(position.row() + direction) {
+        positions |= Bitboard::from_position(single);
+        // Horde pawns on the first rank may also advance two squares
+        let first_row = home_row - direction;
+        if position.row() == home_row
+            || (board.variant() == Variant::Horde && position.row() == first_row)
+        {
+            if let Some(double) = empty(position.row() + 2 * direction) {
+                positions |= Bitboard::from_position(double);
             }
         }
     }
-}
 
-fn apply_metrics_once(
-    position: Position,
-    colour: PieceColour,
-    metrics: &[Metric],
-    board: &Board,
-) -> Vec<Position> {
-    metrics
-        .iter()
-        .filter_map(
-            |&metric| match apply_metric_once(position, colour, metric, board) {
-                MoveOutcome::Empty(position) | MoveOutcome::OccupiedOppositeColour(position) => {
-                    Some(position)
-                }
-                MoveOutcome::OccupiedSameColour | MoveOutcome::Invalid => None,
-            },
-        )
-        .collect()
-}
+    let mut capturable = board.occupied_by(colour.opposite());
+    if let Some(&en_passant_square) = board.en_passant_square() {
+        capturable |= Bitboard::from_position(en_passant_square);
+    }
 
-fn apply_metric_once(
-    position: Position,
-    colour: PieceColour,
-    metric: Metric,
-    board: &Board,
-) -> MoveOutcome {
-    let row = position.row() + metric.0;
-    let col = position.col() + metric.1;
-    let new_position = Position::try_from(row, col);
-
-    new_position.map_or_else(
-        |_| MoveOutcome::Invalid,
-        |p| match board.occupant(p) {
-            None => MoveOutcome::Empty(p),
-            Some(piece) if piece.colour() != &colour => MoveOutcome::OccupiedOppositeColour(p),
-            _ => MoveOutcome::OccupiedSameColour,
-        },
-    )
+    positions | (attacks::pawn(position, colour) & capturable)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn find(piece: Piece, position: Position, board: &Board) -> Vec<Position> {
+        targets(piece, position, board).positions().collect()
+    }
+
     mod white_pawn_moves_tests {
         use super::*;
 
         #[test]
         fn blocks_forward_move_if_occupied() {
-            let positions = pawn_moves(Position::new(1, 3), PieceColour::White, &board());
+            let positions = find(white_pawn(), Position::new(1, 3), &board());
             assert!(positions.is_empty())
         }
 
         #[test]
         fn finds_pawn_move() {
-            let positions = pawn_moves(Position::new(2, 5), PieceColour::White, &board());
+            let positions = find(white_pawn(), Position::new(2, 5), &board());
             assert_eq!(positions, vec![Position::new(3, 5)])
         }
 
         #[test]
         fn finds_double_move_if_on_home_row() {
-            let positions = pawn_moves(Position::new(1, 5), PieceColour::White, &board());
+            let positions = find(white_pawn(), Position::new(1, 5), &board());
             assert_eq!(positions, vec![Position::new(2, 5), Position::new(3, 5),])
         }
 
         #[test]
         fn finds_capture_if_available() {
-            let positions = pawn_moves(Position::new(1, 4), PieceColour::White, &board());
+            let positions = find(white_pawn(), Position::new(1, 4), &board());
             assert_eq!(
                 positions,
                 vec![
+                    Position::new(2, 3),
                     Position::new(2, 4),
-                    Position::new(3, 4),
-                    Position::new(2, 3)
+                    Position::new(3, 4)
                 ]
             )
         }
 
         #[test]
         fn finds_en_passant_capture_if_available() {
-            let positions = pawn_moves(Position::new(4, 4), PieceColour::White, &board());
+            let positions = find(white_pawn(), Position::new(4, 4), &board());
             assert_eq!(positions, vec![Position::new(5, 4), Position::new(5, 5),])
         }
 
+        fn white_pawn() -> Piece {
+            Piece::new(PieceColour::White, PieceType::Pawn)
+        }
+
         fn board() -> Board {
             let mut builder = Board::builder();
             builder.piece(
@@ -236,41 +124,45 @@ mod tests {
 
         #[test]
         fn blocks_forward_move_if_occupied() {
-            let positions = pawn_moves(Position::new(6, 3), PieceColour::Black, &board());
+            let positions = find(black_pawn(), Position::new(6, 3), &board());
             assert!(positions.is_empty())
         }
 
         #[test]
         fn finds_pawn_move() {
-            let positions = pawn_moves(Position::new(5, 5), PieceColour::Black, &board());
+            let positions = find(black_pawn(), Position::new(5, 5), &board());
             assert_eq!(positions, vec![Position::new(4, 5)])
         }
 
         #[test]
         fn finds_double_move_if_on_home_row() {
-            let positions = pawn_moves(Position::new(6, 5), PieceColour::Black, &board());
-            assert_eq!(positions, vec![Position::new(5, 5), Position::new(4, 5)])
+            let positions = find(black_pawn(), Position::new(6, 5), &board());
+            assert_eq!(positions, vec![Position::new(4, 5), Position::new(5, 5)])
         }
 
         #[test]
         fn finds_capture_if_available() {
-            let positions = pawn_moves(Position::new(6, 4), PieceColour::Black, &board());
+            let positions = find(black_pawn(), Position::new(6, 4), &board());
             assert_eq!(
                 positions,
                 vec![
-                    Position::new(5, 4),
                     Position::new(4, 4),
-                    Position::new(5, 3)
+                    Position::new(5, 3),
+                    Position::new(5, 4)
                 ]
             )
         }
 
         #[test]
         fn finds_en_passant_capture_if_available() {
-            let positions = pawn_moves(Position::new(5, 6), PieceColour::Black, &board());
+            let positions = find(black_pawn(), Position::new(5, 6), &board());
             assert_eq!(positions, vec![Position::new(4, 6), Position::new(4, 7)])
         }
 
+        fn black_pawn() -> Piece {
+            Piece::new(PieceColour::Black, PieceType::Pawn)
+        }
+
         fn board() -> Board {
             let mut builder = Board::builder();
             builder.piece(
@@ -282,165 +174,98 @@ mod tests {
         }
     }
 
-    mod apply_metrics_tests {
+    mod piece_moves_tests {
         use super::*;
 
         #[test]
-        fn applies_list_of_metrics_until_blocked() {
-            let metrics = &[(1, 0), (0, 1), (-1, 0), (0, -1)];
-            let positions = apply_metrics(
-                Position::try_from(0, 3).unwrap(),
-                PieceColour::White,
-                metrics,
-                &board(),
+        fn finds_moves_until_blocked() {
+            let mut builder = Board::builder();
+            builder
+                .piece(
+                    Piece::new(PieceColour::Black, PieceType::Rook),
+                    Position::new(2, 3),
+                )
+                .piece(
+                    Piece::new(PieceColour::Black, PieceType::Knight),
+                    Position::new(0, 2),
+                )
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::Rook),
+                    Position::new(0, 4),
+                );
+            let positions = find(
+                Piece::new(PieceColour::White, PieceType::Rook),
+                Position::new(0, 3),
+                &builder.build(),
             );
             assert_eq!(
                 positions,
                 vec![
-                    Position::try_from(1, 3).unwrap(),
-                    Position::try_from(2, 3).unwrap(),
-                    Position::try_from(0, 2).unwrap()
+                    Position::new(0, 2),
+                    Position::new(1, 3),
+                    Position::new(2, 3)
                 ]
             )
         }
 
-        fn board() -> Board {
-            let mut builder = Board::builder();
-            builder.piece(
-                Piece::new(PieceColour::Black, PieceType::Rook),
-                Position::try_from(2, 3).unwrap(),
-            );
-            builder.piece(
-                Piece::new(PieceColour::Black, PieceType::Knight),
-                Position::try_from(0, 2).unwrap(),
-            );
-            builder.piece(
-                Piece::new(PieceColour::White, PieceType::Rook),
-                Position::try_from(0, 4).unwrap(),
-            );
-            builder.build()
-        }
-    }
-
-    mod apply_metric_tests {
-        use super::*;
-
         #[test]
-        fn returns_once_new_position_invalid() {
-            let positions = apply_metric(
-                Position::try_from(1, 3).unwrap(),
-                PieceColour::White,
-                (-1, 0),
+        fn excludes_positions_occupied_by_same_colour() {
+            let positions = find(
+                Piece::new(PieceColour::Black, PieceType::King),
+                Position::new(0, 0),
                 &board(),
             );
-            assert_eq!(positions, vec![Position::try_from(0, 3).unwrap()])
+            assert_eq!(positions, vec![Position::new(0, 1), Position::new(1, 1)])
         }
 
         #[test]
-        fn returns_once_new_position_occupied_by_same_colour() {
-            let positions = apply_metric(
-                Position::try_from(0, 3).unwrap(),
-                PieceColour::Black,
-                (1, 0),
-                &board(),
-            );
-            assert_eq!(positions, vec![Position::try_from(1, 3).unwrap()])
-        }
-
-        #[test]
-        fn returns_once_new_position_occupied_by_opposite_colour_including_new_position() {
-            let positions = apply_metric(
-                Position::try_from(0, 3).unwrap(),
-                PieceColour::White,
-                (1, 0),
+        fn includes_positions_occupied_by_opposite_colour() {
+            let positions = find(
+                Piece::new(PieceColour::White, PieceType::Knight),
+                Position::new(3, 1),
                 &board(),
             );
             assert_eq!(
                 positions,
                 vec![
-                    Position::try_from(1, 3).unwrap(),
-                    Position::try_from(2, 3).unwrap()
+                    Position::new(1, 0),
+                    Position::new(1, 2),
+                    Position::new(2, 3),
+                    Position::new(4, 3),
+                    Position::new(5, 0),
+                    Position::new(5, 2)
                 ]
             )
         }
-
-        fn board() -> Board {
-            let mut builder = Board::builder();
-            builder.piece(
-                Piece::new(PieceColour::Black, PieceType::Rook),
-                Position::try_from(2, 3).unwrap(),
-            );
-            builder.build()
-        }
-    }
-
-    mod apply_metrics_once_tests {
-        use super::*;
-
-        #[test]
-        fn applies_list_of_metrics_once_each() {
-            let metrics = &[(-1, 0), (1, 0), (0, 1)];
-            let positions = apply_metrics_once(
-                Position::try_from(0, 0).unwrap(),
-                PieceColour::Black,
-                metrics,
-                &board(),
-            );
-
-            assert_eq!(positions, vec![Position::try_from(0, 1).unwrap()])
-        }
     }
 
-    mod apply_metric_once_tests {
+    mod geometry_tests {
         use super::*;
 
         #[test]
-        fn returns_none_if_new_position_is_invalid() {
-            let position = apply_metric_once(
-                Position::try_from(0, 0).unwrap(),
-                PieceColour::White,
-                (-1, 0),
-                &board(),
-            );
-            assert_eq!(position, MoveOutcome::Invalid)
-        }
-
-        #[test]
-        fn returns_none_if_new_position_is_occupied_by_same_piece_colour() {
-            let position = apply_metric_once(
-                Position::try_from(0, 0).unwrap(),
-                PieceColour::Black,
-                (1, 0),
-                &board(),
-            );
-            assert_eq!(position, MoveOutcome::OccupiedSameColour)
-        }
-
-        #[test]
-        fn returns_new_position_if_occupied_by_opposite_piece_colour() {
-            let position = apply_metric_once(
-                Position::try_from(0, 0).unwrap(),
-                PieceColour::White,
-                (1, 0),
-                &board(),
+        fn ignores_other_pieces() {
+            let geometry = geometry(
+                Piece::new(PieceColour::White, PieceType::Rook),
+                Position::new(0, 0),
             );
-            assert_eq!(
-                position,
-                MoveOutcome::OccupiedOppositeColour(Position::try_from(1, 0).unwrap())
-            )
+            assert!(geometry.contains(Position::new(7, 0)));
+            assert_eq!(geometry.count(), 14)
         }
 
         #[test]
-        fn returns_new_position_if_unoccupied() {
-            let position = apply_metric_once(
-                Position::try_from(0, 0).unwrap(),
-                PieceColour::White,
-                (0, 1),
-                &board(),
+        fn includes_pawn_pushes_and_captures() {
+            let geometry = geometry(
+                Piece::new(PieceColour::Black, PieceType::Pawn),
+                Position::new(6, 3),
             );
             assert_eq!(
-                position,
-                MoveOutcome::Empty(Position::try_from(0, 1).unwrap())
+                geometry.positions().collect::<Vec<Position>>(),
+                vec![
+                    Position::new(4, 3),
+                    Position::new(5, 2),
+                    Position::new(5, 3),
+                    Position::new(5, 4)
+                ]
             )
         }
     }
@@ -0,0 +1,137 @@
+use crate::model::{Board, Move};
+
+use super::{apply, generate};
+
+pub fn perft(board: &Board, depth: usize) -> usize {
+    count(&mut board.clone(), depth)
+}
+
+pub fn divide(board: &Board, depth: usize) -> Vec<(Move, usize)> {
+    if depth == 0 {
+        return Vec::new();
+    }
+
+    let mut board = board.clone();
+    generate::legal_moves(&board)
+        .into_iter()
+        .map(|movement| {
+            let undo = apply::make_move(&mut board, &movement);
+            let nodes = count(&mut board, depth - 1);
+            apply::unmake_move(&mut board, undo);
+            (movement, nodes)
+        })
+        .collect()
+}
+
+fn count(board: &mut Board, depth: usize) -> usize {
+    if depth == 0 {
+        return 1;
+    }
+
+    let moves = generate::legal_moves(board);
+    if depth == 1 {
+        return moves.len();
+    }
+
+    moves
+        .iter()
+        .map(|movement| {
+            let undo = apply::make_move(board, movement);
+            let nodes = count(board, depth - 1);
+            apply::unmake_move(board, undo);
+            nodes
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{parse_fen, DEFAULT_FEN};
+
+    // Node counts from https://www.chessprogramming.org/Perft_Results
+    mod perft_tests {
+        use super::*;
+
+        #[test]
+        fn counts_starting_position() {
+            assert_nodes(DEFAULT_FEN, &[20, 400, 8902, 197281]);
+        }
+
+        #[test]
+        fn counts_kiwipete() {
+            assert_nodes(
+                "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+                &[48, 2039, 97862],
+            );
+        }
+
+        #[test]
+        fn counts_position_3() {
+            assert_nodes(
+                "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+                &[14, 191, 2812, 43238],
+            );
+        }
+
+        #[test]
+        fn counts_position_4() {
+            assert_nodes(
+                "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+                &[6, 264, 9467, 422333],
+            );
+        }
+
+        #[test]
+        fn counts_position_5() {
+            assert_nodes(
+                "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+                &[44, 1486, 62379],
+            );
+        }
+
+        #[test]
+        fn counts_position_6() {
+            assert_nodes(
+                "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+                &[46, 2079, 89890],
+            );
+        }
+    }
+
+    mod divide_tests {
+        use super::*;
+        use crate::engine::uci::uci;
+
+        #[test]
+        fn splits_nodes_by_root_move() {
+            let board = board(DEFAULT_FEN);
+            let divided = divide(&board, 3);
+
+            assert_eq!(divided.len(), 20);
+            assert_eq!(divided.iter().map(|(_, nodes)| nodes).sum::<usize>(), 8902);
+            let e4 = divided
+                .iter()
+                .find(|(movement, _)| uci(movement) == "e2e4")
+                .map(|(_, nodes)| *nodes);
+            assert_eq!(e4, Some(600));
+        }
+
+        #[test]
+        fn returns_nothing_at_depth_zero() {
+            assert!(divide(&board(DEFAULT_FEN), 0).is_empty())
+        }
+    }
+
+    fn assert_nodes(fen: &str, expected: &[usize]) {
+        let board = board(fen);
+        let actual: Vec<usize> = (1..=expected.len())
+            .map(|depth| perft(&board, depth))
+            .collect();
+        assert_eq!(actual, expected)
+    }
+
+    fn board(fen: &str) -> Board {
+        parse_fen(fen).unwrap().starting_board().clone()
+    }
+}
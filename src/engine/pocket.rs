@@ -0,0 +1,130 @@
+use crate::model::{
+    Board, Move, Piece, PieceColour, PieceType, Position, MAX_POSITION, MIN_POSITION,
+};
+
+use super::{attacks, error::Rejection};
+
+const DROPPABLE: [PieceType; 5] = [
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+    PieceType::Pawn,
+];
+
+// Why a drop cannot be made, or None if it can
+pub fn rejection(board: &Board, piece: Piece, position: Position) -> Option<Rejection> {
+    if board.occupant(position).is_some() {
+        return Some(Rejection::Occupied);
+    }
+    if !droppable(piece, position) {
+        return Some(Rejection::WrongGeometry);
+    }
+
+    let mut next_board = board.clone();
+    if !next_board.remove_from_pocket(piece) {
+        return Some(Rejection::NotInPocket);
+    }
+    next_board.add(piece, position);
+    if attacks::in_check(&next_board, *piece.colour()) {
+        return Some(Rejection::LeavesKingInCheck);
+    }
+    None
+}
+
+// Every drop onto an empty square, whether or not it leaves the king in check
+pub fn moves(board: &Board, colour: PieceColour) -> Vec<Move> {
+    let Some(pocket) = board.pocket(colour) else {
+        return Vec::new();
+    };
+    let empty = !board.occupied();
+
+    DROPPABLE
+        .into_iter()
+        .filter(|&piece_type| board.is_partnered() || pocket.count(piece_type) > 0)
+        .map(|piece_type| Piece::new(colour, piece_type))
+        .flat_map(|piece| {
+            empty
+                .positions()
+                .filter(move |&position| droppable(piece, position))
+                .map(move |position| Move::drop(piece, position))
+        })
+        .collect()
+}
+
+// Pawns may not be dropped on the first or last rank
+fn droppable(piece: Piece, position: Position) -> bool {
+    *piece.piece_type() != PieceType::Pawn
+        || (position.row() != MIN_POSITION && position.row() != MAX_POSITION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse_fen;
+
+    mod rejection_tests {
+        use super::*;
+
+        #[test]
+        fn accepts_drop_from_pocket() {
+            let board = board("4k3/8/8/8/8/8/8/4K3[N] w - - 0 1");
+            let knight = Piece::new(PieceColour::White, PieceType::Knight);
+            assert_eq!(rejection(&board, knight, Position::new(2, 5)), None)
+        }
+
+        #[test]
+        fn rejects_illegal_drops() {
+            let board = board("4k3/8/8/8/8/8/4r3/4K3[P] w - - 0 1");
+            let pawn = Piece::new(PieceColour::White, PieceType::Pawn);
+            let knight = Piece::new(PieceColour::White, PieceType::Knight);
+
+            assert_eq!(
+                rejection(&board, pawn, Position::new(1, 4)),
+                Some(Rejection::Occupied)
+            );
+            assert_eq!(
+                rejection(&board, pawn, Position::new(7, 0)),
+                Some(Rejection::WrongGeometry)
+            );
+            assert_eq!(
+                rejection(&board, knight, Position::new(2, 2)),
+                Some(Rejection::NotInPocket)
+            );
+            assert_eq!(
+                rejection(&board, pawn, Position::new(2, 2)),
+                Some(Rejection::LeavesKingInCheck)
+            )
+        }
+
+        #[test]
+        fn partners_supply_any_piece() {
+            let mut board = board("4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+            let queen = Piece::new(PieceColour::White, PieceType::Queen);
+            assert_eq!(
+                rejection(&board, queen, Position::new(3, 3)),
+                Some(Rejection::NotInPocket)
+            );
+
+            board.enable_pockets(true);
+            assert_eq!(rejection(&board, queen, Position::new(3, 3)), None)
+        }
+    }
+
+    mod moves_tests {
+        use super::*;
+
+        #[test]
+        fn finds_drops_onto_empty_squares() {
+            let board = board("4k3/8/8/8/8/8/8/4K3[Pn] w - - 0 1");
+            let drops = moves(&board, PieceColour::White);
+            assert_eq!(drops.len(), 48);
+            assert!(drops.iter().all(Move::is_drop));
+            assert_eq!(moves(&board, PieceColour::Black).len(), 62)
+        }
+    }
+
+    fn board(fen: &str) -> Board {
+        parse_fen(fen).unwrap().starting_board().clone()
+    }
+}
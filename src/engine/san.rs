@@ -0,0 +1,318 @@
+use crate::model::{Board, Check, Move, PieceType, COLUMNS, ROWS};
+
+use super::{apply, attacks, generate};
+
+pub fn san(board: &Board, movement: &Move) -> String {
+    let text = if movement.is_drop() {
+        format!(
+            "{}@{}",
+            letter(*movement.piece().piece_type()),
+            movement.to()
+        )
+    } else if movement.is_castle() {
+        if movement.to().col() > movement.from().col() {
+            "O-O".to_string()
+        } else {
+            "O-O-O".to_string()
+        }
+    } else {
+        let capture = if apply::is_capture(board, movement) {
+            "x"
+        } else {
+            ""
+        };
+        let promotion = movement.promotes_to().map_or(String::new(), |piece_type| {
+            format!("={}", letter(piece_type))
+        });
+
+        match movement.piece().piece_type() {
+            PieceType::Pawn if capture.is_empty() => format!("{}{promotion}", movement.to()),
+            PieceType::Pawn => format!(
+                "{}x{}{promotion}",
+                column(movement.from().col()),
+                movement.to()
+            ),
+            &piece_type => format!(
+                "{}{}{capture}{}",
+                letter(piece_type),
+                disambiguation(board, movement),
+                movement.to()
+            ),
+        }
+    };
+
+    match check(&apply::apply(board, movement)) {
+        None => text,
+        Some(Check::Check) => format!("{text}+"),
+        Some(Check::Checkmate) => format!("{text}#"),
+    }
+}
+
+pub fn check(board: &Board) -> Option<Check> {
+    if !attacks::in_check(board, *board.active_colour()) {
+        None
+    } else if generate::has_legal_move(board) {
+        Some(Check::Check)
+    } else {
+        Some(Check::Checkmate)
+    }
+}
+
+fn disambiguation(board: &Board, movement: &Move) -> String {
+    let others: Vec<Move> = generate::legal_moves(board)
+        .into_iter()
+        .filter(|other| {
+            !other.is_drop()
+                && other.piece() == movement.piece()
+                && other.to() == movement.to()
+                && other.from() != movement.from()
+        })
+        .collect();
+
+    let from = movement.from();
+    if others.is_empty() {
+        String::new()
+    } else if others.iter().all(|other| other.from().col() != from.col()) {
+        column(from.col()).to_string()
+    } else if others.iter().all(|other| other.from().row() != from.row()) {
+        row(from.row()).to_string()
+    } else {
+        from.to_string()
+    }
+}
+
+pub fn letter(piece_type: PieceType) -> char {
+    match piece_type {
+        PieceType::Pawn => 'P',
+        PieceType::Knight => 'N',
+        PieceType::Bishop => 'B',
+        PieceType::Rook => 'R',
+        PieceType::Queen => 'Q',
+        PieceType::King => 'K',
+    }
+}
+
+fn column(col: i8) -> char {
+    COLUMNS.as_bytes()[col as usize] as char
+}
+
+fn row(row: i8) -> char {
+    ROWS.as_bytes()[row as usize] as char
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{AvailableCastle, Piece, PieceColour, Position};
+
+    mod san_tests {
+        use super::*;
+
+        #[test]
+        fn omits_unnecessary_qualifier() {
+            let movement = knight(Position::new(0, 1), Position::new(1, 3));
+            assert_eq!(san(&board(), &movement), "Nd2")
+        }
+
+        #[test]
+        fn qualifies_by_column() {
+            let movement = knight(Position::new(0, 1), Position::new(2, 2));
+            assert_eq!(san(&board(), &movement), "Nbc3")
+        }
+
+        #[test]
+        fn qualifies_by_row() {
+            let movement = Move::new(
+                Piece::new(PieceColour::White, PieceType::Rook),
+                Position::new(4, 0),
+                Position::new(2, 0),
+                None,
+            );
+            assert_eq!(san(&board(), &movement), "R5a3")
+        }
+
+        #[test]
+        fn qualifies_by_position() {
+            let mut builder = Board::builder();
+            builder
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::King),
+                    Position::new(0, 7),
+                )
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::Queen),
+                    Position::new(0, 0),
+                )
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::Queen),
+                    Position::new(2, 0),
+                )
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::Queen),
+                    Position::new(0, 2),
+                )
+                .piece(
+                    Piece::new(PieceColour::Black, PieceType::King),
+                    Position::new(6, 7),
+                );
+            let movement = Move::new(
+                Piece::new(PieceColour::White, PieceType::Queen),
+                Position::new(0, 0),
+                Position::new(1, 1),
+                None,
+            );
+            assert_eq!(san(&builder.build(), &movement), "Qa1b2")
+        }
+
+        #[test]
+        fn adds_capture() {
+            let movement = Move::new(
+                Piece::new(PieceColour::White, PieceType::Bishop),
+                Position::new(0, 2),
+                Position::new(5, 7),
+                None,
+            );
+            assert_eq!(san(&board(), &movement), "Bxh6")
+        }
+
+        #[test]
+        fn adds_pawn_capture_column() {
+            let movement = Move::new(
+                Piece::new(PieceColour::White, PieceType::Pawn),
+                Position::new(4, 4),
+                Position::new(5, 3),
+                None,
+            );
+            assert_eq!(san(&board(), &movement), "exd6")
+        }
+
+        #[test]
+        fn adds_promotion_and_check() {
+            let movement = Move::new(
+                Piece::new(PieceColour::White, PieceType::Pawn),
+                Position::new(6, 6),
+                Position::new(7, 6),
+                Some(PieceType::Queen),
+            );
+            assert_eq!(san(&board(), &movement), "g8=Q+")
+        }
+
+        #[test]
+        fn formats_drop() {
+            let knight = Piece::new(PieceColour::White, PieceType::Knight);
+            let mut builder = Board::builder();
+            builder
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::King),
+                    Position::new(0, 4),
+                )
+                .piece(
+                    Piece::new(PieceColour::Black, PieceType::King),
+                    Position::new(7, 4),
+                )
+                .pocket_piece(knight);
+            let movement = Move::drop(knight, Position::new(5, 5));
+            assert_eq!(san(&builder.build(), &movement), "N@f6+")
+        }
+
+        #[test]
+        fn adds_checkmate() {
+            let movement = Move::new(
+                Piece::new(PieceColour::White, PieceType::Rook),
+                Position::new(4, 0),
+                Position::new(7, 0),
+                None,
+            );
+            let mut builder = Board::builder();
+            builder
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::King),
+                    Position::new(5, 4),
+                )
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::Rook),
+                    Position::new(4, 0),
+                )
+                .piece(
+                    Piece::new(PieceColour::Black, PieceType::King),
+                    Position::new(7, 4),
+                );
+            assert_eq!(san(&builder.build(), &movement), "Ra8#")
+        }
+
+        #[test]
+        fn formats_castle() {
+            let movement = Move::new(
+                Piece::new(PieceColour::White, PieceType::King),
+                Position::new(0, 4),
+                Position::new(0, 6),
+                None,
+            );
+            assert_eq!(san(&board(), &movement), "O-O")
+        }
+    }
+
+    fn knight(from: Position, to: Position) -> Move {
+        Move::new(
+            Piece::new(PieceColour::White, PieceType::Knight),
+            from,
+            to,
+            None,
+        )
+    }
+
+    fn board() -> Board {
+        let mut builder = Board::builder();
+        builder
+            .available_castles(vec![AvailableCastle::WhiteKingside])
+            .piece(
+                Piece::new(PieceColour::White, PieceType::King),
+                Position::new(0, 4),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Rook),
+                Position::new(0, 7),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Rook),
+                Position::new(4, 0),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Rook),
+                Position::new(0, 0),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Knight),
+                Position::new(0, 1),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Knight),
+                Position::new(0, 3),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Bishop),
+                Position::new(0, 2),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Pawn),
+                Position::new(4, 4),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Pawn),
+                Position::new(6, 6),
+            )
+            .piece(
+                Piece::new(PieceColour::Black, PieceType::Pawn),
+                Position::new(5, 3),
+            )
+            .piece(
+                Piece::new(PieceColour::Black, PieceType::Pawn),
+                Position::new(5, 7),
+            )
+            .piece(
+                Piece::new(PieceColour::Black, PieceType::King),
+                Position::new(7, 3),
+            );
+        builder.build()
+    }
+}
@@ -0,0 +1,452 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use crate::model::{Board, Move, PieceColour, PieceType, Variant};
+
+use super::{apply, attacks, evaluate, generate, variant};
+
+const MATE: i32 = 100_000;
+const INFINITY: i32 = MATE + 1;
+const MAX_DEPTH: usize = 64;
+const NODES_BETWEEN_CLOCK_CHECKS: usize = 1024;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Limit {
+    Depth(usize),
+    Time(Duration),
+}
+
+// From white's point of view; mates are counted in moves
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Score {
+    Centipawns(i32),
+    Mate(i32),
+}
+
+impl Score {
+    // Converts a score given from the side to move's point of view, as UCI engines report them,
+    // to white's
+    pub fn from_side_to_move(self, colour: PieceColour) -> Self {
+        match (self, colour) {
+            (score, PieceColour::White) => score,
+            (Score::Centipawns(centipawns), PieceColour::Black) => Score::Centipawns(-centipawns),
+            (Score::Mate(moves), PieceColour::Black) => Score::Mate(-moves),
+        }
+    }
+
+    fn from_search(score: i32, colour: PieceColour) -> Self {
+        let score = match colour {
+            PieceColour::White => score,
+            PieceColour::Black => -score,
+        };
+        if score.abs() >= MATE - MAX_DEPTH as i32 {
+            let plies = MATE - score.abs();
+            Score::Mate(score.signum() * (plies + 1) / 2)
+        } else {
+            Score::Centipawns(score)
+        }
+    }
+}
+
+impl Display for Score {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Score::Centipawns(centipawns) => write!(f, "{:+.2}", f64::from(*centipawns) / 100.0),
+            Score::Mate(moves) => write!(f, "#{moves}"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SearchResult {
+    principal_variation: Vec<Move>,
+    score: Score,
+    depth: usize,
+    nodes: usize,
+}
+
+impl SearchResult {
+    pub fn best_move(&self) -> Option<&Move> {
+        self.principal_variation.first()
+    }
+
+    pub fn principal_variation(&self) -> &[Move] {
+        &self.principal_variation
+    }
+
+    pub fn score(&self) -> Score {
+        self.score
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn nodes(&self) -> usize {
+        self.nodes
+    }
+}
+
+pub fn search(board: &Board, limit: Limit) -> SearchResult {
+    let colour = *board.active_colour();
+    let (max_depth, deadline) = match limit {
+        Limit::Depth(depth) => (depth.clamp(1, MAX_DEPTH), None),
+        Limit::Time(duration) => (MAX_DEPTH, Some(Instant::now() + duration)),
+    };
+
+    let mut searcher = Searcher {
+        board: board.clone(),
+        deadline: None,
+        nodes: 0,
+        aborted: false,
+    };
+    let mut result = SearchResult {
+        principal_variation: Vec::new(),
+        score: Score::from_search(evaluate::evaluate(board), colour),
+        depth: 0,
+        nodes: 0,
+    };
+
+    for depth in 1..=max_depth {
+        let mut principal_variation = Vec::new();
+        let score = searcher.negamax(
+            depth,
+            0,
+            -INFINITY,
+            INFINITY,
+            &result.principal_variation,
+            &mut principal_variation,
+        );
+        if searcher.aborted {
+            break;
+        }
+
+        result = SearchResult {
+            principal_variation,
+            score: Score::from_search(score, colour),
+            depth,
+            nodes: searcher.nodes,
+        };
+
+        if result.principal_variation.is_empty() || matches!(result.score, Score::Mate(_)) {
+            break;
+        }
+        // The first iteration always completes, so that there is a move to report
+        searcher.deadline = deadline;
+    }
+
+    result.nodes = searcher.nodes;
+    result
+}
+
+struct Searcher {
+    board: Board,
+    deadline: Option<Instant>,
+    nodes: usize,
+    aborted: bool,
+}
+
+impl Searcher {
+    fn negamax(
+        &mut self,
+        depth: usize,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+        previous_variation: &[Move],
+        principal_variation: &mut Vec<Move>,
+    ) -> i32 {
+        if self.should_abort() {
+            return 0;
+        }
+        let colour = *self.board.active_colour();
+        // Variant wins, such as a king reaching the hill, end the game before any move
+        if let Some(winner) = variant::winner(&self.board) {
+            return mate(winner == colour, ply);
+        }
+        if depth == 0 {
+            return self.quiescence(alpha, beta, ply);
+        }
+
+        let mut moves = generate::legal_moves(&self.board);
+        if moves.is_empty() {
+            return match self.board.variant() {
+                // Running out of moves wins in Antichess
+                Variant::Antichess => mate(true, ply),
+                _ if attacks::in_check(&self.board, colour) => mate(false, ply),
+                _ => 0,
+            };
+        }
+        order(&self.board, &mut moves, previous_variation.get(ply));
+
+        for movement in moves {
+            let mut variation = Vec::new();
+            let undo = apply::make_move(&mut self.board, &movement);
+            let score = -self.negamax(
+                depth - 1,
+                ply + 1,
+                -beta,
+                -alpha,
+                previous_variation,
+                &mut variation,
+            );
+            apply::unmake_move(&mut self.board, undo);
+
+            if self.aborted {
+                return 0;
+            }
+            if score > alpha {
+                alpha = score;
+                principal_variation.clear();
+                principal_variation.push(movement);
+                principal_variation.append(&mut variation);
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+        alpha
+    }
+
+    fn quiescence(&mut self, mut alpha: i32, beta: i32, ply: usize) -> i32 {
+        if self.should_abort() {
+            return 0;
+        }
+        if let Some(winner) = variant::winner(&self.board) {
+            return mate(winner == *self.board.active_colour(), ply);
+        }
+
+        let standing = evaluate::evaluate(&self.board);
+        if standing >= beta {
+            return beta;
+        }
+        alpha = alpha.max(standing);
+
+        let mut moves: Vec<Move> = generate::legal_moves(&self.board)
+            .into_iter()
+            .filter(|movement| {
+                apply::is_capture(&self.board, movement) || movement.promotes_to().is_some()
+            })
+            .collect();
+        order(&self.board, &mut moves, None);
+
+        for movement in moves {
+            let undo = apply::make_move(&mut self.board, &movement);
+            let score = -self.quiescence(-beta, -alpha, ply + 1);
+            apply::unmake_move(&mut self.board, undo);
+
+            if self.aborted {
+                return 0;
+            }
+            if score >= beta {
+                return beta;
+            }
+            alpha = alpha.max(score);
+        }
+        alpha
+    }
+
+    fn should_abort(&mut self) -> bool {
+        self.nodes += 1;
+        if self.nodes % NODES_BETWEEN_CLOCK_CHECKS == 0 {
+            if let Some(deadline) = self.deadline {
+                self.aborted |= Instant::now() >= deadline;
+            }
+        }
+        self.aborted
+    }
+}
+
+// For the side to move, with quicker wins and slower losses preferred
+fn mate(won: bool, ply: usize) -> i32 {
+    let score = MATE - ply as i32;
+    if won {
+        score
+    } else {
+        -score
+    }
+}
+
+// Previous best move first, then captures of the most valuable piece by the least valuable
+fn order(board: &Board, moves: &mut [Move], previous_best: Option<&Move>) {
+    moves.sort_by_key(|movement| {
+        if Some(movement) == previous_best {
+            return i32::MIN;
+        }
+        let victim = match board.occupant(movement.to()) {
+            Some(piece) => evaluate::value(*piece.piece_type()),
+            None if apply::is_capture(board, movement) => evaluate::value(PieceType::Pawn),
+            None => return 0,
+        };
+        evaluate::value(*movement.piece().piece_type()) / 10 - victim
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Piece, Position};
+    use crate::parse::parse_fen;
+
+    #[test]
+    fn finds_mate_in_one() {
+        let result = search(&back_rank(), Limit::Depth(3));
+        assert_eq!(
+            result.best_move(),
+            Some(&Move::new(
+                Piece::new(PieceColour::White, PieceType::Rook),
+                Position::new(0, 0),
+                Position::new(7, 0),
+                None
+            ))
+        );
+        assert_eq!(result.score(), Score::Mate(1))
+    }
+
+    #[test]
+    fn captures_hanging_piece() {
+        let mut builder = Board::builder();
+        builder
+            .piece(
+                Piece::new(PieceColour::White, PieceType::King),
+                Position::new(0, 4),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Knight),
+                Position::new(2, 2),
+            )
+            .piece(
+                Piece::new(PieceColour::Black, PieceType::Queen),
+                Position::new(4, 3),
+            )
+            .piece(
+                Piece::new(PieceColour::Black, PieceType::King),
+                Position::new(7, 7),
+            );
+        let result = search(&builder.build(), Limit::Depth(2));
+
+        assert_eq!(result.best_move().map(Move::to), Some(Position::new(4, 3)));
+        assert!(matches!(result.score(), Score::Centipawns(score) if score > 0))
+    }
+
+    #[test]
+    fn reports_score_from_whites_point_of_view() {
+        let mut builder = Board::builder();
+        builder
+            .active_colour(PieceColour::Black)
+            .piece(
+                Piece::new(PieceColour::White, PieceType::King),
+                Position::new(0, 4),
+            )
+            .piece(
+                Piece::new(PieceColour::Black, PieceType::Queen),
+                Position::new(4, 3),
+            )
+            .piece(
+                Piece::new(PieceColour::Black, PieceType::King),
+                Position::new(7, 7),
+            );
+        let result = search(&builder.build(), Limit::Depth(1));
+
+        assert!(matches!(result.score(), Score::Centipawns(score) if score < -800))
+    }
+
+    #[test]
+    fn returns_no_move_if_checkmated() {
+        let board = apply::apply(
+            &back_rank(),
+            &Move::new(
+                Piece::new(PieceColour::White, PieceType::Rook),
+                Position::new(0, 0),
+                Position::new(7, 0),
+                None,
+            ),
+        );
+        let result = search(&board, Limit::Depth(2));
+
+        assert!(result.best_move().is_none());
+        assert_eq!(result.score(), Score::Mate(0))
+    }
+
+    #[test]
+    fn walks_king_to_the_hill() {
+        let board = variant_board("8/8/8/8/8/8/4K3/k7 w - - 0 1", Variant::KingOfTheHill);
+        let result = search(&board, Limit::Depth(3));
+
+        assert_eq!(
+            result
+                .best_move()
+                .map(|movement| *movement.piece().piece_type()),
+            Some(PieceType::King)
+        );
+        assert_eq!(result.score(), Score::Mate(2))
+    }
+
+    #[test]
+    fn loses_every_piece_to_win_antichess() {
+        let board = variant_board("8/8/8/8/8/3q4/8/4K3 w - - 0 1", Variant::Antichess);
+        let result = search(&board, Limit::Depth(2));
+
+        // Any square the queen attacks forces it to capture the last piece
+        assert!(result.best_move().is_some());
+        assert_eq!(result.score(), Score::Mate(1))
+    }
+
+    #[test]
+    fn completes_first_iteration_within_time_limit() {
+        let result = search(&back_rank(), Limit::Time(Duration::ZERO));
+        assert!(result.best_move().is_some());
+        assert!(result.depth() >= 1)
+    }
+
+    #[test]
+    fn converts_score_from_side_to_move() {
+        assert_eq!(
+            Score::Centipawns(35).from_side_to_move(PieceColour::Black),
+            Score::Centipawns(-35)
+        );
+        assert_eq!(
+            Score::Mate(2).from_side_to_move(PieceColour::White),
+            Score::Mate(2)
+        );
+    }
+
+    #[test]
+    fn formats_score() {
+        assert_eq!(Score::Centipawns(35).to_string(), "+0.35");
+        assert_eq!(Score::Centipawns(-120).to_string(), "-1.20");
+        assert_eq!(Score::Mate(-2).to_string(), "#-2");
+    }
+
+    fn variant_board(fen: &str, variant: Variant) -> Board {
+        let mut board = parse_fen(fen).unwrap().starting_board().clone();
+        board.update_variant(variant);
+        board
+    }
+
+    fn back_rank() -> Board {
+        let mut builder = Board::builder();
+        builder
+            .piece(
+                Piece::new(PieceColour::White, PieceType::King),
+                Position::new(0, 6),
+            )
+            .piece(
+                Piece::new(PieceColour::White, PieceType::Rook),
+                Position::new(0, 0),
+            )
+            .piece(
+                Piece::new(PieceColour::Black, PieceType::King),
+                Position::new(7, 6),
+            );
+        for col in 5..=7 {
+            builder.piece(
+                Piece::new(PieceColour::Black, PieceType::Pawn),
+                Position::new(6, col),
+            );
+        }
+        builder.build()
+    }
+}
@@ -0,0 +1,300 @@
+use std::fmt::Display;
+
+use crate::model::{Bitboard, Board, Piece, PieceColour, PieceType, Position};
+
+use super::attacks::{self, attackers, between, checkers};
+use super::evaluate::value;
+
+// Kings outrank everything when comparing the pieces on a line
+const KING_WORTH: i32 = 10_000;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Tactic {
+    Fork,
+    AbsolutePin,
+    RelativePin,
+    Skewer,
+    DiscoveredCheck,
+    HangingPiece,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Motif {
+    tactic: Tactic,
+    // The piece carrying out the tactic, or the piece left hanging
+    origin: Position,
+    // Nearest first for pins and skewers
+    targets: Vec<Position>,
+}
+
+impl Motif {
+    pub fn new(tactic: Tactic, origin: Position, targets: Vec<Position>) -> Self {
+        Motif {
+            tactic,
+            origin,
+            targets,
+        }
+    }
+
+    pub fn tactic(&self) -> Tactic {
+        self.tactic
+    }
+
+    pub fn origin(&self) -> Position {
+        self.origin
+    }
+
+    pub fn targets(&self) -> &[Position] {
+        &self.targets
+    }
+}
+
+impl Display for Motif {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let targets = |separator: &str| {
+            self.targets
+                .iter()
+                .map(Position::to_string)
+                .collect::<Vec<String>>()
+                .join(separator)
+        };
+        let origin = self.origin;
+        match self.tactic {
+            Tactic::Fork => write!(f, "Knight fork from {origin} on {}", targets(", ")),
+            Tactic::AbsolutePin => write!(f, "Absolute pin: {origin} pins {}", targets(" to ")),
+            Tactic::RelativePin => write!(f, "Relative pin: {origin} pins {}", targets(" to ")),
+            Tactic::Skewer => write!(f, "Skewer: {origin} skewers {}", targets(" to ")),
+            Tactic::DiscoveredCheck => write!(f, "Discovered check from {origin}"),
+            Tactic::HangingPiece => write!(f, "Hanging piece on {origin}"),
+        }
+    }
+}
+
+// Motifs which the move between the boards created for the side that played it
+pub fn motifs(before: &Board, after: &Board) -> Vec<Motif> {
+    let colour = after.active_colour().opposite();
+    let existing = position_motifs(before, colour);
+    let mut motifs: Vec<Motif> = position_motifs(after, colour)
+        .into_iter()
+        .filter(|motif| !existing.contains(motif) && !recapturable(before, after, motif))
+        .collect();
+    motifs.extend(discovered_checks(before, after));
+    motifs
+}
+
+fn position_motifs(board: &Board, colour: PieceColour) -> Vec<Motif> {
+    let mut motifs = forks(board, colour);
+    motifs.extend(lines(board, colour));
+    motifs.extend(hanging(board, colour));
+    motifs
+}
+
+// Attacks on two or more pieces which are either worth more than the knight or undefended
+fn forks(board: &Board, colour: PieceColour) -> Vec<Motif> {
+    let opponent = colour.opposite();
+    board
+        .bitboard(Piece::new(colour, PieceType::Knight))
+        .positions()
+        .filter_map(|origin| {
+            let targets: Vec<Position> = (attacks::knight(origin) & board.occupied_by(opponent))
+                .positions()
+                .filter(|&target| {
+                    let piece_type = occupant_type(board, target);
+                    worth(piece_type) > value(PieceType::Knight)
+                        || (piece_type != PieceType::Pawn
+                            && attackers(board, target, opponent).is_empty())
+                })
+                .collect();
+            (targets.len() >= 2).then(|| Motif::new(Tactic::Fork, origin, targets))
+        })
+        .collect()
+}
+
+// Pins and skewers: a slider attacks one opposing piece with another behind it on the same line
+fn lines(board: &Board, colour: PieceColour) -> Vec<Motif> {
+    let opponent = board.occupied_by(colour.opposite());
+    let occupied = board.occupied();
+    let mut motifs = Vec::new();
+
+    for piece_type in [PieceType::Bishop, PieceType::Rook, PieceType::Queen] {
+        let piece = Piece::new(colour, piece_type);
+        for origin in board.bitboard(piece).positions() {
+            let attacked = attacks::from(piece, origin, occupied) & opponent;
+            for front in attacked.positions() {
+                let xray = attacks::from(piece, origin, occupied & !Bitboard::from_position(front));
+                let Some(behind) = (xray & opponent)
+                    .positions()
+                    .find(|&behind| between(origin, behind).contains(front))
+                else {
+                    continue;
+                };
+
+                let (front_type, behind_type) =
+                    (occupant_type(board, front), occupant_type(board, behind));
+                let tactic = if behind_type == PieceType::King {
+                    Tactic::AbsolutePin
+                } else if worth(front_type) < worth(behind_type) {
+                    Tactic::RelativePin
+                } else if worth(front_type) > worth(behind_type) && behind_type != PieceType::Pawn {
+                    Tactic::Skewer
+                } else {
+                    continue;
+                };
+                motifs.push(Motif::new(tactic, origin, vec![front, behind]));
+            }
+        }
+    }
+    motifs
+}
+
+// Pieces the opponent can win, either because they are undefended or a cheaper piece attacks them
+fn hanging(board: &Board, colour: PieceColour) -> Vec<Motif> {
+    let opponent = colour.opposite();
+    let pieces = board.occupied_by(colour) & !board.bitboard(Piece::new(colour, PieceType::King));
+    pieces
+        .positions()
+        .filter(|&position| {
+            let cheapest = attackers(board, position, opponent)
+                .positions()
+                .map(|attacker| worth(occupant_type(board, attacker)))
+                .min();
+            cheapest.is_some_and(|cheapest| {
+                cheapest < worth(occupant_type(board, position))
+                    || attackers(board, position, colour).is_empty()
+            })
+        })
+        .map(|position| Motif::new(Tactic::HangingPiece, position, Vec::new()))
+        .collect()
+}
+
+// A piece which captured at least its own worth in pawns is trading rather than hanging
+fn recapturable(before: &Board, after: &Board, motif: &Motif) -> bool {
+    let position = motif.origin();
+    let pawns = |piece_type| worth(piece_type) / value(PieceType::Pawn);
+    motif.tactic() == Tactic::HangingPiece
+        && before.occupant(position).is_some_and(|captured| {
+            pawns(*captured.piece_type()) >= pawns(occupant_type(after, position))
+        })
+}
+
+// Checks given by a piece other than the one which moved
+fn discovered_checks(before: &Board, after: &Board) -> Vec<Motif> {
+    let king = after
+        .bitboard(Piece::new(*after.active_colour(), PieceType::King))
+        .first();
+    checkers(after)
+        .positions()
+        .filter(|&checker| before.occupant(checker) == after.occupant(checker))
+        .map(|checker| Motif::new(Tactic::DiscoveredCheck, checker, king.into_iter().collect()))
+        .collect()
+}
+
+fn occupant_type(board: &Board, position: Position) -> PieceType {
+    board
+        .occupant(position)
+        .map_or(PieceType::Pawn, |piece| *piece.piece_type())
+}
+
+fn worth(piece_type: PieceType) -> i32 {
+    match piece_type {
+        PieceType::King => KING_WORTH,
+        other => value(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::execute_moves;
+    use crate::parse::{parse_fen, parse_movetext};
+
+    mod motifs_tests {
+        use super::*;
+
+        #[test]
+        fn finds_knight_fork() {
+            let motifs = last_motifs("r3k3/8/8/1N6/8/8/8/4K3 w - - 0 1", "1. Nc7+");
+            assert_eq!(
+                motifs,
+                vec![Motif::new(
+                    Tactic::Fork,
+                    Position::new(6, 2),
+                    vec![Position::new(7, 0), Position::new(7, 4)]
+                )]
+            );
+            assert_eq!(motifs[0].to_string(), "Knight fork from c7 on a8, e8")
+        }
+
+        #[test]
+        fn finds_absolute_and_relative_pins() {
+            let motifs = last_motifs(
+                "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3",
+                "3. Bb5",
+            );
+            assert!(motifs.is_empty());
+
+            let motifs = last_motifs("4k3/4n3/8/8/8/8/8/R4K2 w - - 0 1", "1. Re1");
+            assert_eq!(
+                motifs,
+                vec![Motif::new(
+                    Tactic::AbsolutePin,
+                    Position::new(0, 4),
+                    vec![Position::new(6, 4), Position::new(7, 4)]
+                )]
+            );
+
+            let motifs = last_motifs("5q1k/8/3n4/8/8/8/8/4B2K w - - 0 1", "1. Bb4");
+            assert_eq!(motifs.len(), 1);
+            assert_eq!(motifs[0].tactic(), Tactic::RelativePin);
+            assert_eq!(motifs[0].to_string(), "Relative pin: b4 pins d6 to f8")
+        }
+
+        #[test]
+        fn finds_skewer() {
+            let motifs = last_motifs("8/8/8/8/8/q1k5/8/4K2R w - - 0 1", "1. Rh3+");
+            assert_eq!(
+                motifs,
+                vec![Motif::new(
+                    Tactic::Skewer,
+                    Position::new(2, 7),
+                    vec![Position::new(2, 2), Position::new(2, 0)]
+                )]
+            )
+        }
+
+        #[test]
+        fn finds_discovered_check() {
+            let motifs = last_motifs("4k3/8/8/8/4N3/8/8/4RK2 w - - 0 1", "1. Nc5+");
+            assert_eq!(
+                motifs,
+                vec![Motif::new(
+                    Tactic::DiscoveredCheck,
+                    Position::new(0, 4),
+                    vec![Position::new(7, 4)]
+                )]
+            )
+        }
+
+        #[test]
+        fn finds_hanging_piece_but_not_trade() {
+            let motifs = last_motifs("4k3/8/2p5/8/8/8/8/3QK3 w - - 0 1", "1. Qd5");
+            assert_eq!(
+                motifs,
+                vec![Motif::new(
+                    Tactic::HangingPiece,
+                    Position::new(4, 3),
+                    Vec::new()
+                )]
+            );
+
+            let motifs = last_motifs("4k3/1p6/2n5/8/4B3/8/8/4K3 w - - 0 1", "1. Bxc6+");
+            assert!(motifs.is_empty())
+        }
+
+        fn last_motifs(fen: &str, moves: &str) -> Vec<Motif> {
+            let board = parse_fen(fen).unwrap().starting_board().clone();
+            let boards = execute_moves(&board, &parse_movetext(moves).unwrap()).unwrap();
+            motifs(&boards[boards.len() - 2], &boards[boards.len() - 1])
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate::model::{Board, Move, PieceType};
+
+use super::{apply, generate, san};
+
+pub fn uci(movement: &Move) -> String {
+    if movement.is_drop() {
+        return format!(
+            "{}@{}",
+            san::letter(*movement.piece().piece_type()),
+            movement.to()
+        );
+    }
+    let promotion = match movement.promotes_to() {
+        Some(PieceType::Knight) => "n",
+        Some(PieceType::Bishop) => "b",
+        Some(PieceType::Rook) => "r",
+        Some(PieceType::Queen) => "q",
+        Some(PieceType::Pawn | PieceType::King) | None => "",
+    };
+    format!("{}{}{promotion}", movement.from(), movement.to())
+}
+
+pub fn from_uci(board: &Board, text: &str) -> Option<Move> {
+    generate::legal_moves(board)
+        .into_iter()
+        .find(|movement| uci(movement) == text)
+}
+
+// Stops at the first move which is not legal in the line so far
+pub fn san_line(board: &Board, moves: &[String]) -> Vec<String> {
+    let mut board = board.clone();
+    let mut line = Vec::new();
+    for text in moves {
+        let Some(movement) = from_uci(&board, text) else {
+            break;
+        };
+        line.push(san::san(&board, &movement));
+        apply::make_move(&mut board, &movement);
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Piece, PieceColour, Position};
+    use crate::parse::{parse_fen, DEFAULT_FEN};
+
+    #[test]
+    fn formats_move() {
+        let movement = Move::new(
+            Piece::new(PieceColour::White, PieceType::Knight),
+            Position::new(0, 6),
+            Position::new(2, 5),
+            None,
+        );
+        assert_eq!(uci(&movement), "g1f3")
+    }
+
+    #[test]
+    fn formats_drop() {
+        let movement = Move::drop(
+            Piece::new(PieceColour::White, PieceType::Pawn),
+            Position::new(5, 4),
+        );
+        assert_eq!(uci(&movement), "P@e6")
+    }
+
+    #[test]
+    fn formats_promotion() {
+        let movement = Move::new(
+            Piece::new(PieceColour::Black, PieceType::Pawn),
+            Position::new(1, 0),
+            Position::new(0, 1),
+            Some(PieceType::Knight),
+        );
+        assert_eq!(uci(&movement), "a2b1n")
+    }
+
+    #[test]
+    fn finds_legal_move_from_uci() {
+        let board = parse_fen(DEFAULT_FEN).unwrap().starting_board().clone();
+        let movement = from_uci(&board, "g1f3").unwrap();
+        assert_eq!(
+            movement,
+            Move::new(
+                Piece::new(PieceColour::White, PieceType::Knight),
+                Position::new(0, 6),
+                Position::new(2, 5),
+                None,
+            )
+        )
+    }
+
+    #[test]
+    fn finds_castling_from_king_move() {
+        let board = parse_fen("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1")
+            .unwrap()
+            .starting_board()
+            .clone();
+        let movement = from_uci(&board, "e8c8").unwrap();
+        assert_eq!(*movement.piece().piece_type(), PieceType::King)
+    }
+
+    #[test]
+    fn returns_none_for_illegal_move() {
+        let board = parse_fen(DEFAULT_FEN).unwrap().starting_board().clone();
+        assert!(from_uci(&board, "e2e5").is_none());
+        assert!(from_uci(&board, "nonsense").is_none())
+    }
+
+    #[test]
+    fn converts_line_to_san() {
+        let board = parse_fen(DEFAULT_FEN).unwrap().starting_board().clone();
+        let moves = ["e2e4", "e7e5", "g1f3", "e1e2", "b8c6"].map(String::from);
+        assert_eq!(san_line(&board, &moves), vec!["e4", "e5", "Nf3"])
+    }
+}
@@ -0,0 +1,266 @@
+use crate::model::{
+    Bitboard, Board, GameResult, Move, Piece, PieceColour, PieceType, Position, Variant,
+};
+
+use super::{apply, attacks, generate};
+
+// The four central squares d4, e4, d5 and e5
+const HILL: Bitboard = Bitboard::new(0x0000_0018_1800_0000);
+const CHECKS_TO_WIN: u8 = 3;
+
+// Variants whose moves follow the standard rules, apart from drops
+pub fn has_standard_moves(variant: Variant) -> bool {
+    matches!(
+        variant,
+        Variant::Standard | Variant::Crazyhouse | Variant::Bughouse
+    )
+}
+
+pub fn outcome(board: &Board) -> Option<GameResult> {
+    if let Some(winner) = winner(board) {
+        return Some(result(winner));
+    }
+    if generate::has_legal_move(board) {
+        return None;
+    }
+
+    let colour = *board.active_colour();
+    Some(match board.variant() {
+        // Running out of moves wins in Antichess
+        Variant::Antichess => result(colour),
+        _ if attacks::in_check(board, colour) => result(colour.opposite()),
+        _ => GameResult::Draw,
+    })
+}
+
+// A side which has won under the variant's own rules, whoever is to move
+pub fn winner(board: &Board) -> Option<PieceColour> {
+    let colours = [PieceColour::White, PieceColour::Black];
+    let king = |colour| board.bitboard(Piece::new(colour, PieceType::King));
+    match board.variant() {
+        Variant::ThreeCheck => colours
+            .into_iter()
+            .find(|&colour| board.checks(colour) >= CHECKS_TO_WIN),
+        Variant::KingOfTheHill => colours
+            .into_iter()
+            .find(|&colour| !(king(colour) & HILL).is_empty()),
+        Variant::Atomic => colours
+            .into_iter()
+            .find(|&colour| king(colour.opposite()).is_empty()),
+        Variant::Antichess => colours
+            .into_iter()
+            .find(|&colour| board.occupied_by(colour).is_empty()),
+        Variant::Horde => board
+            .occupied_by(PieceColour::White)
+            .is_empty()
+            .then_some(PieceColour::Black),
+        _ => None,
+    }
+}
+
+// Whether the side which just moved has left its king safe on the resulting board
+pub fn is_safe(next_board: &Board, colour: PieceColour) -> bool {
+    let king = |colour| {
+        next_board
+            .bitboard(Piece::new(colour, PieceType::King))
+            .first()
+    };
+    match next_board.variant() {
+        Variant::Antichess => true,
+        // Exploding the opposing king wins outright, and touching kings can't attack each other
+        Variant::Atomic => match (king(colour), king(colour.opposite())) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(own), Some(theirs)) => {
+                attacks::king(own).contains(theirs) || !attacks::in_check(next_board, colour)
+            }
+        },
+        _ => !attacks::in_check(next_board, colour),
+    }
+}
+
+// Captures are compulsory in Antichess, and kings can't capture in Atomic
+pub fn restrict(board: &Board, moves: Vec<Move>) -> Vec<Move> {
+    match board.variant() {
+        Variant::Antichess if moves.iter().any(|m| apply::is_capture(board, m)) => moves
+            .into_iter()
+            .filter(|movement| apply::is_capture(board, movement))
+            .collect(),
+        Variant::Atomic => moves
+            .into_iter()
+            .filter(|movement| {
+                *movement.piece().piece_type() != PieceType::King
+                    || !apply::is_capture(board, movement)
+            })
+            .collect(),
+        _ => moves,
+    }
+}
+
+// Atomic captures remove the capturing piece and every piece but pawns around the target square
+pub fn explode(board: &mut Board, movement: &Move) -> Vec<(Piece, Position)> {
+    let centre = movement.to();
+    let blast = (Bitboard::from_position(centre) | attacks::king(centre)) & board.occupied();
+    let mut exploded = Vec::new();
+    for position in blast.positions() {
+        let Some(&piece) = board.occupant(position) else {
+            continue;
+        };
+        if position == centre || *piece.piece_type() != PieceType::Pawn {
+            board.remove(position);
+            exploded.push((piece, position));
+        }
+    }
+    exploded
+}
+
+// Credits a Three-check check to the side which just moved
+pub fn count_check(board: &mut Board) {
+    let checked = *board.active_colour();
+    if board.variant() == Variant::ThreeCheck && attacks::in_check(board, checked) {
+        board.add_check(checked.opposite());
+    }
+}
+
+fn result(winner: PieceColour) -> GameResult {
+    match winner {
+        PieceColour::White => GameResult::WhiteWin,
+        PieceColour::Black => GameResult::BlackWin,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{execute_moves, legal_moves};
+    use crate::parse::{parse_fen, parse_movetext};
+
+    mod outcome_tests {
+        use super::*;
+
+        #[test]
+        fn wins_three_check_on_third_check() {
+            let boards = play(
+                Variant::ThreeCheck,
+                None,
+                "1. e4 e5 2. Bc4 Nc6 3. Bxf7+ Kxf7 4. Qh5+ Ke7 5. Qxe5+",
+            );
+            assert_eq!(boards[8].checks(PieceColour::White), 2);
+            assert_eq!(outcome(&boards[8]), None);
+            assert_eq!(outcome(boards.last().unwrap()), Some(GameResult::WhiteWin))
+        }
+
+        #[test]
+        fn wins_king_of_the_hill_in_centre() {
+            let boards = play(
+                Variant::KingOfTheHill,
+                Some("8/8/8/8/8/4K3/8/4k3 w - - 0 1"),
+                "1. Ke4",
+            );
+            assert_eq!(outcome(&boards[0]), None);
+            assert_eq!(outcome(&boards[1]), Some(GameResult::WhiteWin))
+        }
+
+        #[test]
+        fn wins_horde_by_capturing_every_pawn() {
+            let boards = play(
+                Variant::Horde,
+                Some("4k3/8/8/8/8/8/3P4/3r4 b - - 0 1"),
+                "1... Rxd2",
+            );
+            assert_eq!(outcome(&boards[1]), Some(GameResult::BlackWin))
+        }
+
+        #[test]
+        fn wins_antichess_by_losing_everything() {
+            let boards = play(
+                Variant::Antichess,
+                Some("8/8/8/8/8/8/1p6/R7 b - - 0 1"),
+                "1... bxa1=K",
+            );
+            assert_eq!(outcome(&boards[1]), Some(GameResult::WhiteWin))
+        }
+    }
+
+    mod legal_moves_tests {
+        use super::*;
+
+        #[test]
+        fn forces_captures_in_antichess() {
+            let board = board(
+                Variant::Antichess,
+                Some("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w - - 0 2"),
+            );
+            let moves = legal_moves(&board);
+            assert_eq!(moves.len(), 1);
+            assert_eq!(moves[0].to(), Position::new(4, 3))
+        }
+
+        #[test]
+        fn ignores_check_in_antichess() {
+            let board = board(Variant::Antichess, Some("4k3/8/8/8/8/8/8/R3K3 b - - 0 1"));
+            assert_eq!(legal_moves(&board).len(), 5)
+        }
+
+        #[test]
+        fn lets_horde_pawns_advance_two_from_first_rank() {
+            let board = board(Variant::Horde, Some("4k3/8/8/8/8/8/8/P7 w - - 0 1"));
+            assert!(legal_moves(&board)
+                .iter()
+                .any(|movement| movement.from() == Position::new(0, 0)
+                    && movement.to() == Position::new(2, 0)))
+        }
+    }
+
+    mod explode_tests {
+        use super::*;
+
+        #[test]
+        fn explodes_capture_sparing_pawns() {
+            let boards = play(
+                Variant::Atomic,
+                Some("4k3/8/8/3pnb2/8/8/8/3QK3 w - - 0 1"),
+                "1. Qxd5",
+            );
+            let after = &boards[1];
+            assert!(after.occupant(Position::new(4, 3)).is_none());
+            assert!(after.occupant(Position::new(4, 4)).is_none());
+            assert!(after.occupant(Position::new(4, 5)).is_some());
+            assert_eq!(after.occupied().count(), 3)
+        }
+
+        #[test]
+        fn wins_atomic_by_exploding_king() {
+            let boards = play(
+                Variant::Atomic,
+                Some("4k3/4p3/8/8/8/8/8/4RK2 w - - 0 1"),
+                "1. Rxe7",
+            );
+            assert!(boards[1]
+                .bitboard(Piece::new(PieceColour::Black, PieceType::King))
+                .is_empty());
+            assert_eq!(outcome(&boards[1]), Some(GameResult::WhiteWin))
+        }
+
+        #[test]
+        fn forbids_exploding_own_king() {
+            let board = board(Variant::Atomic, Some("4k3/8/8/8/8/8/3p4/3QK3 w - - 0 1"));
+            assert!(!legal_moves(&board)
+                .iter()
+                .any(|movement| movement.to() == Position::new(1, 3)))
+        }
+    }
+
+    fn board(variant: Variant, fen: Option<&str>) -> Board {
+        let fen = fen
+            .or(variant.starting_fen())
+            .unwrap_or(crate::parse::DEFAULT_FEN);
+        let mut board = parse_fen(fen).unwrap().starting_board().clone();
+        board.update_variant(variant);
+        board
+    }
+
+    fn play(variant: Variant, fen: Option<&str>, moves: &str) -> Vec<Board> {
+        execute_moves(&board(variant, fen), &parse_movetext(moves).unwrap()).unwrap()
+    }
+}
@@ -0,0 +1,110 @@
+use crate::model::{Board, Game};
+use crate::query::Query;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Match {
+    game: usize,
+    ply: usize,
+}
+
+impl Match {
+    pub fn new(game: usize, ply: usize) -> Self {
+        Match { game, ply }
+    }
+
+    // Index into the searched games
+    pub fn game(&self) -> usize {
+        self.game
+    }
+
+    // Index into the game's boards, so 0 is the starting position
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+}
+
+// The first time each game reaches the position, ignoring move clocks
+pub fn find_position(games: &[Game], board: &Board) -> Vec<Match> {
+    let hash = board.zobrist();
+    games
+        .iter()
+        .enumerate()
+        .filter_map(|(game_index, game)| {
+            game.boards()
+                .iter()
+                .position(|candidate| candidate.zobrist() == hash && candidate == board)
+                .map(|ply| Match::new(game_index, ply))
+        })
+        .collect()
+}
+
+// The first ply in each game where the query holds
+pub fn find_query(games: &[Game], query: &Query) -> Vec<Match> {
+    games
+        .iter()
+        .enumerate()
+        .filter_map(|(game_index, game)| {
+            let boards = game.boards();
+            (0..boards.len())
+                .find(|&ply| query.matches(boards, ply))
+                .map(|ply| Match::new(game_index, ply))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine;
+    use crate::parse::{parse, parse_fen};
+    use crate::query::parse_query;
+
+    #[test]
+    fn finds_first_ply_reaching_position() {
+        let games = games(&[
+            "1. e4 e5 2. Nf3 Nc6 3. Bb5 *",
+            "1. d4 d5 2. c4 *",
+            "1. Nf3 Nc6 2. e4 e5 3. Ng1 Nb8 4. Nf3 Nc6 *",
+        ]);
+        let board = parse_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3")
+            .unwrap()
+            .starting_board()
+            .clone();
+
+        assert_eq!(
+            find_position(&games, &board),
+            vec![Match::new(0, 4), Match::new(2, 4)]
+        )
+    }
+
+    #[test]
+    fn returns_empty_if_no_game_reaches_position() {
+        let games = games(&["1. e4 e5 *"]);
+        let board = parse_fen("8/8/8/4k3/8/8/8/4K3 w - - 0 1")
+            .unwrap()
+            .starting_board()
+            .clone();
+
+        assert!(find_position(&games, &board).is_empty())
+    }
+
+    #[test]
+    fn finds_first_ply_matching_query() {
+        let games = games(&["1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7# *", "1. d4 d5 *"]);
+        let query = parse_query("check").unwrap();
+
+        assert_eq!(find_query(&games, &query), vec![Match::new(0, 7)])
+    }
+
+    fn games(movetexts: &[&str]) -> Vec<Game> {
+        movetexts
+            .iter()
+            .map(|movetext| {
+                let mut pgns = parse(&format!("[Result \"*\"]\n\n{movetext}\n")).unwrap();
+                let pgn = pgns.remove(0);
+                let boards = engine::execute_moves(pgn.fen().starting_board(), pgn.ply()).unwrap();
+                Game::new(pgn, boards)
+            })
+            .collect()
+    }
+}
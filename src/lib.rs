@@ -1,12 +1,45 @@
-mod cli;
+mod annotate;
+mod clock;
+mod diagram;
 mod engine;
+mod find;
 mod model;
+mod opening;
 mod parse;
+mod query;
+#[cfg(feature = "serde")]
+mod serialize;
+mod structure;
+mod svg;
+mod uci;
+#[cfg(feature = "tui")]
 mod ui;
+mod write;
 
-pub use cli::pigin;
-pub use engine::execute_moves;
-pub use model::Game;
-pub use model::Pgn;
-pub use parse::parse;
-pub use ui::launch;
+pub use annotate::{annotate, annotated_pgn};
+pub use clock::{format_duration, time_usage, PlyTime, Summary, TimeUsage};
+pub use diagram::{diagram, DiagramOptions, Glyphs};
+pub use engine::{
+    attackers, checkers, control_map, divide, evaluate, execute_moves, from_uci, is_attacked,
+    legal_moves, make_move, motifs, outcome, perft, pinned_pieces, resolve, san, san_line, search,
+    uci, unmake_move, value, Candidate, ControlMap, EngineError, Limit, Motif, MoveContext,
+    Rejection, Score, SearchResult, Tactic, Undo,
+};
+pub use find::{find_position, find_query, Match};
+pub use model::{
+    AvailableCastle, Bitboard, Board, Check, Fen, Game, GameResult, InvalidPositionError, Move,
+    MoveQualifier, Movement, Period, Pgn, PgnDate, Piece, PieceColour, PieceType, Ply, PlyMovement,
+    Pocket, Position, Positions, Round, Tags, TimeControl, Variant,
+};
+pub use opening::{classify, Opening};
+pub use parse::{
+    parse, parse_fen, parse_movetext, parse_piece, parse_position, parse_result, parse_san,
+    PgnParseError, DEFAULT_FEN,
+};
+pub use query::{parse_query, Place, Query, QueryError};
+pub use structure::{analyse, Formation, Pawns, Structure};
+pub use svg::{ply_svg, svg, Arrow, MarkColour, Markup, SquareMark, SvgOptions};
+pub use uci::{Info, UciEngine, UciError};
+#[cfg(feature = "tui")]
+pub use ui::{launch, UiError};
+pub use write::{write_annotated, write_canonical, write_line, write_pgn, Annotation};
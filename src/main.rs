@@ -1,11 +1,46 @@
-use std::{error::Error, fs};
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
 
-use pigin::{execute_moves, launch, parse, pigin, Game, Pgn};
+mod cli;
+
+use clap::ArgMatches;
+use cli::pigin;
+use pigin::{
+    annotated_pgn, diagram, divide, execute_moves, find_query, launch, parse, parse_fen,
+    parse_query, perft, ply_svg, uci, write_canonical, DiagramOptions, Game, Glyphs, Pgn,
+    PieceColour, SvgOptions, UciEngine,
+};
 
 type PgnsResult = Result<Vec<Pgn>, Box<dyn Error>>;
 
-fn main() -> Result<(), Box<dyn Error>> {
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
     let matches = pigin().get_matches();
+    match matches.subcommand() {
+        Some(("perft", perft_matches)) => run_perft(perft_matches),
+        Some(("annotate", annotate_matches)) => run_annotate(annotate_matches),
+        Some(("search", search_matches)) => run_search(search_matches),
+        Some(("fmt", fmt_matches)) => run_fmt(fmt_matches),
+        Some(("show", show_matches)) => run_show(show_matches),
+        Some(("export", export_matches)) => run_export(export_matches),
+        _ => visualise(&matches),
+    }
+}
+
+fn visualise(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
     let file_names: Vec<&String> = matches
         .get_many("file")
         .ok_or("'file' argument not provided")?
@@ -17,22 +52,269 @@ fn main() -> Result<(), Box<dyn Error>> {
         .reduce(reduce)
         .unwrap_or_else(|| Ok(Vec::new()))?;
 
-    let games = pgns
+    let mut games = pgns
+        .into_iter()
+        .enumerate()
+        .map(|(index, pgn)| game_from(index + 1, pgn))
+        .collect::<Result<Vec<Game>, Box<dyn Error>>>()?;
+
+    if let Some(query) = matches.get_one::<String>("opening") {
+        games.retain(|game| game.opening().is_some_and(|opening| opening.matches(query)));
+        if games.is_empty() {
+            return Err(format!("No games match opening '{query}'").into());
+        }
+    }
+
+    let engine = matches
+        .get_one::<PathBuf>("engine")
+        .map(UciEngine::spawn)
+        .transpose()?;
+
+    launch(games, engine)?;
+    Ok(())
+}
+
+fn run_perft(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let depth = *matches
+        .get_one::<usize>("depth")
+        .ok_or("'depth' argument not provided")?;
+    let fen = matches
+        .get_one::<String>("fen")
+        .ok_or("'fen' argument not provided")?;
+    let board = parse_fen(fen)?.starting_board().clone();
+
+    if matches.get_flag("divide") {
+        let divided = divide(&board, depth);
+        for (movement, nodes) in &divided {
+            println!("{}: {nodes}", uci(movement));
+        }
+        let total: usize = divided.iter().map(|(_, nodes)| nodes).sum();
+        println!("\nNodes searched: {total}");
+    } else {
+        println!("{}", perft(&board, depth));
+    }
+    Ok(())
+}
+
+fn run_annotate(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let input = matches
+        .get_one::<String>("input")
+        .ok_or("'input' argument not provided")?;
+    let engine_path = matches
+        .get_one::<PathBuf>("engine")
+        .ok_or("'engine' argument not provided")?;
+    let depth = *matches
+        .get_one::<usize>("depth")
+        .ok_or("'depth' argument not provided")?;
+
+    let games = pgns_from(input)?
+        .into_iter()
+        .enumerate()
+        .map(|(index, pgn)| game_from(index + 1, pgn))
+        .collect::<Result<Vec<Game>, Box<dyn Error>>>()?;
+
+    let mut engine = UciEngine::spawn(engine_path)?;
+    let mut annotated = Vec::new();
+    for (index, game) in games.iter().enumerate() {
+        annotated.push(annotated_pgn(game, &mut engine, depth)?);
+        eprintln!("Annotated game {} of {}", index + 1, games.len());
+    }
+    let annotated = annotated.join("\n");
+
+    match matches.get_one::<PathBuf>("output") {
+        Some(output) => fs::write(output, annotated)?,
+        None => print!("{annotated}"),
+    }
+    Ok(())
+}
+
+fn run_search(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let query = matches
+        .get_one::<String>("query")
+        .ok_or("'query' argument not provided")?;
+    let query = parse_query(query)?;
+    let file_names: Vec<&String> = matches
+        .get_many("input")
+        .ok_or("'input' argument not provided")?
+        .collect();
+
+    let games = file_names
         .into_iter()
-        .map(game_from)
+        .map(|file_name| pgns_from(file_name))
+        .reduce(reduce)
+        .unwrap_or_else(|| Ok(Vec::new()))?
+        .into_iter()
+        .enumerate()
+        .map(|(index, pgn)| game_from(index + 1, pgn))
         .collect::<Result<Vec<Game>, Box<dyn Error>>>()?;
 
-    launch(games)?;
+    let matches = find_query(&games, &query);
+    for found in &matches {
+        let tags = games[found.game()].pgn().tags();
+        println!(
+            "Game {}: {} vs {} (ply {})",
+            found.game() + 1,
+            tags.get_or_default("White", "?"),
+            tags.get_or_default("Black", "?"),
+            found.ply()
+        );
+    }
+    eprintln!("{} of {} games matched", matches.len(), games.len());
     Ok(())
 }
 
+fn run_fmt(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let file_names: Vec<&String> = matches
+        .get_many("input")
+        .ok_or("'input' argument not provided")?
+        .collect();
+    let check = matches.get_flag("check");
+
+    let mut unformatted = 0;
+    for file_name in file_names {
+        let content = fs::read_to_string(file_name)?;
+        let formatted = parse(&content)?
+            .into_iter()
+            .enumerate()
+            .map(|(index, pgn)| game_from(index + 1, pgn).map(|game| write_canonical(&game)))
+            .collect::<Result<Vec<String>, Box<dyn Error>>>()
+            .map_err(|e| format!("{file_name}: {e}"))?
+            .join("\n");
+        if formatted == content {
+            continue;
+        }
+        if check {
+            println!("{file_name}");
+            unformatted += 1;
+        } else {
+            fs::write(file_name, formatted)?;
+            eprintln!("Formatted {file_name}");
+        }
+    }
+
+    if unformatted > 0 {
+        return Err(format!("{unformatted} file(s) would be reformatted").into());
+    }
+    Ok(())
+}
+
+fn run_show(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let input = matches
+        .get_one::<String>("input")
+        .ok_or("'input' argument not provided")?;
+    let game_number = *matches
+        .get_one::<usize>("game")
+        .ok_or("'game' argument not provided")?;
+
+    let game = numbered_game(input, game_number)?;
+
+    let last = game.boards().len() - 1;
+    let ply = matches.get_one::<usize>("ply").copied().unwrap_or(last);
+    let board = game
+        .boards()
+        .get(ply)
+        .ok_or_else(|| format!("Ply {ply} not found; game {game_number} has {last} ply"))?;
+
+    let perspective = if matches.get_flag("flip") {
+        PieceColour::Black
+    } else {
+        PieceColour::White
+    };
+    let glyphs = if matches.get_flag("unicode") {
+        Glyphs::Unicode
+    } else {
+        Glyphs::Ascii
+    };
+    let options = DiagramOptions::default()
+        .glyphs(glyphs)
+        .perspective(perspective)
+        .coordinates(!matches.get_flag("no-coordinates"))
+        .colour(matches.get_flag("colour"));
+    print!("{}", diagram(board, options));
+    Ok(())
+}
+
+fn run_export(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    match matches.subcommand() {
+        Some(("svg", svg_matches)) => run_export_svg(svg_matches),
+        _ => Err("No export format given".into()),
+    }
+}
+
+fn run_export_svg(matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let input = matches
+        .get_one::<String>("input")
+        .ok_or("'input' argument not provided")?;
+    let game_number = *matches
+        .get_one::<usize>("game")
+        .ok_or("'game' argument not provided")?;
+    let output = matches
+        .get_one::<PathBuf>("output")
+        .ok_or("'output' argument not provided")?;
+    let size = *matches
+        .get_one::<u32>("size")
+        .ok_or("'size' argument not provided")?;
+
+    let game = numbered_game(input, game_number)?;
+    let last = game.boards().len() - 1;
+    let plies: Vec<usize> = match matches.get_many::<usize>("ply") {
+        Some(plies) => plies.copied().collect(),
+        None => (0..=last).collect(),
+    };
+
+    let perspective = if matches.get_flag("flip") {
+        PieceColour::Black
+    } else {
+        PieceColour::White
+    };
+    let mut options = SvgOptions::default()
+        .square_size(size)
+        .perspective(perspective)
+        .coordinates(!matches.get_flag("no-coordinates"));
+    if let Some(light) = matches.get_one::<String>("light") {
+        options = options.light(light);
+    }
+    if let Some(dark) = matches.get_one::<String>("dark") {
+        options = options.dark(dark);
+    }
+    if let Some(highlight) = matches.get_one::<String>("highlight") {
+        options = options.highlight(highlight);
+    }
+
+    let stem = Path::new(input)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("game");
+    fs::create_dir_all(output)?;
+    for ply in plies {
+        let svg = ply_svg(&game, ply, &options)
+            .ok_or_else(|| format!("Ply {ply} not found; game {game_number} has {last} ply"))?;
+        let path = output.join(format!("{stem}-{game_number}-{ply:03}.svg"));
+        fs::write(&path, svg)?;
+        eprintln!("Wrote {}", path.display());
+    }
+    Ok(())
+}
+
+// The game with a number counting from 1, with its moves played out
+fn numbered_game(file_name: &str, game_number: usize) -> Result<Game, Box<dyn Error>> {
+    let pgns = pgns_from(file_name)?;
+    let count = pgns.len();
+    let pgn = pgns
+        .into_iter()
+        .nth(game_number.wrapping_sub(1))
+        .ok_or_else(|| format!("Game {game_number} not found; {file_name} has {count} game(s)"))?;
+    game_from(game_number, pgn)
+}
+
 fn pgns_from(file_name: &str) -> PgnsResult {
     let file = fs::read_to_string(file_name)?;
     parse(&file).map_err(|err| err.into())
 }
 
-fn game_from(pgn: Pgn) -> Result<Game, Box<dyn Error>> {
-    let boards = execute_moves(pgn.fen().starting_board(), pgn.ply())?;
+fn game_from(game_number: usize, pgn: Pgn) -> Result<Game, Box<dyn Error>> {
+    let boards =
+        execute_moves(pgn.fen().starting_board(), pgn.ply()).map_err(|e| e.in_game(game_number))?;
     Ok(Game::new(pgn, boards))
 }
 
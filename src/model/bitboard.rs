@@ -0,0 +1,152 @@
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use super::Position;
+
+/// A set of squares, bit `row * 8 + col` for each. Returned by the engine's attack functions;
+/// its methods are unstable and may change between releases.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash)]
+pub struct Bitboard(u64);
+
+impl Bitboard {
+    pub const EMPTY: Bitboard = Bitboard(0);
+
+    pub const fn new(bits: u64) -> Self {
+        Bitboard(bits)
+    }
+
+    pub fn from_position(position: Position) -> Self {
+        Bitboard(1 << position.index())
+    }
+
+    pub const fn bits(&self) -> u64 {
+        self.0
+    }
+
+    pub fn contains(&self, position: Position) -> bool {
+        self.0 & (1 << position.index()) != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn count(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub fn first(&self) -> Option<Position> {
+        (!self.is_empty()).then(|| Position::from_index(self.0.trailing_zeros() as usize))
+    }
+
+    pub fn last(&self) -> Option<Position> {
+        (!self.is_empty()).then(|| Position::from_index(63 - self.0.leading_zeros() as usize))
+    }
+
+    pub fn positions(self) -> Positions {
+        Positions(self.0)
+    }
+}
+
+/// The squares of a `Bitboard`, from a1 to h8. Unstable, as `Bitboard` is.
+pub struct Positions(u64);
+
+impl Iterator for Positions {
+    type Item = Position;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 == 0 {
+            return None;
+        }
+        let index = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+        Some(Position::from_index(index))
+    }
+}
+
+impl BitAnd for Bitboard {
+    type Output = Bitboard;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 & rhs.0)
+    }
+}
+
+impl BitAndAssign for Bitboard {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl BitOr for Bitboard {
+    type Output = Bitboard;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Bitboard {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitXor for Bitboard {
+    type Output = Bitboard;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Bitboard(self.0 ^ rhs.0)
+    }
+}
+
+impl BitXorAssign for Bitboard {
+    fn bitxor_assign(&mut self, rhs: Self) {
+        self.0 ^= rhs.0;
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Bitboard;
+
+    fn not(self) -> Self::Output {
+        Bitboard(!self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iterates_positions_in_square_order() {
+        let bitboard = Bitboard::from_position(Position::new(7, 7))
+            | Bitboard::from_position(Position::new(0, 3))
+            | Bitboard::from_position(Position::new(2, 1));
+        let positions: Vec<Position> = bitboard.positions().collect();
+        assert_eq!(
+            positions,
+            vec![
+                Position::new(0, 3),
+                Position::new(2, 1),
+                Position::new(7, 7)
+            ]
+        )
+    }
+
+    #[test]
+    fn finds_first_and_last_positions() {
+        let bitboard = Bitboard::from_position(Position::new(1, 1))
+            | Bitboard::from_position(Position::new(5, 0));
+        assert_eq!(bitboard.first(), Some(Position::new(1, 1)));
+        assert_eq!(bitboard.last(), Some(Position::new(5, 0)));
+        assert_eq!(Bitboard::EMPTY.first(), None);
+    }
+
+    #[test]
+    fn checks_membership() {
+        let bitboard = Bitboard::from_position(Position::new(3, 4));
+        assert!(bitboard.contains(Position::new(3, 4)));
+        assert!(!bitboard.contains(Position::new(4, 3)));
+        assert_eq!(bitboard.count(), 1);
+    }
+}
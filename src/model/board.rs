@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
 
-use super::{Piece, PieceColour, Position};
+use super::{fen, zobrist, Bitboard, Piece, PieceColour, PieceType, Pocket, Position, Variant};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum AvailableCastle {
@@ -10,19 +11,35 @@ pub enum AvailableCastle {
     BlackQueenside,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+const SQUARES: usize = 64;
+const CASTLES: usize = 4;
+
+#[derive(Debug, Clone)]
 pub struct Board {
-    grid: HashMap<Position, Piece>,
+    squares: [Option<Piece>; SQUARES],
+    colours: [Bitboard; 2],
+    piece_types: [Bitboard; 6],
     active_colour: PieceColour,
-    available_castles: Vec<AvailableCastle>,
+    available_castles: [AvailableCastle; CASTLES],
+    available_castles_count: usize,
     en_passant_square: Option<Position>,
     halfmove_clock: usize,
     fullmove_clock: usize,
+    // Only in drop variants
+    pockets: Option<[Pocket; 2]>,
+    // Bughouse: pieces are dropped from the partner's captures, which the game doesn't record
+    partnered: bool,
+    // Pieces which were pawns, and go back into the pocket as pawns when captured
+    promoted: Bitboard,
+    variant: Variant,
+    // Checks given by each side, for Three-check
+    checks: [u8; 2],
+    hash: u64,
 }
 
 // TODO: add tests
 impl Board {
-    pub fn builder() -> Builder {
+    pub(crate) fn builder() -> Builder {
         Builder::new()
     }
 
@@ -31,7 +48,7 @@ impl Board {
     }
 
     pub fn available_castles(&self) -> &[AvailableCastle] {
-        &self.available_castles
+        &self.available_castles[..self.available_castles_count]
     }
 
     pub fn en_passant_square(&self) -> Option<&Position> {
@@ -46,117 +63,571 @@ impl Board {
         self.fullmove_clock
     }
 
+    pub fn pocket(&self, colour: PieceColour) -> Option<&Pocket> {
+        self.pockets
+            .as_ref()
+            .map(|pockets| &pockets[colour.index()])
+    }
+
+    pub fn pockets(&self) -> Option<[Pocket; 2]> {
+        self.pockets
+    }
+
+    pub fn is_partnered(&self) -> bool {
+        self.partnered
+    }
+
+    pub fn promoted(&self) -> Bitboard {
+        self.promoted
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    pub fn checks(&self, colour: PieceColour) -> u8 {
+        self.checks[colour.index()]
+    }
+
+    pub fn fen(&self) -> String {
+        fen::from_board(self)
+    }
+
+    pub fn zobrist(&self) -> u64 {
+        self.capturable_en_passant_square()
+            .map_or(self.hash, |position| {
+                self.hash ^ zobrist::en_passant(position)
+            })
+    }
+
+    // Only counts towards position identity if a pawn is placed to make the capture
+    pub fn capturable_en_passant_square(&self) -> Option<Position> {
+        let position = self.en_passant_square?;
+        let row = match self.active_colour {
+            PieceColour::White => position.row() - 1,
+            PieceColour::Black => position.row() + 1,
+        };
+        let pawn = Piece::new(self.active_colour, PieceType::Pawn);
+
+        [position.col() - 1, position.col() + 1]
+            .into_iter()
+            .filter_map(|col| Position::try_from(row, col).ok())
+            .any(|adjacent| self.occupant(adjacent) == Some(&pawn))
+            .then_some(position)
+    }
+
     pub fn occupant(&self, position: Position) -> Option<&Piece> {
-        self.grid.get(&position)
+        self.squares[position.index()].as_ref()
+    }
+
+    pub fn occupied(&self) -> Bitboard {
+        self.colours[0] | self.colours[1]
+    }
+
+    pub fn occupied_by(&self, colour: PieceColour) -> Bitboard {
+        self.colours[colour.index()]
+    }
+
+    pub fn bitboard(&self, piece: Piece) -> Bitboard {
+        self.colours[piece.colour().index()] & self.piece_types[piece.piece_type().index()]
     }
 
-    // TODO: review implementation
     pub fn search(&self, piece: Piece) -> Vec<Position> {
-        self.grid
-            .iter()
-            .filter_map(|(&key, &val)| if val == piece { Some(key) } else { None })
-            .collect()
+        self.bitboard(piece).positions().collect()
     }
 
-    pub fn add(&mut self, piece: Piece, position: Position) {
-        self.grid.insert(position, piece);
+    pub(crate) fn add(&mut self, piece: Piece, position: Position) {
+        self.remove(position);
+
+        let bit = Bitboard::from_position(position);
+        self.squares[position.index()] = Some(piece);
+        self.colours[piece.colour().index()] |= bit;
+        self.piece_types[piece.piece_type().index()] |= bit;
+        self.hash ^= zobrist::piece(piece, position);
     }
 
-    pub fn remove(&mut self, position: Position) {
-        self.grid.remove(&position);
+    pub(crate) fn remove(&mut self, position: Position) {
+        if let Some(previous) = self.squares[position.index()].take() {
+            let bit = Bitboard::from_position(position);
+            self.colours[previous.colour().index()] ^= bit;
+            self.piece_types[previous.piece_type().index()] ^= bit;
+            self.promoted &= !bit;
+            self.hash ^= zobrist::piece(previous, position);
+        }
     }
 
-    pub fn update_active_colour(&mut self, active_colour: PieceColour) {
+    // In drop variants the capturing side pockets the piece, demoted to a pawn if it was promoted
+    pub(crate) fn capture(&mut self, position: Position) {
+        if let Some(&captured) = self.occupant(position) {
+            if !self.partnered {
+                let piece_type = if self.promoted.contains(position) {
+                    PieceType::Pawn
+                } else {
+                    *captured.piece_type()
+                };
+                self.add_to_pocket(Piece::new(captured.colour().opposite(), piece_type));
+            }
+        }
+        self.remove(position);
+    }
+
+    pub(crate) fn enable_pockets(&mut self, partnered: bool) {
+        self.pockets.get_or_insert_with(Default::default);
+        self.partnered = partnered;
+    }
+
+    pub(crate) fn add_to_pocket(&mut self, piece: Piece) {
+        let (colour, piece_type) = (*piece.colour(), *piece.piece_type());
+        let added = self
+            .pockets
+            .as_mut()
+            .and_then(|pockets| pockets[colour.index()].add(piece_type));
+        if let Some(count) = added {
+            self.hash ^= zobrist::pocket(colour, piece_type, count);
+        }
+    }
+
+    // Whether the piece could be taken; partners can always supply one
+    pub(crate) fn remove_from_pocket(&mut self, piece: Piece) -> bool {
+        let (colour, piece_type) = (*piece.colour(), *piece.piece_type());
+        let removed = self
+            .pockets
+            .as_mut()
+            .and_then(|pockets| pockets[colour.index()].remove(piece_type));
+        match removed {
+            Some(count) => {
+                self.hash ^= zobrist::pocket(colour, piece_type, count);
+                true
+            }
+            None => self.pockets.is_some() && self.partnered,
+        }
+    }
+
+    pub(crate) fn update_pockets(&mut self, pockets: [Pocket; 2], promoted: Bitboard) {
+        for colour in [PieceColour::White, PieceColour::Black] {
+            let current = self.pocket(colour).copied().unwrap_or_default();
+            let target = pockets[colour.index()];
+            for (piece_type, _) in current.pieces() {
+                for _ in 0..current.count(piece_type) {
+                    self.remove_from_pocket(Piece::new(colour, piece_type));
+                }
+            }
+            for (piece_type, count) in target.pieces() {
+                for _ in 0..count {
+                    self.add_to_pocket(Piece::new(colour, piece_type));
+                }
+            }
+        }
+        self.promoted = promoted & self.occupied();
+    }
+
+    // Only tracked where captures are pocketed
+    pub(crate) fn mark_promoted(&mut self, position: Position) {
+        if self.pockets.is_some() && self.occupant(position).is_some() {
+            self.promoted |= Bitboard::from_position(position);
+        }
+    }
+
+    pub(crate) fn update_active_colour(&mut self, active_colour: PieceColour) {
+        if self.active_colour != active_colour {
+            self.hash ^= zobrist::black_to_move();
+        }
         self.active_colour = active_colour;
     }
 
-    pub fn remove_available_castle(&mut self, available_castle: AvailableCastle) {
+    pub(crate) fn remove_available_castle(&mut self, available_castle: AvailableCastle) {
         let index = self
-            .available_castles
+            .available_castles()
             .iter()
             .position(|x| x == &available_castle);
         if let Some(index) = index {
-            self.available_castles.remove(index);
+            self.available_castles
+                .copy_within(index + 1..self.available_castles_count, index);
+            self.available_castles_count -= 1;
+            self.hash ^= zobrist::castle(available_castle);
+        }
+    }
+
+    pub(crate) fn update_available_castles(&mut self, available_castles: &[AvailableCastle]) {
+        for &castle in &self.available_castles[..self.available_castles_count] {
+            self.hash ^= zobrist::castle(castle);
+        }
+        self.available_castles_count = 0;
+
+        for &castle in available_castles {
+            if !self.available_castles().contains(&castle) {
+                self.available_castles[self.available_castles_count] = castle;
+                self.available_castles_count += 1;
+                self.hash ^= zobrist::castle(castle);
+            }
         }
     }
 
-    pub fn remove_en_passant_square(&mut self) {
+    pub(crate) fn remove_en_passant_square(&mut self) {
         self.en_passant_square.take();
     }
 
-    pub fn update_en_passant_square(&mut self, position: Position) {
+    pub(crate) fn update_en_passant_square(&mut self, position: Position) {
         self.en_passant_square.replace(position);
     }
 
-    pub fn update_halfmove_clock(&mut self, halfmove_clock: usize) {
+    pub(crate) fn update_variant(&mut self, variant: Variant) {
+        self.variant = variant;
+        if variant.has_pockets() {
+            self.enable_pockets(variant == Variant::Bughouse);
+        }
+    }
+
+    pub(crate) fn add_check(&mut self, colour: PieceColour) {
+        self.checks[colour.index()] += 1;
+    }
+
+    pub(crate) fn update_checks(&mut self, white: u8, black: u8) {
+        self.checks = [white, black];
+    }
+
+    pub(crate) fn update_halfmove_clock(&mut self, halfmove_clock: usize) {
         self.halfmove_clock = halfmove_clock;
     }
 
-    pub fn update_fullmove_clock(&mut self, fullmove_clock: usize) {
+    pub(crate) fn update_fullmove_clock(&mut self, fullmove_clock: usize) {
         self.fullmove_clock = fullmove_clock;
     }
 }
 
-pub struct Builder {
-    grid: HashMap<Position, Piece>,
+pub(crate) struct Builder {
+    squares: [Option<Piece>; SQUARES],
     active_colour: PieceColour,
     available_castles: Vec<AvailableCastle>,
     en_passant_square: Option<Position>,
     halfmove_clock: usize,
     fullmove_clock: usize,
+    pockets: Option<[Pocket; 2]>,
+    partnered: bool,
+    promoted: Bitboard,
 }
 
 // TODO: add tests
 impl Builder {
     fn new() -> Self {
         Builder {
-            grid: HashMap::new(),
+            squares: [None; SQUARES],
             active_colour: PieceColour::White,
             available_castles: Vec::new(),
             en_passant_square: None,
             halfmove_clock: 0,
             fullmove_clock: 1,
+            pockets: None,
+            partnered: false,
+            promoted: Bitboard::EMPTY,
         }
     }
 
-    pub fn piece(&mut self, piece: Piece, position: Position) -> &mut Builder {
-        self.grid.insert(position, piece);
+    pub(crate) fn piece(&mut self, piece: Piece, position: Position) -> &mut Builder {
+        self.squares[position.index()] = Some(piece);
         self
     }
 
-    pub fn active_colour(&mut self, active_colour: PieceColour) -> &mut Builder {
+    pub(crate) fn active_colour(&mut self, active_colour: PieceColour) -> &mut Builder {
         self.active_colour = active_colour;
         self
     }
 
-    pub fn available_castles(&mut self, available_castles: Vec<AvailableCastle>) -> &mut Builder {
+    pub(crate) fn available_castles(
+        &mut self,
+        available_castles: Vec<AvailableCastle>,
+    ) -> &mut Builder {
         self.available_castles = available_castles;
         self
     }
 
-    pub fn en_passant_square(&mut self, position: Position) -> &mut Builder {
+    pub(crate) fn en_passant_square(&mut self, position: Position) -> &mut Builder {
         let _result = self.en_passant_square.insert(position);
         self
     }
 
-    pub fn halfmove_clock(&mut self, halfmove_clock: usize) -> &mut Builder {
+    pub(crate) fn halfmove_clock(&mut self, halfmove_clock: usize) -> &mut Builder {
         self.halfmove_clock = halfmove_clock;
         self
     }
 
-    pub fn fullmove_clock(&mut self, fullmove_clock: usize) -> &mut Builder {
+    pub(crate) fn fullmove_clock(&mut self, fullmove_clock: usize) -> &mut Builder {
         self.fullmove_clock = fullmove_clock;
         self
     }
 
-    pub fn build(self) -> Board {
-        Board {
-            grid: self.grid,
-            active_colour: self.active_colour,
-            available_castles: self.available_castles,
+    pub(crate) fn pockets(&mut self, partnered: bool) -> &mut Builder {
+        self.pockets.get_or_insert_with(Default::default);
+        self.partnered = partnered;
+        self
+    }
+
+    pub(crate) fn pocket_piece(&mut self, piece: Piece) -> &mut Builder {
+        let pockets = self.pockets.get_or_insert_with(Default::default);
+        pockets[piece.colour().index()].add(*piece.piece_type());
+        self
+    }
+
+    pub(crate) fn promoted(&mut self, position: Position) -> &mut Builder {
+        self.promoted |= Bitboard::from_position(position);
+        self
+    }
+
+    pub(crate) fn build(self) -> Board {
+        let mut board = Board {
+            squares: [None; SQUARES],
+            colours: [Bitboard::EMPTY; 2],
+            piece_types: [Bitboard::EMPTY; 6],
+            active_colour: PieceColour::White,
+            available_castles: [AvailableCastle::WhiteKingside; CASTLES],
+            available_castles_count: 0,
             en_passant_square: self.en_passant_square,
             halfmove_clock: self.halfmove_clock,
             fullmove_clock: self.fullmove_clock,
+            pockets: None,
+            partnered: false,
+            promoted: Bitboard::EMPTY,
+            variant: Variant::Standard,
+            checks: [0; 2],
+            hash: 0,
+        };
+
+        for (index, piece) in self.squares.into_iter().enumerate() {
+            if let Some(piece) = piece {
+                board.add(piece, Position::from_index(index));
+            }
+        }
+
+        board.update_active_colour(self.active_colour);
+        board.update_available_castles(&self.available_castles);
+        if let Some(pockets) = self.pockets {
+            board.enable_pockets(self.partnered);
+            board.update_pockets(pockets, self.promoted);
         }
+
+        board
+    }
+}
+
+// Positions are equal if they would count as a repetition: clocks are ignored, as is an en
+// passant square which cannot be captured on
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.zobrist() == other.zobrist()
+            && self.active_colour == other.active_colour
+            && self.squares == other.squares
+            && self.available_castles_count == other.available_castles_count
+            && self
+                .available_castles()
+                .iter()
+                .all(|castle| other.available_castles().contains(castle))
+            && self.capturable_en_passant_square() == other.capturable_en_passant_square()
+            && self.pockets == other.pockets
+            && self.variant == other.variant
+            && self.checks == other.checks
+    }
+}
+
+impl Eq for Board {}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.fen())
+    }
+}
+
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.zobrist());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_positions_have_equal_hashes() {
+        let mut moved = board(vec![AvailableCastle::WhiteKingside]);
+        moved.remove(Position::new(0, 6));
+        moved.add(white_knight(), Position::new(2, 5));
+        moved.update_active_colour(PieceColour::Black);
+
+        let mut builder = Board::builder();
+        builder
+            .piece(white_knight(), Position::new(2, 5))
+            .available_castles(vec![AvailableCastle::WhiteKingside])
+            .active_colour(PieceColour::Black);
+        let built = builder.build();
+
+        assert_eq!(moved.zobrist(), built.zobrist());
+        assert_eq!(moved, built);
+    }
+
+    #[test]
+    fn keeps_bitboards_in_step_with_squares() {
+        let mut board = board(vec![]);
+        board.add(white_knight(), Position::new(4, 4));
+        board.add(
+            Piece::new(PieceColour::Black, PieceType::Queen),
+            Position::new(0, 6),
+        );
+
+        assert_eq!(board.search(white_knight()), vec![Position::new(4, 4)]);
+        assert_eq!(
+            board.occupied_by(PieceColour::Black),
+            Bitboard::from_position(Position::new(0, 6))
+        );
+        assert_eq!(board.occupied().count(), 2);
+
+        board.remove(Position::new(4, 4));
+        assert!(board.bitboard(white_knight()).is_empty());
+        assert!(board.occupant(Position::new(4, 4)).is_none());
+    }
+
+    #[test]
+    fn removes_available_castle_preserving_order() {
+        let mut board = board(vec![
+            AvailableCastle::WhiteKingside,
+            AvailableCastle::WhiteQueenside,
+            AvailableCastle::BlackQueenside,
+        ]);
+        board.remove_available_castle(AvailableCastle::WhiteQueenside);
+        assert_eq!(
+            board.available_castles(),
+            &[
+                AvailableCastle::WhiteKingside,
+                AvailableCastle::BlackQueenside
+            ]
+        );
+    }
+
+    #[test]
+    fn replaces_available_castles() {
+        let mut board = board(vec![AvailableCastle::WhiteKingside]);
+        board.update_available_castles(&[
+            AvailableCastle::BlackQueenside,
+            AvailableCastle::WhiteQueenside,
+        ]);
+        assert_eq!(
+            board.available_castles(),
+            &[
+                AvailableCastle::BlackQueenside,
+                AvailableCastle::WhiteQueenside
+            ]
+        );
+        assert_eq!(
+            board.zobrist(),
+            self::board(vec![
+                AvailableCastle::WhiteQueenside,
+                AvailableCastle::BlackQueenside
+            ])
+            .zobrist()
+        );
+    }
+
+    #[test]
+    fn ignores_clocks() {
+        let mut other = board(vec![]);
+        other.update_halfmove_clock(12);
+        other.update_fullmove_clock(40);
+        assert_eq!(board(vec![]), other)
+    }
+
+    #[test]
+    fn ignores_castle_order() {
+        let castles = vec![
+            AvailableCastle::WhiteKingside,
+            AvailableCastle::BlackQueenside,
+        ];
+        let reversed = castles.iter().rev().copied().collect();
+        assert_eq!(board(castles), board(reversed))
+    }
+
+    #[test]
+    fn distinguishes_castling_rights() {
+        let mut other = board(vec![AvailableCastle::WhiteKingside]);
+        other.remove_available_castle(AvailableCastle::WhiteKingside);
+        assert_ne!(board(vec![AvailableCastle::WhiteKingside]), other);
+        assert_eq!(board(vec![]), other);
+    }
+
+    #[test]
+    fn distinguishes_active_colour() {
+        let mut other = board(vec![]);
+        other.update_active_colour(PieceColour::Black);
+        assert_ne!(board(vec![]).zobrist(), other.zobrist());
+        assert_ne!(board(vec![]), other);
+    }
+
+    #[test]
+    fn ignores_en_passant_square_which_cannot_be_captured() {
+        let mut other = board(vec![]);
+        other.update_en_passant_square(Position::new(5, 3));
+        assert_eq!(board(vec![]), other)
+    }
+
+    #[test]
+    fn distinguishes_en_passant_square_which_can_be_captured() {
+        let mut without = board(vec![]);
+        without.add(
+            Piece::new(PieceColour::White, PieceType::Pawn),
+            Position::new(4, 4),
+        );
+        let mut with = without.clone();
+        with.update_en_passant_square(Position::new(5, 3));
+
+        assert_eq!(
+            with.capturable_en_passant_square(),
+            Some(Position::new(5, 3))
+        );
+        assert_ne!(with.zobrist(), without.zobrist());
+        assert_ne!(with, without);
+    }
+
+    #[test]
+    fn distinguishes_pockets() {
+        let mut with = board(vec![]);
+        with.enable_pockets(false);
+        let without = with.clone();
+        with.add_to_pocket(white_knight());
+        assert_ne!(with.zobrist(), without.zobrist());
+        assert_ne!(with, without);
+
+        assert!(with.remove_from_pocket(white_knight()));
+        assert!(!with.remove_from_pocket(white_knight()));
+        assert_eq!(with.zobrist(), without.zobrist());
+        assert_eq!(with, without)
+    }
+
+    #[test]
+    fn pockets_captured_piece_demoting_promoted_ones() {
+        let black_queen = Piece::new(PieceColour::Black, PieceType::Queen);
+        let mut board = board(vec![]);
+        board.enable_pockets(false);
+        board.add(black_queen, Position::new(4, 4));
+        board.add(black_queen, Position::new(5, 5));
+        board.mark_promoted(Position::new(5, 5));
+
+        board.capture(Position::new(4, 4));
+        board.capture(Position::new(5, 5));
+        let pocket = board.pocket(PieceColour::White).unwrap();
+        assert_eq!(
+            pocket.pieces(),
+            vec![(PieceType::Queen, 1), (PieceType::Pawn, 1)]
+        );
+        assert!(board.promoted().is_empty())
+    }
+
+    fn white_knight() -> Piece {
+        Piece::new(PieceColour::White, PieceType::Knight)
+    }
+
+    fn board(available_castles: Vec<AvailableCastle>) -> Board {
+        let mut builder = Board::builder();
+        builder
+            .piece(white_knight(), Position::new(0, 6))
+            .available_castles(available_castles);
+        builder.build()
     }
 }
@@ -0,0 +1,135 @@
+use std::fmt::Display;
+
+// A `Date` tag such as "2023.08.13", where any part may be unknown ("2023.??.??");
+// unknown parts sort before known ones
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub struct PgnDate {
+    year: Option<u16>,
+    month: Option<u8>,
+    day: Option<u8>,
+}
+
+impl PgnDate {
+    pub fn new(year: Option<u16>, month: Option<u8>, day: Option<u8>) -> Self {
+        PgnDate { year, month, day }
+    }
+
+    pub fn from_tag(tag: &str) -> Option<PgnDate> {
+        let mut parts = tag.trim().split('.');
+        let year = part(parts.next()?, 4)?;
+        let month = part(parts.next()?, 2)?;
+        let day = part(parts.next()?, 2)?;
+        if parts.next().is_some() {
+            return None;
+        }
+
+        let date = PgnDate {
+            year: year.map(u16::try_from).transpose().ok()?,
+            month: month.map(u8::try_from).transpose().ok()?,
+            day: day.map(u8::try_from).transpose().ok()?,
+        };
+        let valid = date.month.is_none_or(|month| (1..=12).contains(&month))
+            && date.day.is_none_or(|day| (1..=31).contains(&day));
+        valid.then_some(date)
+    }
+
+    pub fn year(&self) -> Option<u16> {
+        self.year
+    }
+
+    pub fn month(&self) -> Option<u8> {
+        self.month
+    }
+
+    pub fn day(&self) -> Option<u8> {
+        self.day
+    }
+}
+
+// Some(None) for an unknown part written as question marks
+fn part(text: &str, width: usize) -> Option<Option<u32>> {
+    if text.len() != width {
+        return None;
+    }
+    if text.chars().all(|c| c == '?') {
+        return Some(None);
+    }
+    if !text.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok().map(Some)
+}
+
+impl Display for PgnDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.year {
+            Some(year) => write!(f, "{year:04}")?,
+            None => write!(f, "????")?,
+        }
+        for part in [self.month, self.day] {
+            match part {
+                Some(part) => write!(f, ".{part:02}")?,
+                None => write!(f, ".??")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod from_tag_tests {
+        use super::*;
+
+        #[test]
+        fn parses_full_and_partial_dates() {
+            assert_eq!(
+                PgnDate::from_tag("2023.08.13"),
+                Some(PgnDate::new(Some(2023), Some(8), Some(13)))
+            );
+            assert_eq!(
+                PgnDate::from_tag("2023.??.??"),
+                Some(PgnDate::new(Some(2023), None, None))
+            );
+            assert_eq!(
+                PgnDate::from_tag("????.??.??"),
+                Some(PgnDate::new(None, None, None))
+            )
+        }
+
+        #[test]
+        fn returns_none_if_malformed() {
+            assert_eq!(PgnDate::from_tag("2023-08-13"), None);
+            assert_eq!(PgnDate::from_tag("2023.13.01"), None);
+            assert_eq!(PgnDate::from_tag("23.08.13"), None);
+            assert_eq!(PgnDate::from_tag("2023.08.13.01"), None)
+        }
+
+        #[test]
+        fn round_trips_display() {
+            for tag in ["2023.08.13", "2023.??.??", "????.??.??"] {
+                assert_eq!(PgnDate::from_tag(tag).unwrap().to_string(), tag)
+            }
+        }
+    }
+
+    mod ord_tests {
+        use super::*;
+
+        #[test]
+        fn sorts_chronologically_with_unknown_parts_first() {
+            let mut dates: Vec<PgnDate> = ["2023.08.13", "2021.03.05", "2023.??.??", "2023.08.02"]
+                .into_iter()
+                .filter_map(PgnDate::from_tag)
+                .collect();
+            dates.sort();
+            let sorted: Vec<String> = dates.iter().map(ToString::to_string).collect();
+            assert_eq!(
+                sorted,
+                vec!["2021.03.05", "2023.??.??", "2023.08.02", "2023.08.13"]
+            )
+        }
+    }
+}
@@ -1,9 +1,9 @@
-use crate::model::AvailableCastle;
-use crate::model::{Board, Piece, PieceColour, PieceType, Position, MAX_POSITION, MIN_POSITION};
-use crate::ui::UiError;
+use super::{
+    AvailableCastle, Board, Piece, PieceColour, PieceType, Position, MAX_POSITION, MIN_POSITION,
+};
 
-pub fn from_board(board: &Board) -> Result<String, UiError> {
-    let pieces = pieces(board)?;
+pub fn from_board(board: &Board) -> String {
+    let pieces = pieces(board);
     let active_colour = match board.active_colour() {
         PieceColour::White => "w",
         PieceColour::Black => "b",
@@ -13,15 +13,31 @@ pub fn from_board(board: &Board) -> Result<String, UiError> {
         .en_passant_square()
         .map_or("-".to_string(), ToString::to_string);
 
-    Ok(format!(
-        "{} {} {} {} {} {}",
+    let pockets = board.pockets().map_or(String::new(), |pockets| {
+        let held: String = [PieceColour::White, PieceColour::Black]
+            .into_iter()
+            .flat_map(|colour| {
+                pockets[colour.index()]
+                    .pieces()
+                    .into_iter()
+                    .flat_map(move |(piece_type, count)| {
+                        std::iter::repeat_n(to_char(&Piece::new(colour, piece_type)), count.into())
+                    })
+            })
+            .collect();
+        format!("[{held}]")
+    });
+
+    format!(
+        "{}{} {} {} {} {} {}",
         pieces,
+        pockets,
         active_colour,
         castling_availability,
         en_passent_square,
         board.halfmove_clock(),
         board.fullmove_clock()
-    ))
+    )
 }
 
 fn castling_availability(available_castles: &[AvailableCastle]) -> String {
@@ -39,7 +55,7 @@ fn castling_availability(available_castles: &[AvailableCastle]) -> String {
         .collect()
 }
 
-fn pieces(board: &Board) -> Result<String, UiError> {
+fn pieces(board: &Board) -> String {
     let mut chars: Vec<char> = Vec::new();
     for row in (MIN_POSITION..=MAX_POSITION).rev() {
         let mut empty_columns = 0;
@@ -47,22 +63,17 @@ fn pieces(board: &Board) -> Result<String, UiError> {
             let position = Position::new(row, col);
             if let Some(piece) = board.occupant(position) {
                 if empty_columns > 0 {
-                    chars.push(char::from_digit(empty_columns, 10).ok_or_else(|| {
-                        UiError::new(
-                            "Failed to parse char from empty columns in FEN string".to_string(),
-                        )
-                    })?);
+                    chars.push(empty(empty_columns));
                     empty_columns = 0;
                 }
                 chars.push(to_char(piece));
+                if board.promoted().contains(position) {
+                    chars.push('~');
+                }
             } else {
                 empty_columns += 1;
                 if col == MAX_POSITION {
-                    chars.push(char::from_digit(empty_columns, 10).ok_or_else(|| {
-                        UiError::new(
-                            "Failed to parse char from empty columns in FEN string".to_string(),
-                        )
-                    })?);
+                    chars.push(empty(empty_columns));
                 }
             }
         }
@@ -71,10 +82,14 @@ fn pieces(board: &Board) -> Result<String, UiError> {
             chars.push('/');
         }
     }
-    Ok(chars.into_iter().collect())
+    chars.into_iter().collect()
+}
+
+fn empty(columns: u8) -> char {
+    char::from(b'0' + columns)
 }
 
-fn to_char(piece: &Piece) -> char {
+pub fn to_char(piece: &Piece) -> char {
     match (piece.colour(), piece.piece_type()) {
         (PieceColour::Black, PieceType::Pawn) => 'p',
         (PieceColour::Black, PieceType::Knight) => 'n',
@@ -113,6 +128,28 @@ mod tests {
         }
     }
 
+    mod from_board_tests {
+        use super::*;
+
+        #[test]
+        fn adds_pockets_and_promoted_pieces() {
+            let mut builder = Board::builder();
+            builder
+                .piece(
+                    Piece::new(PieceColour::White, PieceType::Queen),
+                    Position::new(7, 3),
+                )
+                .promoted(Position::new(7, 3))
+                .pocket_piece(Piece::new(PieceColour::Black, PieceType::Pawn))
+                .pocket_piece(Piece::new(PieceColour::White, PieceType::Knight));
+
+            assert_eq!(
+                from_board(&builder.build()),
+                "3Q~4/8/8/8/8/8/8/8[Np] w - - 0 1"
+            )
+        }
+    }
+
     mod pieces_tests {
         use super::*;
 
@@ -129,7 +166,7 @@ mod tests {
             );
 
             let board = builder.build();
-            assert_eq!(pieces(&board).unwrap(), "5b2/8/8/8/3K4/8/8/8".to_string())
+            assert_eq!(pieces(&board), "5b2/8/8/8/3K4/8/8/8".to_string())
         }
     }
 }
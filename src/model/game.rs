@@ -1,13 +1,29 @@
-use super::{Board, Pgn};
+use crate::clock::{self, TimeUsage};
+use crate::engine;
+use crate::opening::{self, Opening};
+
+use super::{Board, Pgn, Variant};
 
 pub struct Game {
     pgn: Pgn,
     boards: Vec<Board>,
+    opening: Option<&'static Opening>,
+    san: Vec<String>,
+    time_usage: TimeUsage,
 }
 
 impl Game {
     pub fn new(pgn: Pgn, boards: Vec<Board>) -> Self {
-        Game { pgn, boards }
+        let opening = opening::classify(&boards);
+        let san = san(&pgn, &boards);
+        let time_usage = clock::time_usage(&pgn);
+        Game {
+            pgn,
+            boards,
+            opening,
+            san,
+            time_usage,
+        }
     }
 
     pub fn boards(&self) -> &[Board] {
@@ -17,4 +33,55 @@ impl Game {
     pub fn pgn(&self) -> &Pgn {
         &self.pgn
     }
+
+    pub fn opening(&self) -> Option<&'static Opening> {
+        self.opening
+    }
+
+    // Canonical SAN for each ply, computed once so it needn't be on every redraw
+    pub fn san(&self) -> &[String] {
+        &self.san
+    }
+
+    // Parsed from the clock comments once, rather than on every redraw
+    pub fn time_usage(&self) -> &TimeUsage {
+        &self.time_usage
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.pgn.fen().starting_board().variant()
+    }
+}
+
+fn san(pgn: &Pgn, boards: &[Board]) -> Vec<String> {
+    pgn.ply()
+        .iter()
+        .zip(boards)
+        .map(|(ply, board)| {
+            engine::resolve(board, ply.movement()).map_or_else(
+                |_| ply.movement().to_string(),
+                |movement| engine::san(board, &movement),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+
+    mod san_tests {
+        use super::*;
+
+        #[test]
+        fn computes_canonical_san_for_each_ply() {
+            let pgn = parse("[Result \"*\"]\n\n1. e4 e5 2. Ngf3 Nc6 3. Bb5 a6 *\n")
+                .unwrap()
+                .remove(0);
+            let boards = engine::execute_moves(pgn.fen().starting_board(), pgn.ply()).unwrap();
+            let game = Game::new(pgn, boards);
+            assert_eq!(game.san(), ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6"])
+        }
+    }
 }
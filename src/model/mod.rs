@@ -1,13 +1,27 @@
+mod bitboard;
 mod board;
+mod date;
+mod fen;
 mod game;
 mod pgn;
 mod piece;
 mod ply;
+mod pocket;
 mod position;
+mod round;
+mod time_control;
+mod variant;
+mod zobrist;
 
+pub use bitboard::{Bitboard, Positions};
 pub use board::{AvailableCastle, Board};
+pub use date::PgnDate;
 pub use game::Game;
 pub use pgn::{Fen, GameResult, Pgn, Tags};
 pub use piece::{Piece, PieceColour, PieceType};
-pub use ply::{Check, MoveQualifier, Movement, Ply, PlyMovement};
-pub use position::{Position, COLUMNS, MAX_POSITION, MIN_POSITION, ROWS};
+pub use ply::{Check, Move, MoveQualifier, Movement, Ply, PlyMovement};
+pub use pocket::Pocket;
+pub use position::{InvalidPositionError, Position, COLUMNS, MAX_POSITION, MIN_POSITION, ROWS};
+pub use round::Round;
+pub use time_control::{Period, TimeControl};
+pub use variant::Variant;
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
+use std::fmt::Display;
 
-use super::{board::Board, PieceColour, Ply};
+use super::{board::Board, PgnDate, PieceColour, Ply, Round, TimeControl, Variant};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum GameResult {
@@ -10,6 +11,17 @@ pub enum GameResult {
     Ongoing,
 }
 
+impl Display for GameResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameResult::WhiteWin => write!(f, "1-0"),
+            GameResult::BlackWin => write!(f, "0-1"),
+            GameResult::Draw => write!(f, "1/2-1/2"),
+            GameResult::Ongoing => write!(f, "*"),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Fen {
     starting_board: Board,
@@ -30,6 +42,10 @@ impl Fen {
         &self.starting_board
     }
 
+    pub(crate) fn update_variant(&mut self, variant: Variant) {
+        self.starting_board.update_variant(variant);
+    }
+
     pub fn active_colour(&self) -> &PieceColour {
         &self.active_colour
     }
@@ -63,6 +79,27 @@ impl Tags {
     pub fn inner(&self) -> &HashMap<String, String> {
         &self.0
     }
+
+    pub fn date(&self) -> Option<PgnDate> {
+        self.get("Date").and_then(|date| PgnDate::from_tag(date))
+    }
+
+    pub fn elo(&self, colour: PieceColour) -> Option<u16> {
+        let key = match colour {
+            PieceColour::White => "WhiteElo",
+            PieceColour::Black => "BlackElo",
+        };
+        self.get(key).and_then(|elo| elo.trim().parse().ok())
+    }
+
+    pub fn time_control(&self) -> Option<TimeControl> {
+        self.get("TimeControl")
+            .and_then(|time_control| TimeControl::from_tag(time_control))
+    }
+
+    pub fn round(&self) -> Option<Round> {
+        self.get("Round").and_then(|round| Round::from_tag(round))
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -99,3 +136,39 @@ impl Pgn {
         &self.ply
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod tags_tests {
+        use super::*;
+
+        #[test]
+        fn reads_typed_tags() {
+            let tags = Tags::new(HashMap::from([
+                ("Date".to_string(), "2023.??.??".to_string()),
+                ("WhiteElo".to_string(), "1292".to_string()),
+                ("BlackElo".to_string(), "?".to_string()),
+                ("TimeControl".to_string(), "300+2".to_string()),
+                ("Round".to_string(), "4.2".to_string()),
+            ]));
+            assert_eq!(tags.date(), Some(PgnDate::new(Some(2023), None, None)));
+            assert_eq!(tags.elo(PieceColour::White), Some(1292));
+            assert_eq!(tags.elo(PieceColour::Black), None);
+            assert_eq!(
+                tags.time_control().map(|control| control.to_string()),
+                Some("300+2".to_string())
+            );
+            assert_eq!(tags.round(), Some(Round::new(vec![4, 2])))
+        }
+
+        #[test]
+        fn returns_none_if_tag_missing() {
+            let tags = Tags::new(HashMap::new());
+            assert_eq!(tags.date(), None);
+            assert_eq!(tags.time_control(), None);
+            assert_eq!(tags.round(), None)
+        }
+    }
+}
@@ -1,3 +1,7 @@
+use std::fmt::Display;
+
+use super::fen;
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum PieceColour {
     Black,
@@ -14,6 +18,35 @@ pub enum PieceType {
     King,
 }
 
+impl PieceColour {
+    pub fn opposite(&self) -> PieceColour {
+        match self {
+            PieceColour::White => PieceColour::Black,
+            PieceColour::Black => PieceColour::White,
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        match self {
+            PieceColour::White => 0,
+            PieceColour::Black => 1,
+        }
+    }
+}
+
+impl PieceType {
+    pub fn index(&self) -> usize {
+        match self {
+            PieceType::Pawn => 0,
+            PieceType::Knight => 1,
+            PieceType::Bishop => 2,
+            PieceType::Rook => 3,
+            PieceType::Queen => 4,
+            PieceType::King => 5,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct Piece {
     colour: PieceColour,
@@ -36,3 +69,10 @@ impl Piece {
         &self.piece_type
     }
 }
+
+// The FEN letter: upper case for white, lower case for black
+impl Display for Piece {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", fen::to_char(self))
+    }
+}
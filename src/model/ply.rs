@@ -1,7 +1,9 @@
+use std::fmt::Display;
+
 use crate::model::piece::PieceType;
 use crate::model::position::Position;
 
-use super::{Piece, PieceColour};
+use super::{Piece, PieceColour, COLUMNS, ROWS};
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct Movement {
@@ -23,6 +25,64 @@ impl Movement {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Move {
+    piece: Piece,
+    from: Position,
+    to: Position,
+    promotes_to: Option<PieceType>,
+    drop: bool,
+}
+
+impl Move {
+    pub fn new(piece: Piece, from: Position, to: Position, promotes_to: Option<PieceType>) -> Self {
+        Move {
+            piece,
+            from,
+            to,
+            promotes_to,
+            drop: false,
+        }
+    }
+
+    // A piece placed from the pocket; it comes from and goes to the same square
+    pub fn drop(piece: Piece, to: Position) -> Self {
+        Move {
+            piece,
+            from: to,
+            to,
+            promotes_to: None,
+            drop: true,
+        }
+    }
+
+    pub fn piece(&self) -> Piece {
+        self.piece
+    }
+
+    pub fn from(&self) -> Position {
+        self.from
+    }
+
+    pub fn to(&self) -> Position {
+        self.to
+    }
+
+    pub fn promotes_to(&self) -> Option<PieceType> {
+        self.promotes_to
+    }
+
+    pub fn is_castle(&self) -> bool {
+        !self.drop
+            && *self.piece.piece_type() == PieceType::King
+            && (self.to.col() - self.from.col()).abs() == 2
+    }
+
+    pub fn is_drop(&self) -> bool {
+        self.drop
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum MoveQualifier {
     Row(i8),
@@ -59,6 +119,120 @@ pub enum PlyMovement {
         check: Option<Check>,
         capture: bool,
     },
+    // Crazyhouse and Bughouse: a piece from the pocket, as in "N@f3"
+    Drop {
+        movement: Movement,
+        check: Option<Check>,
+    },
+}
+
+impl PlyMovement {
+    pub fn colour(&self) -> PieceColour {
+        match self {
+            PlyMovement::KingsideCastle { colour, .. }
+            | PlyMovement::QueensideCastle { colour, .. } => *colour,
+            PlyMovement::Move { movement, .. }
+            | PlyMovement::Promotion { movement, .. }
+            | PlyMovement::Drop { movement, .. } => *movement.piece().colour(),
+        }
+    }
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Check::Check => "+".to_string(),
+                Check::Checkmate => "#".to_string(),
+            }
+        )
+    }
+}
+
+impl Display for MoveQualifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            MoveQualifier::Col(col) => {
+                let col = usize::try_from(*col).map_err(|_| std::fmt::Error)?;
+                COLUMNS.chars().nth(col).ok_or(std::fmt::Error)?.to_string()
+            }
+            MoveQualifier::Row(row) => {
+                let row = usize::try_from(*row).map_err(|_| std::fmt::Error)?;
+                ROWS.chars().nth(row).ok_or(std::fmt::Error)?.to_string()
+            }
+            MoveQualifier::Position(position) => position.to_string(),
+        };
+        write!(f, "{text}")
+    }
+}
+
+impl Display for PlyMovement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (text, check) = match self {
+            PlyMovement::KingsideCastle { colour: _, check } => ("O-O".to_string(), check),
+            PlyMovement::QueensideCastle { colour: _, check } => ("O-O-O".to_string(), check),
+            PlyMovement::Move {
+                movement,
+                qualifier,
+                check,
+                capture,
+            } => (
+                format_move(movement, qualifier.as_ref(), *capture, None),
+                check,
+            ),
+            PlyMovement::Promotion {
+                movement,
+                promotes_to,
+                qualifier,
+                check,
+                capture,
+            } => (
+                format_move(movement, qualifier.as_ref(), *capture, Some(*promotes_to)),
+                check,
+            ),
+            PlyMovement::Drop { movement, check } => {
+                let letter = match *movement.piece().piece_type() {
+                    PieceType::Pawn => "P",
+                    other => piece_letter(other),
+                };
+                (format!("{letter}@{}", movement.position()), check)
+            }
+        };
+        let check = check.as_ref().map_or(String::new(), ToString::to_string);
+
+        write!(f, "{text}{check}")
+    }
+}
+
+fn format_move(
+    movement: &Movement,
+    qualifier: Option<&MoveQualifier>,
+    capture: bool,
+    promotes_to: Option<PieceType>,
+) -> String {
+    let qualifier = qualifier.map_or(String::new(), ToString::to_string);
+    let capture = if capture { "x" } else { "" };
+    let promotion = promotes_to.map_or(String::new(), |piece_type| {
+        format!("={}", piece_letter(piece_type))
+    });
+    format!(
+        "{}{qualifier}{capture}{}{promotion}",
+        piece_letter(*movement.piece().piece_type()),
+        movement.position()
+    )
+}
+
+fn piece_letter(piece_type: PieceType) -> &'static str {
+    match piece_type {
+        PieceType::Pawn => "",
+        PieceType::Knight => "N",
+        PieceType::Bishop => "B",
+        PieceType::Rook => "R",
+        PieceType::Queen => "Q",
+        PieceType::King => "K",
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -66,6 +240,8 @@ pub struct Ply {
     move_number: i16,
     movement: PlyMovement,
     comment: Option<String>,
+    nags: Vec<u8>,
+    variations: Vec<Vec<Ply>>,
 }
 
 impl Ply {
@@ -74,9 +250,18 @@ impl Ply {
             move_number,
             movement: ply,
             comment,
+            nags: Vec::new(),
+            variations: Vec::new(),
         }
     }
 
+    // Each variation is an alternative line starting in place of this ply
+    pub fn with_annotations(mut self, nags: Vec<u8>, variations: Vec<Vec<Ply>>) -> Self {
+        self.nags = nags;
+        self.variations = variations;
+        self
+    }
+
     pub fn move_number(&self) -> i16 {
         self.move_number
     }
@@ -88,4 +273,31 @@ impl Ply {
     pub fn comment(&self) -> Option<&String> {
         self.comment.as_ref()
     }
+
+    // Numeric annotation glyphs, as in `$1`
+    pub fn nags(&self) -> &[u8] {
+        &self.nags
+    }
+
+    pub fn variations(&self) -> &[Vec<Ply>] {
+        &self.variations
+    }
+
+    // The argument of an embedded command such as `[%clk 0:04:58]` in the comment
+    pub fn command(&self, name: &str) -> Option<&str> {
+        let comment = self.comment.as_deref()?;
+        let start = comment.find(&format!("[%{name} "))? + name.len() + 3;
+        let end = start + comment[start..].find(']')?;
+        Some(comment[start..end].trim())
+    }
+}
+
+// "1.e4" for white, "e5" for black
+impl Display for Ply {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.movement.colour() {
+            PieceColour::White => write!(f, "{}.{}", self.move_number, self.movement),
+            PieceColour::Black => write!(f, "{}", self.movement),
+        }
+    }
 }
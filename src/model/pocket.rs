@@ -0,0 +1,87 @@
+use super::PieceType;
+
+// Kings are never captured, so only the other five types can be held
+const POCKET_TYPES: [PieceType; 5] = [
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+    PieceType::Pawn,
+];
+
+// Pieces captured by a side in drop variants, waiting to be put back on the board
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Pocket {
+    counts: [u8; 5],
+}
+
+impl Pocket {
+    pub fn count(&self, piece_type: PieceType) -> u8 {
+        match piece_type {
+            PieceType::King => 0,
+            other => self.counts[other.index()],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.iter().all(|&count| count == 0)
+    }
+
+    // Most valuable first, with a count for each type held
+    pub fn pieces(&self) -> Vec<(PieceType, u8)> {
+        POCKET_TYPES
+            .into_iter()
+            .map(|piece_type| (piece_type, self.count(piece_type)))
+            .filter(|&(_, count)| count > 0)
+            .collect()
+    }
+
+    pub(super) fn add(&mut self, piece_type: PieceType) -> Option<u8> {
+        if piece_type == PieceType::King {
+            return None;
+        }
+        let count = &mut self.counts[piece_type.index()];
+        *count = count.saturating_add(1);
+        Some(*count)
+    }
+
+    // The count before removal, if there was one to remove
+    pub(super) fn remove(&mut self, piece_type: PieceType) -> Option<u8> {
+        if self.count(piece_type) == 0 {
+            return None;
+        }
+        let count = &mut self.counts[piece_type.index()];
+        *count -= 1;
+        Some(*count + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod pieces_tests {
+        use super::*;
+
+        #[test]
+        fn lists_held_pieces_most_valuable_first() {
+            let mut pocket = Pocket::default();
+            pocket.add(PieceType::Pawn);
+            pocket.add(PieceType::Knight);
+            pocket.add(PieceType::Pawn);
+            assert_eq!(
+                pocket.pieces(),
+                vec![(PieceType::Knight, 1), (PieceType::Pawn, 2)]
+            );
+            assert!(!pocket.is_empty())
+        }
+
+        #[test]
+        fn never_holds_kings() {
+            let mut pocket = Pocket::default();
+            assert_eq!(pocket.add(PieceType::King), None);
+            assert_eq!(pocket.remove(PieceType::Knight), None);
+            assert!(pocket.is_empty())
+        }
+    }
+}
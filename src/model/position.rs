@@ -52,6 +52,31 @@ impl Position {
     pub fn col(&self) -> i8 {
         self.col
     }
+
+    pub fn index(&self) -> usize {
+        usize::from(self.row.unsigned_abs()) * 8 + usize::from(self.col.unsigned_abs())
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        let row = i8::try_from(index / 8).unwrap_or(i8::MAX);
+        let col = i8::try_from(index % 8).unwrap_or(i8::MAX);
+        Position::new(row, col)
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let row = usize::try_from(self.row())
+            .ok()
+            .and_then(|row| ROWS.chars().nth(row))
+            .ok_or(std::fmt::Error)?;
+        let col = usize::try_from(self.col())
+            .ok()
+            .and_then(|col| COLUMNS.chars().nth(col))
+            .ok_or(std::fmt::Error)?;
+
+        write!(f, "{col}{row}")
+    }
 }
 
 fn is_invalid(row: i8, col: i8) -> bool {
@@ -68,6 +93,13 @@ mod tests {
         assert_eq!(position, Ok(Position { row: 1, col: 1 }))
     }
 
+    #[test]
+    fn converts_to_and_from_index() {
+        let position = Position::new(3, 5);
+        assert_eq!(position.index(), 29);
+        assert_eq!(Position::from_index(29), position);
+    }
+
     #[test]
     fn returns_error_if_position_is_invalid() {
         let row_below_minimum = Position::try_from(-1, 3);
@@ -0,0 +1,60 @@
+use std::fmt::Display;
+
+// A `Round` tag such as "3" or "3.1", compared part by part so that "2" sorts before "10"
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
+pub struct Round(Vec<u32>);
+
+impl Round {
+    pub fn new(parts: Vec<u32>) -> Self {
+        Round(parts)
+    }
+
+    // "?" and "-" mark unknown and inapplicable rounds, so give None
+    pub fn from_tag(tag: &str) -> Option<Round> {
+        tag.trim()
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<Vec<u32>>>()
+            .map(Round)
+    }
+
+    pub fn parts(&self) -> &[u32] {
+        &self.0
+    }
+}
+
+impl Display for Round {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = self.0.iter().map(ToString::to_string).collect();
+        write!(f, "{}", parts.join("."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod from_tag_tests {
+        use super::*;
+
+        #[test]
+        fn parses_dotted_rounds() {
+            assert_eq!(Round::from_tag("7"), Some(Round::new(vec![7])));
+            assert_eq!(Round::from_tag("3.12"), Some(Round::new(vec![3, 12])));
+            assert_eq!(Round::from_tag("?"), None);
+            assert_eq!(Round::from_tag("-"), None);
+            assert_eq!(Round::from_tag("3."), None)
+        }
+
+        #[test]
+        fn sorts_numerically() {
+            let mut rounds: Vec<Round> = ["10", "2.1", "2", "1.10", "1.9"]
+                .into_iter()
+                .filter_map(Round::from_tag)
+                .collect();
+            rounds.sort();
+            let sorted: Vec<String> = rounds.iter().map(ToString::to_string).collect();
+            assert_eq!(sorted, vec!["1.9", "1.10", "2", "2.1", "10"])
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Period {
+    // Moves to be made within the period, or None for the rest of the game
+    moves: Option<u32>,
+    base: Duration,
+    increment: Duration,
+}
+
+impl Period {
+    pub fn new(moves: Option<u32>, base: Duration, increment: Duration) -> Self {
+        Period {
+            moves,
+            base,
+            increment,
+        }
+    }
+
+    pub fn moves(&self) -> Option<u32> {
+        self.moves
+    }
+
+    pub fn base(&self) -> Duration {
+        self.base
+    }
+
+    pub fn increment(&self) -> Duration {
+        self.increment
+    }
+}
+
+impl Display for Period {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(moves) = self.moves {
+            write!(f, "{moves}/")?;
+        }
+        write!(f, "{}", self.base.as_secs())?;
+        if !self.increment.is_zero() {
+            write!(f, "+{}", self.increment.as_secs())?;
+        }
+        Ok(())
+    }
+}
+
+// A `TimeControl` tag such as "40/7200:3600", "300+2", "1/86400" or "*60"
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum TimeControl {
+    Unknown,
+    Untimed,
+    // A sandclock which runs down for one side as it fills for the other
+    Sandclock(Duration),
+    Periods(Vec<Period>),
+}
+
+impl TimeControl {
+    pub fn from_tag(tag: &str) -> Option<TimeControl> {
+        let tag = tag.trim();
+        match tag {
+            "?" => return Some(TimeControl::Unknown),
+            "-" => return Some(TimeControl::Untimed),
+            _ => {}
+        }
+        if let Some(sandclock) = tag.strip_prefix('*') {
+            return seconds(sandclock).map(TimeControl::Sandclock);
+        }
+        tag.split(':')
+            .map(period)
+            .collect::<Option<Vec<Period>>>()
+            .map(TimeControl::Periods)
+    }
+
+    pub fn periods(&self) -> &[Period] {
+        match self {
+            TimeControl::Periods(periods) => periods,
+            _ => &[],
+        }
+    }
+
+    // Time on each clock at the start of the game
+    pub fn initial(&self) -> Option<Duration> {
+        match self {
+            TimeControl::Sandclock(duration) => Some(*duration),
+            TimeControl::Periods(periods) => periods.first().map(Period::base),
+            _ => None,
+        }
+    }
+
+    // Time added to a side's clock after its nth move: the increment, plus the next period's
+    // base time when the move completes a period. A final period with a move count repeats.
+    pub fn added_after(&self, move_count: u32) -> Duration {
+        let periods = self.periods();
+        let mut remaining = move_count;
+        for (idx, period) in periods.iter().enumerate() {
+            let Some(moves) = period.moves.filter(|&moves| moves > 0) else {
+                return period.increment;
+            };
+            let (completes, next) = match periods.get(idx + 1) {
+                None => (remaining % moves == 0, period),
+                Some(_) if remaining > moves => {
+                    remaining -= moves;
+                    continue;
+                }
+                Some(next) => (remaining == moves, next),
+            };
+            return period.increment + completes.then_some(next.base).unwrap_or_default();
+        }
+        Duration::ZERO
+    }
+}
+
+impl Display for TimeControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeControl::Unknown => write!(f, "?"),
+            TimeControl::Untimed => write!(f, "-"),
+            TimeControl::Sandclock(duration) => write!(f, "*{}", duration.as_secs()),
+            TimeControl::Periods(periods) => {
+                let periods: Vec<String> = periods.iter().map(ToString::to_string).collect();
+                write!(f, "{}", periods.join(":"))
+            }
+        }
+    }
+}
+
+// "moves/seconds", "seconds+increment" or "seconds"
+fn period(text: &str) -> Option<Period> {
+    let (moves, text) = match text.split_once('/') {
+        Some((moves, text)) => (Some(moves.parse().ok()?), text),
+        None => (None, text),
+    };
+    let (base, increment) = match text.split_once('+') {
+        Some((base, increment)) => (seconds(base)?, seconds(increment)?),
+        None => (seconds(text)?, Duration::ZERO),
+    };
+    Some(Period::new(moves, base, increment))
+}
+
+fn seconds(text: &str) -> Option<Duration> {
+    text.parse().ok().map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod from_tag_tests {
+        use super::*;
+
+        #[test]
+        fn parses_periods() {
+            assert_eq!(
+                TimeControl::from_tag("40/7200:3600"),
+                Some(TimeControl::Periods(vec![
+                    Period::new(Some(40), Duration::from_secs(7200), Duration::ZERO),
+                    Period::new(None, Duration::from_secs(3600), Duration::ZERO)
+                ]))
+            );
+            assert_eq!(
+                TimeControl::from_tag("300+2"),
+                Some(TimeControl::Periods(vec![Period::new(
+                    None,
+                    Duration::from_secs(300),
+                    Duration::from_secs(2)
+                )]))
+            );
+            assert_eq!(
+                TimeControl::from_tag("1/86400").unwrap().initial(),
+                Some(Duration::from_secs(86400))
+            )
+        }
+
+        #[test]
+        fn parses_special_values() {
+            assert_eq!(TimeControl::from_tag("?"), Some(TimeControl::Unknown));
+            assert_eq!(TimeControl::from_tag("-"), Some(TimeControl::Untimed));
+            assert_eq!(
+                TimeControl::from_tag("*60"),
+                Some(TimeControl::Sandclock(Duration::from_secs(60)))
+            );
+            assert_eq!(TimeControl::from_tag("5 min"), None)
+        }
+
+        #[test]
+        fn round_trips_display() {
+            for tag in ["40/7200:3600", "300+2", "1/86400", "*60", "-", "?"] {
+                assert_eq!(TimeControl::from_tag(tag).unwrap().to_string(), tag)
+            }
+        }
+    }
+
+    mod added_after_tests {
+        use super::*;
+
+        #[test]
+        fn adds_increment_and_next_period() {
+            let control = TimeControl::from_tag("40/7200+30:3600+30").unwrap();
+            assert_eq!(control.added_after(39), Duration::from_secs(30));
+            assert_eq!(control.added_after(40), Duration::from_secs(3630));
+            assert_eq!(control.added_after(80), Duration::from_secs(30))
+        }
+
+        #[test]
+        fn repeats_final_period_with_move_count() {
+            let control = TimeControl::from_tag("1/86400").unwrap();
+            assert_eq!(control.added_after(1), Duration::from_secs(86400));
+            assert_eq!(control.added_after(7), Duration::from_secs(86400))
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Variant {
+    #[default]
+    Standard,
+    Crazyhouse,
+    Bughouse,
+    ThreeCheck,
+    KingOfTheHill,
+    Atomic,
+    Antichess,
+    Horde,
+}
+
+impl Variant {
+    // Names as written in lichess `[Variant]` tags; anything unrecognised is played as standard
+    pub fn from_tag(tag: &str) -> Variant {
+        let name: String = tag
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect::<String>()
+            .to_lowercase();
+        match name.as_str() {
+            "crazyhouse" => Variant::Crazyhouse,
+            "bughouse" => Variant::Bughouse,
+            "threecheck" | "3check" => Variant::ThreeCheck,
+            "kingofthehill" | "koth" => Variant::KingOfTheHill,
+            "atomic" => Variant::Atomic,
+            "antichess" | "giveaway" | "suicide" => Variant::Antichess,
+            "horde" => Variant::Horde,
+            _ => Variant::Standard,
+        }
+    }
+
+    // Only for variants which don't start from the standard position
+    pub fn starting_fen(&self) -> Option<&'static str> {
+        match self {
+            Variant::Antichess => Some("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1"),
+            Variant::Horde => {
+                Some("rnbqkbnr/pppppppp/8/1PP2PP1/PPPPPPPP/PPPPPPPP/PPPPPPPP/PPPPPPPP w kq - 0 1")
+            }
+            _ => None,
+        }
+    }
+
+    pub fn has_pockets(&self) -> bool {
+        matches!(self, Variant::Crazyhouse | Variant::Bughouse)
+    }
+}
+
+impl Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Variant::Standard => "Standard",
+                Variant::Crazyhouse => "Crazyhouse",
+                Variant::Bughouse => "Bughouse",
+                Variant::ThreeCheck => "Three-check",
+                Variant::KingOfTheHill => "King of the Hill",
+                Variant::Atomic => "Atomic",
+                Variant::Antichess => "Antichess",
+                Variant::Horde => "Horde",
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod from_tag_tests {
+        use super::*;
+
+        #[test]
+        fn reads_lichess_names() {
+            assert_eq!(Variant::from_tag("Three-check"), Variant::ThreeCheck);
+            assert_eq!(
+                Variant::from_tag("King of the Hill"),
+                Variant::KingOfTheHill
+            );
+            assert_eq!(Variant::from_tag("atomic"), Variant::Atomic);
+            assert_eq!(Variant::from_tag("Giveaway"), Variant::Antichess);
+            assert_eq!(Variant::from_tag("From Position"), Variant::Standard)
+        }
+
+        #[test]
+        fn round_trips_display() {
+            for variant in [
+                Variant::Crazyhouse,
+                Variant::ThreeCheck,
+                Variant::KingOfTheHill,
+                Variant::Horde,
+            ] {
+                assert_eq!(Variant::from_tag(&variant.to_string()), variant)
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use super::{AvailableCastle, Piece, PieceColour, PieceType, Position};
+
+const PIECE_KEYS: usize = 2 * 6 * 64;
+const CASTLE_KEYS: usize = PIECE_KEYS;
+const EN_PASSANT_KEYS: usize = CASTLE_KEYS + 4;
+const BLACK_TO_MOVE_KEY: usize = EN_PASSANT_KEYS + 8;
+const POCKET_KEYS: usize = BLACK_TO_MOVE_KEY + 1;
+// Counts beyond this wrap around and share keys
+const POCKET_DEPTH: usize = 16;
+
+static KEYS: [u64; POCKET_KEYS + 2 * 5 * POCKET_DEPTH] = keys(0x7069_6769_6e5f_7a6f);
+
+// SplitMix64, so that hashes are stable across runs and builds
+const fn keys<const N: usize>(seed: u64) -> [u64; N] {
+    let mut keys = [0; N];
+    let mut state = seed;
+    let mut i = 0;
+    while i < N {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        keys[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    keys
+}
+
+pub fn piece(piece: Piece, position: Position) -> u64 {
+    KEYS[(piece.colour().index() * 6 + piece.piece_type().index()) * 64 + position.index()]
+}
+
+pub fn castle(available_castle: AvailableCastle) -> u64 {
+    let index = match available_castle {
+        AvailableCastle::WhiteKingside => 0,
+        AvailableCastle::WhiteQueenside => 1,
+        AvailableCastle::BlackKingside => 2,
+        AvailableCastle::BlackQueenside => 3,
+    };
+    KEYS[CASTLE_KEYS + index]
+}
+
+pub fn en_passant(position: Position) -> u64 {
+    KEYS[EN_PASSANT_KEYS + usize::from(position.col().unsigned_abs())]
+}
+
+pub fn black_to_move() -> u64 {
+    KEYS[BLACK_TO_MOVE_KEY]
+}
+
+// Held pieces hash as the keys for every count up to the one held, so each one added or removed
+// toggles a single key
+pub fn pocket(colour: PieceColour, piece_type: PieceType, count: u8) -> u64 {
+    let depth = usize::from(count.saturating_sub(1)) % POCKET_DEPTH;
+    KEYS[POCKET_KEYS + (colour.index() * 5 + piece_type.index()) * POCKET_DEPTH + depth]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_distinct_keys() {
+        let mut keys = KEYS.to_vec();
+        keys.sort_unstable();
+        keys.dedup();
+        assert_eq!(keys.len(), KEYS.len())
+    }
+}
@@ -0,0 +1,164 @@
+use std::{collections::HashMap, fmt::Display, sync::OnceLock};
+
+use crate::engine;
+use crate::model::{Board, Variant};
+use crate::parse::{parse_fen, parse_movetext, DEFAULT_FEN};
+
+static ECO: &str = include_str!("../resources/eco.tsv");
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Opening {
+    eco: &'static str,
+    name: &'static str,
+    variation: Option<&'static str>,
+}
+
+impl Opening {
+    pub fn eco(&self) -> &str {
+        self.eco
+    }
+
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    pub fn variation(&self) -> Option<&str> {
+        self.variation
+    }
+
+    // An ECO code prefix ("B9") or part of the name ("najdorf")
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.eco.to_lowercase().starts_with(&query)
+            || self.to_string().to_lowercase().contains(&query)
+    }
+}
+
+impl Display for Opening {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.variation {
+            Some(variation) => write!(f, "{} {}: {variation}", self.eco, self.name),
+            None => write!(f, "{} {}", self.eco, self.name),
+        }
+    }
+}
+
+// The last position in the game found in the table, so transpositions are recognised.
+// The table only holds standard chess, so games in other variants are never classified
+pub fn classify(boards: &[Board]) -> Option<&'static Opening> {
+    if boards.first()?.variant() != Variant::Standard {
+        return None;
+    }
+    let table = table();
+    boards
+        .iter()
+        .rev()
+        .find_map(|board| table.get(&board.zobrist()))
+}
+
+fn table() -> &'static HashMap<u64, Opening> {
+    static TABLE: OnceLock<HashMap<u64, Opening>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = HashMap::new();
+        for (eco, name, variation, moves) in ECO.lines().filter_map(columns) {
+            let Some(board) = final_board(moves) else {
+                continue;
+            };
+            table.entry(board.zobrist()).or_insert(Opening {
+                eco,
+                name,
+                variation,
+            });
+        }
+        table
+    })
+}
+
+fn columns(line: &str) -> Option<(&str, &str, Option<&str>, &str)> {
+    let mut columns = line.split('\t');
+    let eco = columns.next()?;
+    let name = columns.next()?;
+    let variation = columns.next().filter(|variation| !variation.is_empty());
+    let moves = columns.next()?;
+    Some((eco, name, variation, moves))
+}
+
+fn final_board(moves: &str) -> Option<Board> {
+    let board = parse_fen(DEFAULT_FEN).ok()?.starting_board().clone();
+    let ply = parse_movetext(moves).ok()?;
+    engine::execute_moves(&board, &ply).ok()?.pop()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod classify_tests {
+        use super::*;
+
+        #[test]
+        fn loads_every_table_entry() {
+            assert_eq!(table().len(), ECO.lines().count())
+        }
+
+        #[test]
+        fn classifies_deepest_known_position() {
+            let opening = classify(&boards(
+                "1. e4 c5 2. Nf3 d6 3. d4 cxd4 4. Nxd4 Nf6 5. Nc3 a6 6. h3 e5",
+            ))
+            .unwrap();
+            assert_eq!(opening.eco(), "B90");
+            assert_eq!(opening.name(), "Sicilian Defence");
+            assert_eq!(opening.variation(), Some("Najdorf Variation"))
+        }
+
+        #[test]
+        fn classifies_transposition() {
+            let opening = classify(&boards("1. Nf3 Nf6 2. c4 e6 3. d4 d5 4. Nc3")).unwrap();
+            assert_eq!(
+                opening.to_string(),
+                "D37 Queen's Gambit Declined: Three Knights Variation"
+            )
+        }
+
+        #[test]
+        fn returns_none_for_unknown_start() {
+            let board = parse_fen("8/8/8/4k3/8/8/8/4K3 w - - 0 1")
+                .unwrap()
+                .starting_board()
+                .clone();
+            assert!(classify(&[board]).is_none())
+        }
+
+        #[test]
+        fn returns_none_for_other_variants() {
+            let mut boards = boards("1. e4 c5 2. Nf3 d6 3. d4 cxd4 4. Nxd4 Nf6 5. Nc3 a6");
+            for board in &mut boards {
+                board.update_variant(Variant::KingOfTheHill);
+            }
+            assert!(classify(&boards).is_none())
+        }
+
+        fn boards(moves: &str) -> Vec<Board> {
+            let board = parse_fen(DEFAULT_FEN).unwrap().starting_board().clone();
+            engine::execute_moves(&board, &parse_movetext(moves).unwrap()).unwrap()
+        }
+    }
+
+    mod matches_tests {
+        use super::*;
+
+        #[test]
+        fn matches_eco_prefix_and_name() {
+            let opening = Opening {
+                eco: "B90",
+                name: "Sicilian Defence",
+                variation: Some("Najdorf Variation"),
+            };
+            assert!(opening.matches("B9"));
+            assert!(opening.matches("najdorf"));
+            assert!(!opening.matches("B8"));
+            assert!(!opening.matches("French"))
+        }
+    }
+}
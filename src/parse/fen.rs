@@ -5,8 +5,9 @@ use crate::model::{
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete::{i8, u8};
-use nom::combinator::all_consuming;
+use nom::combinator::{all_consuming, opt};
 use nom::multi::many0;
+use nom::sequence::delimited;
 use nom::{
     character::complete::one_of,
     combinator::{map, map_res},
@@ -23,6 +24,10 @@ enum FenCharacter {
     Empty(i8),
     NewRow,
     Piece(Piece),
+    // '~' after a piece which was promoted from a pawn
+    Promoted,
+    // Crazyhouse pockets, as in "[Nn]"
+    Pocket(Vec<Piece>),
 }
 
 pub fn parse(input: &str) -> IResult<&str, Fen> {
@@ -64,21 +69,41 @@ fn board_from(
 
     let mut row = MAX_POSITION;
     let mut col = MIN_POSITION;
+    let mut last_position = None;
 
     for character in fen_characters {
         match character {
             FenCharacter::NewRow => {
                 row -= 1;
                 col = MIN_POSITION;
+                // A ninth rank holds the pockets
+                if row < MIN_POSITION {
+                    builder.pockets(false);
+                }
             }
             FenCharacter::Empty(spaces) => col += spaces,
+            FenCharacter::Piece(piece) if row < MIN_POSITION => {
+                builder.pocket_piece(piece);
+            }
             FenCharacter::Piece(piece) => {
                 let position = Position::try_from(row, col).map_err(|e| {
                     PgnParseError::new(format!("Failed to create position for fen character: {e}"))
                 })?;
                 builder.piece(piece, position);
+                last_position = Some(position);
                 col += 1;
             }
+            FenCharacter::Promoted => {
+                if let Some(position) = last_position {
+                    builder.promoted(position);
+                }
+            }
+            FenCharacter::Pocket(pieces) => {
+                builder.pockets(false);
+                for piece in pieces {
+                    builder.pocket_piece(piece);
+                }
+            }
         }
     }
 
@@ -86,9 +111,30 @@ fn board_from(
 }
 
 fn fen_characters(input: &str) -> IResult<&str, Vec<FenCharacter>> {
-    let parser = alt((new_row, empty_spaces, piece));
+    let parser = alt((new_row, empty_spaces, piece, promoted));
+    let (remaining, mut characters) = many0(parser)(input)?;
+    let (remaining, pocket) = terminated(opt(pocket), tag(" "))(remaining)?;
+    characters.extend(pocket);
+    Ok((remaining, characters))
+}
 
-    terminated(many0(parser), tag(" "))(input)
+fn promoted(input: &str) -> IResult<&str, FenCharacter> {
+    map(tag("~"), |_| FenCharacter::Promoted)(input)
+}
+
+fn pocket(input: &str) -> IResult<&str, FenCharacter> {
+    let pieces = alt((map(tag("-"), |_| Vec::new()), many0(piece)));
+    map(delimited(tag("["), pieces, tag("]")), |pieces| {
+        FenCharacter::Pocket(
+            pieces
+                .into_iter()
+                .filter_map(|character| match character {
+                    FenCharacter::Piece(piece) => Some(piece),
+                    _ => None,
+                })
+                .collect(),
+        )
+    })(input)
 }
 
 fn new_row(input: &str) -> IResult<&str, FenCharacter> {
@@ -105,6 +151,10 @@ fn empty_spaces(input: &str) -> IResult<&str, FenCharacter> {
 }
 
 fn piece(input: &str) -> IResult<&str, FenCharacter> {
+    map(fen_piece, FenCharacter::Piece)(input)
+}
+
+pub fn fen_piece(input: &str) -> IResult<&str, Piece> {
     map_res(one_of("pnbrqkPNBRQK"), |c: char| {
         match c {
             'p' => Ok((PieceColour::Black, PieceType::Pawn)),
@@ -121,9 +171,7 @@ fn piece(input: &str) -> IResult<&str, FenCharacter> {
             'K' => Ok((PieceColour::White, PieceType::King)),
             _ => Err(PgnParseError::new(format!("'{c}' is not a valid piece"))),
         }
-        .map(|capture: (PieceColour, PieceType)| {
-            FenCharacter::Piece(Piece::new(capture.0, capture.1))
-        })
+        .map(|(colour, piece_type)| Piece::new(colour, piece_type))
     })(input)
 }
 
@@ -333,6 +381,44 @@ mod tests {
             assert_eq!(result, expected)
         }
 
+        #[test]
+        fn fills_pockets_from_ninth_rank() {
+            let bracketed = parse("4k3/8/8/8/8/8/8/4K3[PPb] w - - 0 1").unwrap().1;
+            let ranked = parse("4k3/8/8/8/8/8/8/4K3/PPb w - - 0 1").unwrap().1;
+            let board = bracketed.starting_board();
+
+            assert_eq!(board, ranked.starting_board());
+            assert_eq!(
+                board
+                    .pocket(PieceColour::White)
+                    .unwrap()
+                    .count(PieceType::Pawn),
+                2
+            );
+            assert_eq!(
+                board
+                    .pocket(PieceColour::Black)
+                    .unwrap()
+                    .count(PieceType::Bishop),
+                1
+            );
+            assert!(parse("4k3/8/8/8/8/8/8/4K3 w - - 0 1")
+                .unwrap()
+                .1
+                .starting_board()
+                .pocket(PieceColour::White)
+                .is_none())
+        }
+
+        #[test]
+        fn marks_promoted_pieces() {
+            let fen = parse("3Q~k3/8/8/8/8/8/8/4K3[] b - - 0 1").unwrap().1;
+            assert!(fen
+                .starting_board()
+                .promoted()
+                .contains(Position::new(7, 3)))
+        }
+
         #[test]
         fn returns_err_if_invalid_fen_characters() {
             let fen_characters = vec![
@@ -367,6 +453,21 @@ mod tests {
             ];
             assert_eq!(result, ("something", expected))
         }
+
+        #[test]
+        fn parses_pocket_and_promoted_piece() {
+            let result = fen_characters("Q~7[Nn] something").unwrap();
+            let expected = vec![
+                FenCharacter::Piece(Piece::new(PieceColour::White, PieceType::Queen)),
+                FenCharacter::Promoted,
+                FenCharacter::Empty(7),
+                FenCharacter::Pocket(vec![
+                    Piece::new(PieceColour::White, PieceType::Knight),
+                    Piece::new(PieceColour::Black, PieceType::Knight),
+                ]),
+            ];
+            assert_eq!(result, ("something", expected))
+        }
     }
 
     mod new_row_tests {
@@ -7,11 +7,15 @@ mod position;
 mod result;
 mod tag;
 
+use std::str::FromStr;
+
 use nom::{combinator::all_consuming, multi::many1};
 
-use crate::model::Pgn;
+use crate::model::{Board, Fen, GameResult, Pgn, Piece, PieceColour, Ply, PlyMovement, Position};
+
+pub use self::error::PgnParseError;
 
-use self::error::PgnParseError;
+pub static DEFAULT_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
 
 pub fn parse(input: &str) -> Result<Vec<Pgn>, PgnParseError> {
     let (_, pgns) = all_consuming(many1(pgn::parse))(input)
@@ -19,3 +23,128 @@ pub fn parse(input: &str) -> Result<Vec<Pgn>, PgnParseError> {
 
     Ok(pgns)
 }
+
+pub fn parse_fen(input: &str) -> Result<Fen, PgnParseError> {
+    let (_, fen) = fen::parse(input.trim())
+        .map_err(|e| PgnParseError::new(format!("Failed to parse FEN: {e}")))?;
+
+    Ok(fen)
+}
+
+pub fn parse_movetext(input: &str) -> Result<Vec<Ply>, PgnParseError> {
+    // A white move is only accepted without its reply if a result follows
+    let input = format!("{} *", input.trim());
+    let (_, ply) = all_consuming(movement::parse)(&input)
+        .map_err(|e| PgnParseError::new(format!("Failed to parse moves: {e}")))?;
+
+    Ok(ply)
+}
+
+pub fn parse_position(input: &str) -> Result<Position, PgnParseError> {
+    let (_, position) = all_consuming(position::parse)(input.trim())
+        .map_err(|e| PgnParseError::new(format!("Failed to parse position: {e}")))?;
+
+    Ok(position)
+}
+
+// A single FEN letter, such as "N" for a white knight or "q" for a black queen
+pub fn parse_piece(input: &str) -> Result<Piece, PgnParseError> {
+    let (_, piece) = all_consuming(fen::fen_piece)(input.trim())
+        .map_err(|e| PgnParseError::new(format!("Failed to parse piece: {e}")))?;
+
+    Ok(piece)
+}
+
+pub fn parse_san(input: &str, colour: PieceColour) -> Result<PlyMovement, PgnParseError> {
+    // Every ply is terminated by whitespace in movetext
+    let input = format!("{} ", input.trim());
+    let (_, movement) = all_consuming(|input| ply::parse(input, colour))(&input)
+        .map_err(|e| PgnParseError::new(format!("Failed to parse move: {e}")))?;
+
+    Ok(movement)
+}
+
+pub fn parse_result(input: &str) -> Result<GameResult, PgnParseError> {
+    let (_, result) = all_consuming(result::parse)(input.trim())
+        .map_err(|e| PgnParseError::new(format!("Failed to parse result: {e}")))?;
+
+    Ok(result)
+}
+
+impl FromStr for Position {
+    type Err = PgnParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_position(s)
+    }
+}
+
+impl FromStr for Piece {
+    type Err = PgnParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_piece(s)
+    }
+}
+
+impl FromStr for Board {
+    type Err = PgnParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_fen(s).map(|fen| fen.starting_board().clone())
+    }
+}
+
+impl FromStr for GameResult {
+    type Err = PgnParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_result(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PieceType;
+
+    mod from_str_tests {
+        use super::*;
+
+        #[test]
+        fn round_trips_positions() {
+            let position: Position = "e4".parse().unwrap();
+            assert_eq!(position, Position::new(3, 4));
+            assert_eq!(position.to_string(), "e4");
+            assert!("i9".parse::<Position>().is_err())
+        }
+
+        #[test]
+        fn round_trips_pieces() {
+            let piece: Piece = "n".parse().unwrap();
+            assert_eq!(piece, Piece::new(PieceColour::Black, PieceType::Knight));
+            assert_eq!(piece.to_string(), "n");
+            assert_eq!("Q".parse::<Piece>().unwrap().to_string(), "Q");
+            assert!("x".parse::<Piece>().is_err());
+            assert!("NN".parse::<Piece>().is_err())
+        }
+
+        #[test]
+        fn round_trips_boards() {
+            for fen in [
+                DEFAULT_FEN,
+                "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3",
+            ] {
+                assert_eq!(fen.parse::<Board>().unwrap().to_string(), fen)
+            }
+            assert!("not a fen".parse::<Board>().is_err())
+        }
+
+        #[test]
+        fn round_trips_results() {
+            for result in ["1-0", "0-1", "1/2-1/2", "*"] {
+                assert_eq!(result.parse::<GameResult>().unwrap().to_string(), result)
+            }
+        }
+    }
+}
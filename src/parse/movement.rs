@@ -1,11 +1,11 @@
-use crate::model::{PieceColour, Ply};
+use crate::model::{PieceColour, Ply, PlyMovement};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::bytes::complete::take_until;
-use nom::character::complete::{char, line_ending, space1};
-use nom::combinator::{map, opt};
-use nom::multi::many0;
-use nom::sequence::delimited;
+use nom::character::complete::{char, line_ending};
+use nom::combinator::{map, opt, peek};
+use nom::multi::{fold_many0, many0};
+use nom::sequence::{delimited, pair, preceded};
 use nom::{
     character::complete::{digit1, space0},
     combinator::map_res,
@@ -34,33 +34,31 @@ fn parse_moves(input: &str) -> IResult<&str, Vec<Ply>> {
 fn parse_move(input: &str) -> IResult<&str, Vec<Ply>> {
     let (remaining, move_number) = white_move_number(input)?;
     let (remaining, white_ply) = ply::parse(remaining, PieceColour::White)?;
-    let (remaining, white_comment) = opt(comment)(remaining)?;
+    let (remaining, white_commentary) = commentary(remaining)?;
 
     let (remaining, maybe_result) = opt(result::parse)(remaining)?;
+    // A variation may end after a white move
+    let (remaining, maybe_close) = opt(peek(char(')')))(remaining)?;
 
-    if maybe_result.is_some() {
+    if maybe_result.is_some() || maybe_close.is_some() {
         return Ok((
             remaining,
-            vec![Ply::new(move_number, white_ply, white_comment)],
+            vec![white_commentary.ply(move_number, white_ply)],
         ));
     }
 
     let (remaining, maybe_black_move_number) = opt(black_move_number)(remaining)?;
 
     let (remaining, black_ply) = ply::parse(remaining, PieceColour::Black)?;
-    let (remaining, black_comment) = opt(comment)(remaining)?;
+    let (remaining, black_commentary) = commentary(remaining)?;
 
     let (remaining, _) = opt(result::parse)(remaining)?;
 
     Ok((
         remaining,
         vec![
-            Ply::new(move_number, white_ply, white_comment),
-            Ply::new(
-                maybe_black_move_number.unwrap_or(move_number),
-                black_ply,
-                black_comment,
-            ),
+            white_commentary.ply(move_number, white_ply),
+            black_commentary.ply(maybe_black_move_number.unwrap_or(move_number), black_ply),
         ],
     ))
 }
@@ -69,11 +67,11 @@ fn parse_partial_move(input: &str) -> IResult<&str, Ply> {
     let (remaining, move_number) = black_move_number(input)?;
 
     let (remaining, ply) = ply::parse(remaining, PieceColour::Black)?;
-    let (remaining, comment) = opt(comment)(remaining)?;
+    let (remaining, commentary) = commentary(remaining)?;
 
     let (remaining, _) = opt(result::parse)(remaining)?;
 
-    Ok((remaining, Ply::new(move_number, ply, comment)))
+    Ok((remaining, commentary.ply(move_number, ply)))
 }
 
 fn white_move_number(input: &str) -> IResult<&str, i16> {
@@ -86,6 +84,66 @@ fn black_move_number(input: &str) -> IResult<&str, i16> {
     map_res(terminated(digit1, terminator), |s: &str| s.parse::<i16>())(input)
 }
 
+// The NAGs, comments and variations following a move, which may come in any order
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Commentary {
+    nags: Vec<u8>,
+    comment: Option<String>,
+    variations: Vec<Vec<Ply>>,
+}
+
+impl Commentary {
+    fn ply(self, move_number: i16, movement: PlyMovement) -> Ply {
+        Ply::new(move_number, movement, self.comment).with_annotations(self.nags, self.variations)
+    }
+}
+
+enum Annotation {
+    Nag(u8),
+    Comment(String),
+    Variation(Vec<Ply>),
+}
+
+// Successive comments are joined into one
+fn commentary(input: &str) -> IResult<&str, Commentary> {
+    let annotation = alt((
+        map(nag, Annotation::Nag),
+        map(comment, Annotation::Comment),
+        map(variation, Annotation::Variation),
+    ));
+    fold_many0(
+        annotation,
+        Commentary::default,
+        |mut commentary, annotation| {
+            match annotation {
+                Annotation::Nag(nag) => commentary.nags.push(nag),
+                Annotation::Comment(comment) => {
+                    commentary.comment = Some(match commentary.comment {
+                        Some(previous) => format!("{previous} {comment}"),
+                        None => comment,
+                    })
+                }
+                Annotation::Variation(plies) => commentary.variations.push(plies),
+            }
+            commentary
+        },
+    )(input)
+}
+
+fn nag(input: &str) -> IResult<&str, u8> {
+    map_res(
+        terminated(preceded(char('$'), digit1), ply::ply_terminator),
+        |s: &str| s.parse::<u8>(),
+    )(input)
+}
+
+fn variation(input: &str) -> IResult<&str, Vec<Ply>> {
+    terminated(
+        delimited(pair(char('('), space0), parse_moves, char(')')),
+        ply::ply_terminator,
+    )(input)
+}
+
 fn comment(input: &str) -> IResult<&str, String> {
     alt((parenthesis_comment, semicolon_comment))(input)
 }
@@ -93,7 +151,7 @@ fn comment(input: &str) -> IResult<&str, String> {
 fn parenthesis_comment(input: &str) -> IResult<&str, String> {
     let parser = terminated(
         delimited(char('{'), take_until("}"), char('}')),
-        alt((space1, line_ending)),
+        ply::ply_terminator,
     );
     map(parser, |s: &str| s.replace('\n', " "))(input)
 }
@@ -567,4 +625,52 @@ mod tests {
             )
         }
     }
+
+    mod commentary_tests {
+        use super::*;
+
+        #[test]
+        fn parses_nags_comments_and_variations() {
+            let (remaining, commentary) =
+                commentary("$2 $14 {Better was} (2. Nf3 {quiet} (2. d4)) (2. c3) 2... d5").unwrap();
+
+            assert_eq!(remaining, "2... d5");
+            assert_eq!(commentary.nags, vec![2, 14]);
+            assert_eq!(commentary.comment, Some("Better was".to_string()));
+            assert_eq!(commentary.variations.len(), 2);
+
+            let first = &commentary.variations[0];
+            assert_eq!(first.len(), 1);
+            assert_eq!(first[0].to_string(), "2.Nf3");
+            assert_eq!(first[0].comment(), Some(&"quiet".to_string()));
+            assert_eq!(first[0].variations()[0][0].to_string(), "2.d4");
+            assert_eq!(commentary.variations[1][0].to_string(), "2.c3")
+        }
+
+        #[test]
+        fn parses_variation_from_black() {
+            let (remaining, plies) = parse("1. e4 e5 (1... c5 2. Nf3) 2. Nf3 *").unwrap();
+
+            assert_eq!(remaining, "");
+            assert_eq!(plies.len(), 3);
+            let variation: Vec<String> = plies[1].variations()[0]
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            assert_eq!(variation, vec!["c5", "2.Nf3"])
+        }
+
+        #[test]
+        fn joins_successive_comments() {
+            let (_, commentary) = commentary("{First} $1 {second} d5").unwrap();
+            assert_eq!(commentary.comment, Some("First second".to_string()));
+            assert_eq!(commentary.nags, vec![1])
+        }
+
+        #[test]
+        fn parses_no_commentary() {
+            let result = commentary("d5").unwrap();
+            assert_eq!(result, ("d5", Commentary::default()))
+        }
+    }
 }
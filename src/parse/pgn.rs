@@ -9,22 +9,29 @@ use super::fen;
 use super::movement;
 use super::result;
 use super::tag;
-use crate::model::Pgn;
-
-static DEFAULT_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+use super::DEFAULT_FEN;
+use crate::model::{Pgn, Variant};
 
 // TODO: consider how to improve error handling here
 pub fn parse(input: &str) -> IResult<&str, Pgn> {
     let (remaining, (mut tags, ply)) =
         terminated(pair(tag::parse, movement::parse), many0(line_ending))(input)?;
 
+    let variant = tags
+        .get("Variant")
+        .map_or(Variant::Standard, |variant| Variant::from_tag(variant));
+    // SetUp only marks the presence of FEN, which is kept as the starting board
+    tags.remove("SetUp");
     let fen = &tags
         .remove("FEN")
+        .or_else(|| variant.starting_fen().map(ToString::to_string))
         .unwrap_or_else(|| DEFAULT_FEN.to_string());
 
-    let (_, fen) = fen::parse(fen)
+    let (_, mut fen) = fen::parse(fen)
         .map_err(|_| nom::Err::Error(Error::from_error_kind(input, ErrorKind::Tag)))?;
 
+    fen.update_variant(variant);
+
     let result = &tags
         .remove("Result")
         .ok_or_else(|| nom::Err::Error(Error::from_error_kind(input, ErrorKind::Tag)))?;
@@ -62,6 +69,37 @@ mod tests {
         assert_eq!(pgn, (remaining, expected()));
     }
 
+    #[test]
+    fn enables_pockets_for_drop_variants() {
+        let pgn = |variant: &str| {
+            let text = format!("[Variant \"{variant}\"]\n[Result \"*\"]\n\n1. e4 d5 *\n");
+            parse(&text).unwrap().1
+        };
+
+        let crazyhouse = pgn("Crazyhouse");
+        let board = crazyhouse.fen().starting_board();
+        assert!(board.pocket(PieceColour::White).is_some());
+        assert!(!board.is_partnered());
+        assert!(pgn("Bughouse").fen().starting_board().is_partnered());
+        assert!(pgn("Standard")
+            .fen()
+            .starting_board()
+            .pocket(PieceColour::White)
+            .is_none())
+    }
+
+    #[test]
+    fn starts_from_variant_position() {
+        let text = "[Variant \"Horde\"]\n[Result \"*\"]\n\n1. a4 *\n";
+        let pgn = parse(text).unwrap().1;
+        let board = pgn.fen().starting_board();
+        assert_eq!(board.variant(), Variant::Horde);
+        assert_eq!(
+            board.fen(),
+            "rnbqkbnr/pppppppp/8/1PP2PP1/PPPPPPPP/PPPPPPPP/PPPPPPPP/PPPPPPPP w kq - 0 1"
+        )
+    }
+
     fn expected() -> Pgn {
         let mut tags: HashMap<String, String> = HashMap::new();
         tags.insert("White".to_string(), "Player, One".to_string());
@@ -2,7 +2,7 @@ use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{line_ending, one_of, space1},
-    combinator::{map, map_res, opt},
+    combinator::{map, map_res, opt, peek},
     sequence::{pair, terminated, tuple},
     IResult,
 };
@@ -16,6 +16,7 @@ use super::position::{column, row};
 
 pub fn parse(input: &str, colour: PieceColour) -> IResult<&str, PlyMovement> {
     piece_move(input, colour)
+        .or_else(|_| drop(input, colour))
         .or_else(|_| kingside_castle(input, colour))
         .or_else(|_| queenside_castle(input, colour))
 }
@@ -61,6 +62,26 @@ fn piece_move(input: &str, colour: PieceColour) -> IResult<&str, PlyMovement> {
     }
 }
 
+// "N@f3"; a bare "@e6" drops a pawn
+fn drop(input: &str, colour: PieceColour) -> IResult<&str, PlyMovement> {
+    let parser = tuple((
+        opt(alt((piece_type, map(tag("P"), |_| PieceType::Pawn)))),
+        tag("@"),
+        position::parse,
+        opt(check),
+    ));
+    map(
+        terminated(parser, ply_terminator),
+        |(maybe_piece_type, _, position, check)| PlyMovement::Drop {
+            movement: Movement::new(
+                Piece::new(colour, maybe_piece_type.unwrap_or(PieceType::Pawn)),
+                position,
+            ),
+            check,
+        },
+    )(input)
+}
+
 fn position_with_qualifier(input: &str) -> IResult<&str, (Option<MoveQualifier>, bool, Position)> {
     alt((
         map(
@@ -125,8 +146,9 @@ fn piece_type(input: &str) -> IResult<&str, PieceType> {
     })(input)
 }
 
-fn ply_terminator(input: &str) -> IResult<&str, &str> {
-    alt((space1, line_ending))(input)
+// A variation may close straight after its last move
+pub fn ply_terminator(input: &str) -> IResult<&str, &str> {
+    alt((space1, line_ending, peek(tag(")"))))(input)
 }
 
 fn check(input: &str) -> IResult<&str, Check> {
@@ -579,6 +601,47 @@ mod tests {
         }
     }
 
+    mod drop_tests {
+        use super::*;
+
+        #[test]
+        fn returns_err_if_not_drop() {
+            let result = drop("Nf3 e5", PieceColour::White);
+            assert!(result.is_err())
+        }
+
+        #[test]
+        fn parses_piece_drop() {
+            let result = parse("N@f3+ e5", PieceColour::White).unwrap();
+            assert_eq!(
+                result,
+                (
+                    "e5",
+                    PlyMovement::Drop {
+                        movement: Movement::new(
+                            Piece::new(PieceColour::White, PieceType::Knight),
+                            Position::new(2, 5)
+                        ),
+                        check: Some(Check::Check)
+                    }
+                )
+            )
+        }
+
+        #[test]
+        fn parses_pawn_drop() {
+            let expected = PlyMovement::Drop {
+                movement: Movement::new(
+                    Piece::new(PieceColour::Black, PieceType::Pawn),
+                    Position::new(5, 4),
+                ),
+                check: None,
+            };
+            assert_eq!(drop("P@e6 ", PieceColour::Black).unwrap().1, expected);
+            assert_eq!(drop("@e6 ", PieceColour::Black).unwrap().1, expected)
+        }
+    }
+
     mod safe_position_tests {
         use super::*;
 
@@ -1,8 +1,10 @@
 use std::collections::HashMap;
 
+use nom::branch::alt;
 use nom::character::complete::char;
+use nom::combinator::{map, opt, value};
 use nom::{
-    bytes::complete::take_until,
+    bytes::complete::{escaped_transform, is_not, take_until},
     character::complete::line_ending,
     multi::many_till,
     sequence::{delimited, separated_pair, terminated},
@@ -15,14 +17,14 @@ pub fn parse(input: &str) -> IResult<&str, Tags> {
     let (remaining, (tags, _)) = many_till(parse_tag, line_ending)(input)?;
     let tags: HashMap<String, String> = tags
         .into_iter()
-        .map(|pair: (&str, &str)| (pair.0.to_string(), pair.1.to_string()))
+        .map(|(name, value): (&str, String)| (name.to_string(), value))
         .collect();
 
     Ok((remaining, Tags::new(tags)))
 }
 
-fn parse_tag(input: &str) -> IResult<&str, (&str, &str)> {
-    fn parse_key_value(input: &str) -> IResult<&str, (&str, &str)> {
+fn parse_tag(input: &str) -> IResult<&str, (&str, String)> {
+    fn parse_key_value(input: &str) -> IResult<&str, (&str, String)> {
         separated_pair(take_until(" "), char(' '), parse_value)(input)
     }
     terminated(
@@ -31,8 +33,17 @@ fn parse_tag(input: &str) -> IResult<&str, (&str, &str)> {
     )(input)
 }
 
-fn parse_value(input: &str) -> IResult<&str, &str> {
-    delimited(char('"'), take_until("\""), char('"'))(input)
+// Quotes and backslashes inside values are escaped with a backslash
+fn parse_value(input: &str) -> IResult<&str, String> {
+    let escaped = escaped_transform(
+        is_not("\\\""),
+        '\\',
+        alt((value("\\", char('\\')), value("\"", char('"')))),
+    );
+    map(
+        delimited(char('"'), opt(escaped), char('"')),
+        Option::unwrap_or_default,
+    )(input)
 }
 
 #[cfg(test)]
@@ -54,12 +65,24 @@ mod tests {
     #[test]
     fn parses_tag() {
         let result = parse_tag("[Tag \"Value\"]\n1.e4").unwrap();
-        assert_eq!(result, ("1.e4", ("Tag", "Value")))
+        assert_eq!(result, ("1.e4", ("Tag", "Value".to_string())))
     }
 
     #[test]
     fn parses_value() {
         let result = parse_value(r#""Value""#).unwrap();
-        assert_eq!(result, ("", "Value"))
+        assert_eq!(result, ("", "Value".to_string()))
+    }
+
+    #[test]
+    fn unescapes_quotes_and_backslashes() {
+        let result = parse_value(r#""The \"Immortal\" \\ Game"]"#).unwrap();
+        assert_eq!(result, ("]", r#"The "Immortal" \ Game"#.to_string()))
+    }
+
+    #[test]
+    fn parses_empty_value() {
+        let result = parse_value(r#""""#).unwrap();
+        assert_eq!(result, ("", String::new()))
     }
 }
@@ -0,0 +1,18 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug)]
+pub struct QueryError(String);
+
+impl QueryError {
+    pub fn new(message: impl Into<String>) -> Self {
+        QueryError(message.into())
+    }
+}
+
+impl Error for QueryError {}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,226 @@
+mod error;
+mod parse;
+
+use crate::engine::{self, Tactic};
+use crate::model::{Bitboard, Board, Piece, PieceColour, PieceType, Position};
+use crate::structure::{self, Formation};
+
+pub use self::error::QueryError;
+pub use self::parse::parse_query;
+
+// Net material a side must be down after the exchange for a capture to count as a sacrifice
+const SACRIFICE_THRESHOLD: i32 = 300;
+
+// Queen, rook, bishop, knight and pawn counts; kings are implied
+type Material = [u32; 5];
+
+const MATERIAL_PIECES: [PieceType; 5] = [
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+    PieceType::Pawn,
+];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Place {
+    Square(Position),
+    Rank(i8),
+    File(i8),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Query {
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    // Either side may hold either set
+    Material(Material, Material),
+    Piece(Piece, Option<Place>),
+    Sacrifice(Piece),
+    // Created by the move which reached the board
+    Tactic(Tactic),
+    Formation(Formation),
+    // Up to and including black's reply on the given move
+    ByMove(usize),
+    OppositeBishops,
+    Check,
+    Mate,
+}
+
+impl Query {
+    // Boards are a game's positions in order; some predicates look at the surrounding ply
+    pub fn matches(&self, boards: &[Board], index: usize) -> bool {
+        let board = &boards[index];
+        match self {
+            Query::And(left, right) => left.matches(boards, index) && right.matches(boards, index),
+            Query::Or(left, right) => left.matches(boards, index) || right.matches(boards, index),
+            Query::Not(query) => !query.matches(boards, index),
+            Query::Material(first, second) => {
+                let white = material(board, PieceColour::White);
+                let black = material(board, PieceColour::Black);
+                (white == *first && black == *second) || (white == *second && black == *first)
+            }
+            Query::Piece(piece, place) => {
+                let mask = place.map_or(Bitboard::new(u64::MAX), mask);
+                !(board.bitboard(*piece) & mask).is_empty()
+            }
+            Query::Sacrifice(piece) => sacrificed(boards, index, *piece),
+            Query::Tactic(tactic) => index.checked_sub(1).is_some_and(|previous| {
+                engine::motifs(&boards[previous], board)
+                    .iter()
+                    .any(|motif| motif.tactic() == *tactic)
+            }),
+            Query::Formation(formation) => structure::analyse(board).has(*formation),
+            Query::ByMove(move_number) => {
+                board.fullmove_clock() <= *move_number
+                    || (board.fullmove_clock() == move_number + 1
+                        && *board.active_colour() == PieceColour::White)
+            }
+            Query::OppositeBishops => opposite_bishops(board),
+            Query::Check => !engine::checkers(board).is_empty(),
+            Query::Mate => {
+                !engine::checkers(board).is_empty() && engine::legal_moves(board).is_empty()
+            }
+        }
+    }
+}
+
+fn material(board: &Board, colour: PieceColour) -> Material {
+    MATERIAL_PIECES.map(|piece_type| board.bitboard(Piece::new(colour, piece_type)).count())
+}
+
+fn mask(place: Place) -> Bitboard {
+    match place {
+        Place::Square(position) => Bitboard::from_position(position),
+        Place::Rank(row) => Bitboard::new(0xff << (8 * row)),
+        Place::File(col) => Bitboard::new(0x0101_0101_0101_0101 << col),
+    }
+}
+
+fn opposite_bishops(board: &Board) -> bool {
+    let white = board.bitboard(Piece::new(PieceColour::White, PieceType::Bishop));
+    let black = board.bitboard(Piece::new(PieceColour::Black, PieceType::Bishop));
+    match (white.first(), black.first()) {
+        (Some(white_bishop), Some(black_bishop)) if white.count() == 1 && black.count() == 1 => {
+            square_colour(white_bishop) != square_colour(black_bishop)
+        }
+        _ => false,
+    }
+}
+
+fn square_colour(position: Position) -> i8 {
+    (position.row() + position.col()) % 2
+}
+
+// The piece was captured on this ply, and from before the move which offered it until the reply
+// to the capture its side came out behind
+fn sacrificed(boards: &[Board], index: usize, piece: Piece) -> bool {
+    let Some(previous) = index.checked_sub(1).map(|previous| &boards[previous]) else {
+        return false;
+    };
+    let board = &boards[index];
+    let colour = *piece.colour();
+    let captured_by_opponent = *previous.active_colour() != colour;
+    let lost = board.bitboard(piece).count() < previous.bitboard(piece).count();
+    if !captured_by_opponent || !lost {
+        return false;
+    }
+
+    let before = &boards[index.saturating_sub(2)];
+    let after = &boards[(index + 1).min(boards.len() - 1)];
+    balance(after, colour) - balance(before, colour) <= -SACRIFICE_THRESHOLD
+}
+
+fn balance(board: &Board, colour: PieceColour) -> i32 {
+    let total = |colour| {
+        MATERIAL_PIECES
+            .iter()
+            .map(|&piece_type| {
+                let count = board.bitboard(Piece::new(colour, piece_type)).count();
+                engine::value(piece_type) * i32::try_from(count).unwrap_or_default()
+            })
+            .sum::<i32>()
+    };
+    total(colour) - total(colour.opposite())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{parse_fen, parse_movetext, DEFAULT_FEN};
+
+    mod matches_tests {
+        use super::*;
+
+        #[test]
+        fn matches_material_for_either_side() {
+            let query = parse_query("material KRPvKR").unwrap();
+            assert!(query.matches(&board("8/5k2/8/3r4/8/3P4/2R5/4K3 w - - 0 1"), 0));
+            assert!(query.matches(&board("8/5k2/3p4/3r4/8/8/2R5/4K3 w - - 0 1"), 0));
+            assert!(!query.matches(&board("8/5k2/3p4/3r4/8/3P4/2R5/4K3 w - - 0 1"), 0))
+        }
+
+        #[test]
+        fn matches_pieces_on_ranks() {
+            let query = parse_query("white pawn on the 7th and black king on rank 8").unwrap();
+            assert!(query.matches(&board("4k3/3P4/8/8/8/8/8/4K3 w - - 0 1"), 0));
+            assert!(!query.matches(&board("8/3P1k2/8/8/8/8/8/4K3 w - - 0 1"), 0))
+        }
+
+        #[test]
+        fn matches_opposite_coloured_bishops() {
+            let query = parse_query("opposite-bishops").unwrap();
+            assert!(query.matches(&board("4k3/8/4b3/8/8/8/8/2B1K3 w - - 0 1"), 0));
+            assert!(!query.matches(&board("4k3/8/3b4/8/8/8/8/2B1K3 w - - 0 1"), 0))
+        }
+
+        #[test]
+        fn matches_mate_and_negation() {
+            let boards = boards("1. f3 e5 2. g4 Qh4#");
+            let query = parse_query("mate and black queen on h4").unwrap();
+            assert!(!query.matches(&boards, 3));
+            assert!(query.matches(&boards, 4))
+        }
+
+        #[test]
+        fn matches_tactics_on_the_ply_they_appear() {
+            let boards = boards("1. e4 e5 2. Qh5 Nc6 3. Qxe5+ Nxe5");
+            let query = parse_query("hanging").unwrap();
+            let matching: Vec<usize> = (0..boards.len())
+                .filter(|&index| query.matches(&boards, index))
+                .collect();
+            assert_eq!(matching, vec![5])
+        }
+
+        #[test]
+        fn matches_formation_by_move() {
+            let boards = boards("1. d4 d5 2. c4 e6 3. Nc3 Nf6 4. cxd5 exd5 5. Bg5 c6 6. e3");
+            let matching = |text| {
+                let query = parse_query(text).unwrap();
+                (0..boards.len()).any(|index| query.matches(&boards, index))
+            };
+            assert!(matching("carlsbad and by move 6"));
+            assert!(!matching("carlsbad and by move 5"))
+        }
+
+        #[test]
+        fn matches_sacrifice_but_not_trade() {
+            let sacrifice = boards("1. e4 e5 2. Qh5 Nc6 3. Qxf7+ Kxf7");
+            let query = parse_query("white queen sacrificed").unwrap();
+            assert!(query.matches(&sacrifice, 6));
+
+            let trade = boards("1. d4 e5 2. dxe5 d6 3. exd6 Qxd6 4. Qxd6 Bxd6");
+            assert!(!(0..trade.len()).any(|index| query.matches(&trade, index)))
+        }
+
+        fn board(fen: &str) -> Vec<Board> {
+            vec![parse_fen(fen).unwrap().starting_board().clone()]
+        }
+
+        fn boards(moves: &str) -> Vec<Board> {
+            let board = parse_fen(DEFAULT_FEN).unwrap().starting_board().clone();
+            engine::execute_moves(&board, &parse_movetext(moves).unwrap()).unwrap()
+        }
+    }
+}
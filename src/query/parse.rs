@@ -0,0 +1,296 @@
+use nom::{
+    branch::alt,
+    bytes::complete::take_while1,
+    character::complete::{char, multispace0},
+    combinator::{all_consuming, map, map_opt, opt, value, verify},
+    multi::many0,
+    sequence::{delimited, pair, preceded, terminated},
+    IResult,
+};
+
+use crate::engine::Tactic;
+use crate::model::{Piece, PieceColour, PieceType, Position};
+use crate::structure::Formation;
+
+use super::{error::QueryError, Material, Place, Query, MATERIAL_PIECES};
+
+pub fn parse_query(input: &str) -> Result<Query, QueryError> {
+    let (_, query) = all_consuming(terminated(or, multispace0))(input)
+        .map_err(|e| QueryError::new(format!("Failed to parse query '{input}': {e}")))?;
+
+    Ok(query)
+}
+
+fn or(input: &str) -> IResult<&str, Query> {
+    let (remaining, first) = and(input)?;
+    let (remaining, others) = many0(preceded(keyword("or"), and))(remaining)?;
+    let query = others.into_iter().fold(first, |left, right| {
+        Query::Or(Box::new(left), Box::new(right))
+    });
+    Ok((remaining, query))
+}
+
+fn and(input: &str) -> IResult<&str, Query> {
+    let (remaining, first) = unary(input)?;
+    let (remaining, others) = many0(preceded(keyword("and"), unary))(remaining)?;
+    let query = others.into_iter().fold(first, |left, right| {
+        Query::And(Box::new(left), Box::new(right))
+    });
+    Ok((remaining, query))
+}
+
+fn unary(input: &str) -> IResult<&str, Query> {
+    alt((
+        map(preceded(keyword("not"), unary), |query| {
+            Query::Not(Box::new(query))
+        }),
+        delimited(
+            preceded(multispace0, char('(')),
+            or,
+            preceded(multispace0, char(')')),
+        ),
+        predicate,
+    ))(input)
+}
+
+fn predicate(input: &str) -> IResult<&str, Query> {
+    alt((
+        preceded(keyword("material"), map_opt(word, material)),
+        value(Query::OppositeBishops, keyword("opposite-bishops")),
+        value(Query::Check, keyword("check")),
+        value(Query::Mate, keyword("mate")),
+        tactic,
+        formation,
+        preceded(
+            pair(keyword("by"), keyword("move")),
+            map_opt(word, |word| word.parse().ok().map(Query::ByMove)),
+        ),
+        piece,
+    ))(input)
+}
+
+fn formation(input: &str) -> IResult<&str, Query> {
+    map_opt(word, |word| match word.to_lowercase().as_str() {
+        "carlsbad" => Some(Query::Formation(Formation::Carlsbad)),
+        "hedgehog" => Some(Query::Formation(Formation::Hedgehog)),
+        "iqp" => Some(Query::Formation(Formation::IsolatedQueensPawn)),
+        "maroczy" => Some(Query::Formation(Formation::Maroczy)),
+        _ => None,
+    })(input)
+}
+
+fn tactic(input: &str) -> IResult<&str, Query> {
+    map_opt(word, |word| {
+        let tactic = |tactic| Some(Query::Tactic(tactic));
+        match word.to_lowercase().trim_end_matches('s') {
+            "fork" => tactic(Tactic::Fork),
+            "pin" => Some(Query::Or(
+                Box::new(Query::Tactic(Tactic::AbsolutePin)),
+                Box::new(Query::Tactic(Tactic::RelativePin)),
+            )),
+            "absolute-pin" => tactic(Tactic::AbsolutePin),
+            "relative-pin" => tactic(Tactic::RelativePin),
+            "skewer" => tactic(Tactic::Skewer),
+            "discovered-check" => tactic(Tactic::DiscoveredCheck),
+            "hanging" => tactic(Tactic::HangingPiece),
+            _ => None,
+        }
+    })(input)
+}
+
+fn piece(input: &str) -> IResult<&str, Query> {
+    let (remaining, piece) = map(pair(colour, piece_type), |(colour, piece_type)| {
+        Piece::new(colour, piece_type)
+    })(input)?;
+    let on = pair(keyword("on"), opt(keyword("the")));
+    alt((
+        value(Query::Sacrifice(piece), keyword("sacrificed")),
+        map(opt(preceded(on, place)), move |place| {
+            Query::Piece(piece, place)
+        }),
+    ))(remaining)
+}
+
+fn place(input: &str) -> IResult<&str, Place> {
+    alt((
+        preceded(keyword("rank"), map_opt(word, rank)),
+        preceded(keyword("file"), map_opt(word, file)),
+        terminated(map_opt(word, ordinal), opt(keyword("rank"))),
+        map_opt(word, square),
+    ))(input)
+}
+
+fn colour(input: &str) -> IResult<&str, PieceColour> {
+    map_opt(word, |word| match word.to_lowercase().as_str() {
+        "white" => Some(PieceColour::White),
+        "black" => Some(PieceColour::Black),
+        _ => None,
+    })(input)
+}
+
+fn piece_type(input: &str) -> IResult<&str, PieceType> {
+    map_opt(word, |word| {
+        match word.to_lowercase().trim_end_matches('s') {
+            "king" => Some(PieceType::King),
+            "queen" => Some(PieceType::Queen),
+            "rook" => Some(PieceType::Rook),
+            "bishop" => Some(PieceType::Bishop),
+            "knight" => Some(PieceType::Knight),
+            "pawn" => Some(PieceType::Pawn),
+            _ => None,
+        }
+    })(input)
+}
+
+fn word(input: &str) -> IResult<&str, &str> {
+    preceded(
+        multispace0,
+        take_while1(|c: char| c.is_alphanumeric() || c == '-'),
+    )(input)
+}
+
+fn keyword<'a>(keyword: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str> {
+    verify(word, move |word: &str| word.eq_ignore_ascii_case(keyword))
+}
+
+// "KRPvKR"; kings may be left out
+fn material(signature: &str) -> Option<Query> {
+    let (first, second) = signature.split_once('v')?;
+    Some(Query::Material(counts(first)?, counts(second)?))
+}
+
+fn counts(pieces: &str) -> Option<Material> {
+    let mut material = [0; 5];
+    for letter in pieces.chars().filter(|&letter| letter != 'K') {
+        let piece_type = match letter {
+            'Q' => PieceType::Queen,
+            'R' => PieceType::Rook,
+            'B' => PieceType::Bishop,
+            'N' => PieceType::Knight,
+            'P' => PieceType::Pawn,
+            _ => return None,
+        };
+        let index = MATERIAL_PIECES.iter().position(|&p| p == piece_type)?;
+        material[index] += 1;
+    }
+    Some(material)
+}
+
+fn rank(word: &str) -> Option<Place> {
+    match word.parse::<i8>() {
+        Ok(rank @ 1..=8) => Some(Place::Rank(rank - 1)),
+        _ => None,
+    }
+}
+
+fn ordinal(word: &str) -> Option<Place> {
+    ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|suffix| word.strip_suffix(suffix))
+        .and_then(rank)
+}
+
+fn file(word: &str) -> Option<Place> {
+    match word.as_bytes() {
+        [file @ b'a'..=b'h'] => Some(Place::File(i8::try_from(file - b'a').ok()?)),
+        _ => None,
+    }
+}
+
+fn square(word: &str) -> Option<Place> {
+    match word.as_bytes() {
+        [file @ b'a'..=b'h', rank @ b'1'..=b'8'] => Some(Place::Square(Position::new(
+            i8::try_from(rank - b'1').ok()?,
+            i8::try_from(file - b'a').ok()?,
+        ))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod parse_query_tests {
+        use super::*;
+
+        #[test]
+        fn parses_material() {
+            let result = parse_query("material KRPvKR").unwrap();
+            assert_eq!(result, Query::Material([0, 1, 0, 0, 1], [0, 1, 0, 0, 0]))
+        }
+
+        #[test]
+        fn parses_pieces_and_places() {
+            let white_pawn = Piece::new(PieceColour::White, PieceType::Pawn);
+            assert_eq!(
+                parse_query("white pawn on the 7th rank").unwrap(),
+                Query::Piece(white_pawn, Some(Place::Rank(6)))
+            );
+            assert_eq!(
+                parse_query("White Pawns on file c").unwrap(),
+                Query::Piece(white_pawn, Some(Place::File(2)))
+            );
+            assert_eq!(
+                parse_query("black king on e8").unwrap(),
+                Query::Piece(
+                    Piece::new(PieceColour::Black, PieceType::King),
+                    Some(Place::Square(Position::new(7, 4)))
+                )
+            );
+            assert_eq!(
+                parse_query("white queen sacrificed").unwrap(),
+                Query::Sacrifice(Piece::new(PieceColour::White, PieceType::Queen))
+            )
+        }
+
+        #[test]
+        fn parses_tactics() {
+            assert_eq!(parse_query("forks").unwrap(), Query::Tactic(Tactic::Fork));
+            assert_eq!(
+                parse_query("pin").unwrap(),
+                Query::Or(
+                    Box::new(Query::Tactic(Tactic::AbsolutePin)),
+                    Box::new(Query::Tactic(Tactic::RelativePin))
+                )
+            );
+            assert_eq!(
+                parse_query("discovered-check").unwrap(),
+                Query::Tactic(Tactic::DiscoveredCheck)
+            )
+        }
+
+        #[test]
+        fn parses_formation_by_move() {
+            assert_eq!(
+                parse_query("IQP and by move 15").unwrap(),
+                Query::And(
+                    Box::new(Query::Formation(Formation::IsolatedQueensPawn)),
+                    Box::new(Query::ByMove(15))
+                )
+            )
+        }
+
+        #[test]
+        fn binds_and_tighter_than_or() {
+            let result = parse_query("check or mate and not (opposite-bishops)").unwrap();
+            assert_eq!(
+                result,
+                Query::Or(
+                    Box::new(Query::Check),
+                    Box::new(Query::And(
+                        Box::new(Query::Mate),
+                        Box::new(Query::Not(Box::new(Query::OppositeBishops)))
+                    ))
+                )
+            )
+        }
+
+        #[test]
+        fn returns_err_if_not_query() {
+            assert!(parse_query("material KXvK").is_err());
+            assert!(parse_query("white pawn on i9").is_err());
+            assert!(parse_query("check and").is_err())
+        }
+    }
+}
@@ -0,0 +1,437 @@
+//! Serde support, enabled by the `serde` feature.
+//!
+//! The JSON shape is stable:
+//! - `Position`: the square name, `"e4"`
+//! - `PieceColour`: `"white"` or `"black"`
+//! - `GameResult`: the PGN token, `"1-0"`, `"0-1"`, `"1/2-1/2"` or `"*"`
+//! - `Variant`: the lichess tag name, `"Crazyhouse"`
+//! - `Board` and `Fen`: `{"fen": "...", "variant": "Standard"}`
+//! - `Tags`: an object of tag names to values, sorted by name
+//! - `PlyMovement`: `{"colour": "white", "movetext": "Nf3"}`, where `movetext` is the move as
+//!   written in the PGN, so may carry redundant disambiguation or annotation glyphs
+//! - `Ply`: `{"move_number": 1, "colour": "white", "movetext": "Nf3", "nags": [1], "comment":
+//!   "...", "variations": [[...]]}`, with `nags`, `comment` and `variations` omitted when empty
+//! - `Pgn`: `{"tags": {...}, "fen": "...", "result": "1-0", "moves": [...]}`, where each move is a
+//!   `Ply` with canonical `san` and `uci` fields added while the moves can be replayed
+//!
+//! `movetext` is authoritative when deserialising; `san` and `uci` are informational and ignored.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+
+use crate::engine;
+use crate::model::{
+    Board, Fen, GameResult, Pgn, PieceColour, Ply, PlyMovement, Position, Tags, Variant,
+};
+use crate::parse::{parse_fen, parse_position, parse_result, parse_san};
+
+#[derive(Serialize, Deserialize)]
+struct MovementRecord {
+    colour: PieceColour,
+    movetext: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PlyRecord {
+    move_number: i16,
+    colour: PieceColour,
+    movetext: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    san: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    uci: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    nags: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    variations: Vec<Vec<PlyRecord>>,
+}
+
+impl PlyRecord {
+    // The board before the ply, when known, adds the canonical SAN and UCI
+    fn new(ply: &Ply, board: Option<&Board>, variations: Vec<Vec<PlyRecord>>) -> Self {
+        let resolved = board.and_then(|board| {
+            let movement = engine::resolve(board, ply.movement()).ok()?;
+            Some((engine::san(board, &movement), engine::uci(&movement)))
+        });
+        let (san, uci) = resolved.unzip();
+        PlyRecord {
+            move_number: ply.move_number(),
+            colour: ply.movement().colour(),
+            movetext: ply.movement().to_string(),
+            san,
+            uci,
+            nags: ply.nags().to_vec(),
+            comment: ply.comment().cloned(),
+            variations,
+        }
+    }
+
+    fn into_ply<E: de::Error>(self) -> Result<Ply, E> {
+        let movement = parse_san(&self.movetext, self.colour).map_err(E::custom)?;
+        let variations = self
+            .variations
+            .into_iter()
+            .map(|line| line.into_iter().map(PlyRecord::into_ply).collect())
+            .collect::<Result<Vec<Vec<Ply>>, E>>()?;
+        Ok(Ply::new(self.move_number, movement, self.comment)
+            .with_annotations(self.nags, variations))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct BoardRecord {
+    fen: String,
+    variant: Variant,
+}
+
+#[derive(Serialize)]
+struct PgnRecordRef<'a> {
+    tags: &'a Tags,
+    fen: String,
+    result: GameResult,
+    moves: Vec<PlyRecord>,
+}
+
+#[derive(Deserialize)]
+struct PgnRecord {
+    tags: Tags,
+    fen: String,
+    result: GameResult,
+    moves: Vec<PlyRecord>,
+}
+
+impl Serialize for Position {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Position {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        parse_position(&text).map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for PieceColour {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match self {
+            PieceColour::White => "white",
+            PieceColour::Black => "black",
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for PieceColour {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "white" => Ok(PieceColour::White),
+            "black" => Ok(PieceColour::Black),
+            other => Err(de::Error::unknown_variant(other, &["white", "black"])),
+        }
+    }
+}
+
+impl Serialize for GameResult {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match self {
+            GameResult::WhiteWin => "1-0",
+            GameResult::BlackWin => "0-1",
+            GameResult::Draw => "1/2-1/2",
+            GameResult::Ongoing => "*",
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for GameResult {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        parse_result(&text).map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for Variant {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Variant {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Ok(Variant::from_tag(&text))
+    }
+}
+
+// The variant isn't part of the FEN, so it's written alongside
+impl Serialize for Board {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BoardRecord {
+            fen: self.fen(),
+            variant: self.variant(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Board {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Fen::deserialize(deserializer).map(|fen| fen.starting_board().clone())
+    }
+}
+
+impl Serialize for Fen {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.starting_board().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Fen {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let record = BoardRecord::deserialize(deserializer)?;
+        let mut fen = parse_fen(&record.fen).map_err(de::Error::custom)?;
+        fen.update_variant(record.variant);
+        Ok(fen)
+    }
+}
+
+impl Serialize for Tags {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.inner().iter().collect::<BTreeMap<_, _>>())
+    }
+}
+
+impl<'de> Deserialize<'de> for Tags {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        HashMap::deserialize(deserializer).map(Tags::new)
+    }
+}
+
+impl Serialize for PlyMovement {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        MovementRecord {
+            colour: self.colour(),
+            movetext: self.to_string(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PlyMovement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let record = MovementRecord::deserialize(deserializer)?;
+        parse_san(&record.movetext, record.colour).map_err(de::Error::custom)
+    }
+}
+
+impl Serialize for Ply {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PlyRecord::new(self, None, variations(self.variations(), None)).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Ply {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        PlyRecord::deserialize(deserializer)?.into_ply()
+    }
+}
+
+impl Serialize for Pgn {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PgnRecordRef {
+            tags: self.tags(),
+            fen: self.fen().starting_board().fen(),
+            result: self.result(),
+            moves: variation(self.ply(), Some(self.fen().starting_board().clone())),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Pgn {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let record = PgnRecord::deserialize(deserializer)?;
+        let mut fen = parse_fen(&record.fen).map_err(de::Error::custom)?;
+        // The variant isn't part of the FEN, so it's restored from the tags as when parsing
+        if let Some(variant) = record.tags.get("Variant") {
+            fen.update_variant(Variant::from_tag(variant));
+        }
+        let ply = record
+            .moves
+            .into_iter()
+            .map(PlyRecord::into_ply)
+            .collect::<Result<Vec<Ply>, D::Error>>()?;
+        Ok(Pgn::new(record.tags, fen, record.result, ply))
+    }
+}
+
+// Each ply with its SAN and UCI move, until one can't be replayed
+fn variation(plies: &[Ply], mut board: Option<Board>) -> Vec<PlyRecord> {
+    plies
+        .iter()
+        .map(|ply| {
+            let record = PlyRecord::new(
+                ply,
+                board.as_ref(),
+                variations(ply.variations(), board.as_ref()),
+            );
+            board = board.take().and_then(|mut current| {
+                let movement = engine::resolve(&current, ply.movement()).ok()?;
+                engine::make_move(&mut current, &movement);
+                Some(current)
+            });
+            record
+        })
+        .collect()
+}
+
+// Variations start from the board before the ply they replace
+fn variations(lines: &[Vec<Ply>], board: Option<&Board>) -> Vec<Vec<PlyRecord>> {
+    lines
+        .iter()
+        .map(|line| variation(line, board.cloned()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::model::{Piece, PieceType};
+    use crate::parse::parse;
+
+    mod position_tests {
+        use super::*;
+
+        #[test]
+        fn serialises_square_name() {
+            let position = Position::new(3, 4);
+            assert_eq!(serde_json::to_string(&position).unwrap(), "\"e4\"");
+            assert_eq!(
+                serde_json::from_str::<Position>("\"e4\"").unwrap(),
+                position
+            );
+            assert!(serde_json::from_str::<Position>("\"i9\"").is_err())
+        }
+    }
+
+    mod board_tests {
+        use super::*;
+
+        #[test]
+        fn serialises_fen_and_variant() {
+            let fen = "3Q~4/8/8/8/8/8/8/8[Np] w - - 0 1";
+            let mut board = parse_fen(fen).unwrap().starting_board().clone();
+            board.update_variant(Variant::Crazyhouse);
+            let json = serde_json::to_string(&board).unwrap();
+            assert_eq!(json, format!(r#"{{"fen":"{fen}","variant":"Crazyhouse"}}"#));
+            let parsed = serde_json::from_str::<Board>(&json).unwrap();
+            assert_eq!(parsed.variant(), Variant::Crazyhouse);
+            assert_eq!(parsed, board)
+        }
+    }
+
+    mod ply_tests {
+        use super::*;
+
+        #[test]
+        fn serialises_movetext_and_comment() {
+            let ply = Ply::new(
+                3,
+                parse_san("exd5+", PieceColour::Black).unwrap(),
+                Some("[%clk 0:04:58.9]".to_string()),
+            );
+            let json = serde_json::to_string(&ply).unwrap();
+            assert_eq!(
+                json,
+                r#"{"move_number":3,"colour":"black","movetext":"exd5+","comment":"[%clk 0:04:58.9]"}"#
+            );
+            assert_eq!(serde_json::from_str::<Ply>(&json).unwrap(), ply)
+        }
+
+        #[test]
+        fn round_trips_movement() {
+            let movement = parse_san("N@f3", PieceColour::White).unwrap();
+            let json = serde_json::to_string(&movement).unwrap();
+            assert_eq!(json, r#"{"colour":"white","movetext":"N@f3"}"#);
+            let parsed = serde_json::from_str::<PlyMovement>(&json).unwrap();
+            assert!(matches!(
+                parsed,
+                PlyMovement::Drop { movement, .. }
+                    if movement.piece() == Piece::new(PieceColour::White, PieceType::Knight)
+            ))
+        }
+    }
+
+    mod pgn_tests {
+        use super::*;
+
+        #[test]
+        fn serialises_moves_as_san_and_uci() {
+            let text = "[White \"A\"]\n[Black \"B\"]\n[Result \"1-0\"]\n\n1. e4 e5 2. Qh5 Nc6 \
+                        3. Bc4 Nf6 4. Qxf7# 1-0\n";
+            let pgn = parse(text).unwrap().remove(0);
+            let json: serde_json::Value = serde_json::to_value(&pgn).unwrap();
+
+            assert_eq!(
+                json["tags"],
+                serde_json::json!({"Black": "B", "White": "A"})
+            );
+            assert_eq!(json["result"], "1-0");
+            assert_eq!(
+                json["fen"],
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+            );
+            assert_eq!(
+                json["moves"][6],
+                serde_json::json!({
+                    "move_number": 4,
+                    "colour": "white",
+                    "movetext": "Qxf7#",
+                    "san": "Qxf7#",
+                    "uci": "h5f7"
+                })
+            )
+        }
+
+        #[test]
+        fn serialises_canonical_san_alongside_movetext() {
+            let text = "[Result \"*\"]\n\n1. e4 $1 e5 2. Ngf3 (2. Nf3 Nc6) Nc6 *\n";
+            let pgn = parse(text).unwrap().remove(0);
+            let json: serde_json::Value = serde_json::to_value(&pgn).unwrap();
+
+            assert_eq!(json["moves"][0]["nags"], serde_json::json!([1]));
+            assert_eq!(json["moves"][2]["movetext"], "Ngf3");
+            assert_eq!(json["moves"][2]["san"], "Nf3");
+            assert_eq!(json["moves"][2]["variations"][0][1]["uci"], "b8c6");
+            assert_eq!(serde_json::from_value::<Pgn>(json).unwrap(), pgn)
+        }
+
+        #[test]
+        fn round_trips_acceptance_games() {
+            for entry in fs::read_dir("./resources/test/acceptance").unwrap() {
+                let text = fs::read_to_string(entry.unwrap().path()).unwrap();
+                for pgn in parse(&text).unwrap() {
+                    let json = serde_json::to_string(&pgn).unwrap();
+                    assert_eq!(serde_json::from_str::<Pgn>(&json).unwrap(), pgn)
+                }
+            }
+        }
+
+        #[test]
+        fn restores_variant_from_tags() {
+            let text = "[Variant \"Crazyhouse\"]\n[Result \"*\"]\n\n1. e4 d5 2. exd5 Qxd5 *\n";
+            let pgn = parse(text).unwrap().remove(0);
+            let json = serde_json::to_string(&pgn).unwrap();
+            let parsed = serde_json::from_str::<Pgn>(&json).unwrap();
+            assert_eq!(parsed.fen().starting_board().variant(), Variant::Crazyhouse);
+            assert_eq!(parsed, pgn)
+        }
+    }
+}
@@ -0,0 +1,344 @@
+use std::fmt::Display;
+
+use crate::model::{Board, Piece, PieceColour, PieceType, Position};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Formation {
+    Carlsbad,
+    Hedgehog,
+    IsolatedQueensPawn,
+    Maroczy,
+}
+
+impl Formation {
+    // Part of the name, or "iqp"
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.to_string().to_lowercase().contains(&query)
+            || (*self == Formation::IsolatedQueensPawn && query == "iqp")
+    }
+}
+
+impl Display for Formation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Formation::Carlsbad => write!(f, "Carlsbad"),
+            Formation::Hedgehog => write!(f, "Hedgehog"),
+            Formation::IsolatedQueensPawn => write!(f, "Isolated Queen's Pawn"),
+            Formation::Maroczy => write!(f, "Maroczy Bind"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Pawns {
+    isolated: Vec<Position>,
+    doubled: Vec<Position>,
+    backward: Vec<Position>,
+    passed: Vec<Position>,
+    islands: usize,
+}
+
+impl Pawns {
+    pub fn isolated(&self) -> &[Position] {
+        &self.isolated
+    }
+
+    pub fn doubled(&self) -> &[Position] {
+        &self.doubled
+    }
+
+    pub fn backward(&self) -> &[Position] {
+        &self.backward
+    }
+
+    pub fn passed(&self) -> &[Position] {
+        &self.passed
+    }
+
+    pub fn islands(&self) -> usize {
+        self.islands
+    }
+}
+
+impl Display for Pawns {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.islands {
+            1 => write!(f, "1 island")?,
+            islands => write!(f, "{islands} islands")?,
+        }
+        for (name, positions) in [
+            ("isolated", &self.isolated),
+            ("doubled", &self.doubled),
+            ("backward", &self.backward),
+            ("passed", &self.passed),
+        ] {
+            if !positions.is_empty() {
+                let squares: Vec<String> = positions.iter().map(Position::to_string).collect();
+                write!(f, "; {name} {}", squares.join(" "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Structure {
+    white: Pawns,
+    black: Pawns,
+    // Each with the side whose pawns give it its name
+    formations: Vec<(Formation, PieceColour)>,
+}
+
+impl Structure {
+    pub fn pawns(&self, colour: PieceColour) -> &Pawns {
+        match colour {
+            PieceColour::White => &self.white,
+            PieceColour::Black => &self.black,
+        }
+    }
+
+    pub fn formations(&self) -> &[(Formation, PieceColour)] {
+        &self.formations
+    }
+
+    pub fn has(&self, formation: Formation) -> bool {
+        self.formations.iter().any(|(found, _)| *found == formation)
+    }
+}
+
+pub fn analyse(board: &Board) -> Structure {
+    let formations = [PieceColour::White, PieceColour::Black]
+        .into_iter()
+        .flat_map(|colour| {
+            formations(board, colour)
+                .into_iter()
+                .map(move |formation| (formation, colour))
+        })
+        .collect();
+
+    Structure {
+        white: pawns(board, PieceColour::White),
+        black: pawns(board, PieceColour::Black),
+        formations,
+    }
+}
+
+fn pawns(board: &Board, colour: PieceColour) -> Pawns {
+    let own = positions(board, colour);
+    let opponent = positions(board, colour.opposite());
+    // Ranks counted from the side's own back rank
+    let rank = |position: &Position| match colour {
+        PieceColour::White => position.row(),
+        PieceColour::Black => 7 - position.row(),
+    };
+    let adjacent = |pawn: &Position| {
+        own.iter()
+            .filter(|other| (other.col() - pawn.col()).abs() == 1)
+            .collect::<Vec<&Position>>()
+    };
+
+    let isolated = |pawn: &&Position| adjacent(pawn).is_empty();
+    let doubled =
+        |pawn: &&Position| own.iter().filter(|other| other.col() == pawn.col()).count() > 1;
+    let passed = |pawn: &&Position| {
+        !opponent
+            .iter()
+            .any(|other| (other.col() - pawn.col()).abs() <= 1 && rank(other) > rank(pawn))
+    };
+    // Every neighbour has advanced past it, and an opposing pawn guards the square in front
+    let backward = |pawn: &&Position| {
+        let neighbours = adjacent(pawn);
+        !neighbours.is_empty()
+            && neighbours.iter().all(|other| rank(other) > rank(pawn))
+            && opponent
+                .iter()
+                .any(|other| (other.col() - pawn.col()).abs() == 1 && rank(other) == rank(pawn) + 2)
+    };
+
+    let occupied_files: Vec<bool> = (0..8)
+        .map(|col| own.iter().any(|pawn| pawn.col() == col))
+        .collect();
+    let islands = (0..occupied_files.len())
+        .filter(|&file| occupied_files[file] && (file == 0 || !occupied_files[file - 1]))
+        .count();
+
+    Pawns {
+        isolated: own.iter().filter(isolated).copied().collect(),
+        doubled: own.iter().filter(doubled).copied().collect(),
+        backward: own.iter().filter(backward).copied().collect(),
+        passed: own.iter().filter(passed).copied().collect(),
+        islands,
+    }
+}
+
+fn formations(board: &Board, colour: PieceColour) -> Vec<Formation> {
+    let opponent = colour.opposite();
+    let own = |square| has_pawn(board, colour, square);
+    let theirs = |square| has_pawn(board, opponent, square);
+    let own_file = |file| has_file(board, colour, file);
+    let their_file = |file| has_file(board, opponent, file);
+
+    let mut formations = Vec::new();
+    if own("d4")
+        && own("e3")
+        && !own_file(b'c')
+        && theirs("c3")
+        && theirs("d4")
+        && !their_file(b'e')
+    {
+        formations.push(Formation::Carlsbad);
+    }
+    if own("a3") && own("b3") && own("d3") && own("e3") && !own_file(b'c') && !their_file(b'd') {
+        formations.push(Formation::Hedgehog);
+    }
+    if own("d4") && !own_file(b'c') && !own_file(b'e') && !their_file(b'd') {
+        formations.push(Formation::IsolatedQueensPawn);
+    }
+    if own("c4") && own("e4") && !own_file(b'd') && !their_file(b'c') && their_file(b'd') {
+        formations.push(Formation::Maroczy);
+    }
+    formations
+}
+
+fn positions(board: &Board, colour: PieceColour) -> Vec<Position> {
+    board
+        .bitboard(Piece::new(colour, PieceType::Pawn))
+        .positions()
+        .collect()
+}
+
+// Squares are given from the side's own point of view, so "d4" is d5 for black
+fn has_pawn(board: &Board, colour: PieceColour, square: &str) -> bool {
+    let &[file, rank] = square.as_bytes() else {
+        return false;
+    };
+    let col = i8::try_from(file - b'a').unwrap_or_default();
+    let row = i8::try_from(rank - b'1').unwrap_or_default();
+    let row = match colour {
+        PieceColour::White => row,
+        PieceColour::Black => 7 - row,
+    };
+    board.occupant(Position::new(row, col)) == Some(&Piece::new(colour, PieceType::Pawn))
+}
+
+fn has_file(board: &Board, colour: PieceColour, file: u8) -> bool {
+    let col = i8::try_from(file - b'a').unwrap_or_default();
+    positions(board, colour)
+        .iter()
+        .any(|pawn| pawn.col() == col)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine;
+    use crate::parse::{parse_fen, parse_movetext, DEFAULT_FEN};
+
+    mod analyse_tests {
+        use super::*;
+
+        #[test]
+        fn finds_weak_and_passed_pawns() {
+            let structure = analyse(&board("4k3/8/8/3p4/4P3/2P5/1PP4P/4K3 w - - 0 1"));
+            let white = structure.pawns(PieceColour::White);
+            assert_eq!(white.islands(), 3);
+            assert_eq!(
+                white.isolated(),
+                &[Position::new(1, 7), Position::new(3, 4)]
+            );
+            assert_eq!(white.doubled(), &[Position::new(1, 2), Position::new(2, 2)]);
+            assert_eq!(white.passed(), &[Position::new(1, 1), Position::new(1, 7)]);
+
+            let black = structure.pawns(PieceColour::Black);
+            assert_eq!(black.isolated(), &[Position::new(4, 3)]);
+            assert!(black.passed().is_empty())
+        }
+
+        #[test]
+        fn finds_backward_pawn() {
+            let structure = analyse(&board("4k3/8/8/8/1p6/1P6/P7/4K3 w - - 0 1"));
+            assert_eq!(
+                structure.pawns(PieceColour::White).backward(),
+                &[Position::new(1, 0)]
+            )
+        }
+
+        #[test]
+        fn describes_pawns() {
+            let structure = analyse(&board("4k3/8/8/8/8/8/P6P/4K3 w - - 0 1"));
+            assert_eq!(
+                structure.pawns(PieceColour::White).to_string(),
+                "2 islands; isolated a2 h2; passed a2 h2"
+            )
+        }
+
+        #[test]
+        fn recognises_carlsbad() {
+            let structure = analyse(&final_board(
+                "1. d4 d5 2. c4 e6 3. Nc3 Nf6 4. cxd5 exd5 5. Bg5 c6 6. e3",
+            ));
+            assert_eq!(
+                structure.formations(),
+                &[(Formation::Carlsbad, PieceColour::White)]
+            )
+        }
+
+        #[test]
+        fn recognises_isolated_queens_pawn() {
+            let structure = analyse(&final_board(
+                "1. e4 c6 2. d4 d5 3. exd5 cxd5 4. c4 Nf6 5. Nc3 e6 6. cxd5 exd5",
+            ));
+            assert!(!structure.has(Formation::IsolatedQueensPawn));
+
+            let structure = analyse(&final_board(
+                "1. d4 d5 2. c4 e6 3. Nc3 c5 4. cxd5 exd5 5. Nf3 Nc6 6. g3 Nf6 7. Bg2 cxd4 8. Nxd4",
+            ));
+            assert_eq!(
+                structure.formations(),
+                &[(Formation::IsolatedQueensPawn, PieceColour::Black)]
+            );
+
+            let structure = analyse(&board("4k3/pp3ppp/4p3/8/3P4/8/PP3PPP/4K3 w - - 0 1"));
+            assert_eq!(
+                structure.formations(),
+                &[(Formation::IsolatedQueensPawn, PieceColour::White)]
+            )
+        }
+
+        #[test]
+        fn recognises_maroczy_and_hedgehog() {
+            let structure = analyse(&board("4k3/5ppp/pp1pp3/8/2P1P3/8/PP3PPP/4K3 w - - 0 1"));
+            assert_eq!(
+                structure.formations(),
+                &[
+                    (Formation::Maroczy, PieceColour::White),
+                    (Formation::Hedgehog, PieceColour::Black)
+                ]
+            )
+        }
+
+        fn board(fen: &str) -> Board {
+            parse_fen(fen).unwrap().starting_board().clone()
+        }
+
+        fn final_board(moves: &str) -> Board {
+            let board = board(DEFAULT_FEN);
+            let mut boards =
+                engine::execute_moves(&board, &parse_movetext(moves).unwrap()).unwrap();
+            boards.pop().unwrap()
+        }
+    }
+
+    mod matches_tests {
+        use super::*;
+
+        #[test]
+        fn matches_name_or_abbreviation() {
+            assert!(Formation::Maroczy.matches("maroczy"));
+            assert!(Formation::IsolatedQueensPawn.matches("IQP"));
+            assert!(Formation::IsolatedQueensPawn.matches("isolated"));
+            assert!(!Formation::Carlsbad.matches("hedgehog"))
+        }
+    }
+}
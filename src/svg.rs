@@ -0,0 +1,595 @@
+use crate::engine;
+use crate::model::{
+    Board, Game, Move, Piece, PieceColour, PieceType, Ply, Position, COLUMNS, MAX_POSITION, ROWS,
+};
+
+const SQUARES: f64 = 8.0;
+const FONT: &str = "'DejaVu Sans', 'Segoe UI Symbol', 'Noto Sans Symbols 2', sans-serif";
+// Asks for the text rather than the emoji form of the figurines
+const TEXT_PRESENTATION: char = '\u{FE0E}';
+const MARK_OPACITY: f64 = 0.8;
+const HIGHLIGHT_OPACITY: f64 = 0.8;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MarkColour {
+    Green,
+    Red,
+    Yellow,
+    Blue,
+}
+
+impl MarkColour {
+    // The letter used by `[%cal]` and `[%csl]`
+    fn from_letter(letter: char) -> Option<MarkColour> {
+        match letter {
+            'G' => Some(MarkColour::Green),
+            'R' => Some(MarkColour::Red),
+            'Y' => Some(MarkColour::Yellow),
+            'B' => Some(MarkColour::Blue),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            MarkColour::Green => "green",
+            MarkColour::Red => "red",
+            MarkColour::Yellow => "yellow",
+            MarkColour::Blue => "blue",
+        }
+    }
+
+    fn fill(&self) -> &'static str {
+        match self {
+            MarkColour::Green => "#15781b",
+            MarkColour::Red => "#882020",
+            MarkColour::Yellow => "#e68f00",
+            MarkColour::Blue => "#003088",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Arrow {
+    colour: MarkColour,
+    from: Position,
+    to: Position,
+}
+
+impl Arrow {
+    pub fn new(colour: MarkColour, from: Position, to: Position) -> Self {
+        Arrow { colour, from, to }
+    }
+
+    pub fn colour(&self) -> MarkColour {
+        self.colour
+    }
+
+    pub fn from(&self) -> Position {
+        self.from
+    }
+
+    pub fn to(&self) -> Position {
+        self.to
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SquareMark {
+    colour: MarkColour,
+    position: Position,
+}
+
+impl SquareMark {
+    pub fn new(colour: MarkColour, position: Position) -> Self {
+        SquareMark { colour, position }
+    }
+
+    pub fn colour(&self) -> MarkColour {
+        self.colour
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+}
+
+// Arrows and marked squares drawn over the board
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Markup {
+    arrows: Vec<Arrow>,
+    squares: Vec<SquareMark>,
+}
+
+impl Markup {
+    pub fn new(arrows: Vec<Arrow>, squares: Vec<SquareMark>) -> Self {
+        Markup { arrows, squares }
+    }
+
+    // From `[%cal Ge2e4,Rd8d1]` and `[%csl Yd5]` in the ply's comment; malformed entries are skipped
+    pub fn from_ply(ply: &Ply) -> Markup {
+        let entries = |name: &str| -> Vec<(MarkColour, String)> {
+            ply.command(name)
+                .into_iter()
+                .flat_map(|argument| argument.split(','))
+                .filter_map(|entry| {
+                    let mut chars = entry.trim().chars();
+                    let colour = MarkColour::from_letter(chars.next()?)?;
+                    Some((colour, chars.as_str().to_string()))
+                })
+                .collect()
+        };
+
+        let arrows = entries("cal")
+            .into_iter()
+            .filter_map(|(colour, squares)| {
+                let from = squares.get(..2)?.parse().ok()?;
+                let to = squares.get(2..)?.parse().ok()?;
+                Some(Arrow::new(colour, from, to))
+            })
+            .collect();
+        let squares = entries("csl")
+            .into_iter()
+            .filter_map(|(colour, square)| Some(SquareMark::new(colour, square.parse().ok()?)))
+            .collect();
+        Markup { arrows, squares }
+    }
+
+    pub fn arrows(&self) -> &[Arrow] {
+        &self.arrows
+    }
+
+    pub fn squares(&self) -> &[SquareMark] {
+        &self.squares
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.arrows.is_empty() && self.squares.is_empty()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SvgOptions {
+    square_size: u32,
+    light: String,
+    dark: String,
+    highlight: String,
+    coordinates: bool,
+    perspective: PieceColour,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            square_size: 45,
+            light: "#f0d9b5".to_string(),
+            dark: "#b58863".to_string(),
+            highlight: "#cdd16a".to_string(),
+            coordinates: true,
+            perspective: PieceColour::White,
+        }
+    }
+}
+
+impl SvgOptions {
+    pub fn square_size(mut self, square_size: u32) -> Self {
+        self.square_size = square_size;
+        self
+    }
+
+    // Any SVG paint, such as "#f0d9b5" or "wheat"
+    pub fn light(mut self, light: impl Into<String>) -> Self {
+        self.light = light.into();
+        self
+    }
+
+    pub fn dark(mut self, dark: impl Into<String>) -> Self {
+        self.dark = dark.into();
+        self
+    }
+
+    // Drawn over the squares the last move left and reached
+    pub fn highlight(mut self, highlight: impl Into<String>) -> Self {
+        self.highlight = highlight.into();
+        self
+    }
+
+    pub fn coordinates(mut self, coordinates: bool) -> Self {
+        self.coordinates = coordinates;
+        self
+    }
+
+    // The side shown at the bottom of the diagram
+    pub fn perspective(mut self, perspective: PieceColour) -> Self {
+        self.perspective = perspective;
+        self
+    }
+
+    fn size(&self) -> f64 {
+        f64::from(self.square_size)
+    }
+
+    // Top left corner of the square
+    fn origin(&self, position: Position) -> (f64, f64) {
+        let (file, rank) = match self.perspective {
+            PieceColour::White => (position.col(), MAX_POSITION - position.row()),
+            PieceColour::Black => (MAX_POSITION - position.col(), position.row()),
+        };
+        (f64::from(file) * self.size(), f64::from(rank) * self.size())
+    }
+
+    fn centre(&self, position: Position) -> (f64, f64) {
+        let (x, y) = self.origin(position);
+        (x + self.size() / 2.0, y + self.size() / 2.0)
+    }
+}
+
+pub fn svg(
+    board: &Board,
+    last_move: Option<&Move>,
+    markup: &Markup,
+    options: &SvgOptions,
+) -> String {
+    let width = number(options.size() * SQUARES);
+    let mut elements = vec![format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {width} {width}\" \
+         width=\"{width}\" height=\"{width}\">"
+    )];
+    elements.extend(markers(markup));
+
+    let positions = (0..=MAX_POSITION)
+        .flat_map(|row| (0..=MAX_POSITION).map(move |col| Position::new(row, col)));
+    for position in positions.clone() {
+        let fill = if is_dark(position) {
+            &options.dark
+        } else {
+            &options.light
+        };
+        elements.push(square(position, fill, 1.0, options));
+    }
+
+    if let Some(movement) = last_move {
+        elements.push(square(
+            movement.to(),
+            &options.highlight,
+            HIGHLIGHT_OPACITY,
+            options,
+        ));
+        if !movement.is_drop() {
+            elements.push(square(
+                movement.from(),
+                &options.highlight,
+                HIGHLIGHT_OPACITY,
+                options,
+            ));
+        }
+    }
+
+    if options.coordinates {
+        elements.extend(coordinates(options));
+    }
+
+    for mark in markup.squares() {
+        let (x, y) = options.centre(mark.position());
+        elements.push(format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" stroke=\"{}\" \
+             stroke-width=\"{}\" opacity=\"{MARK_OPACITY}\"/>",
+            number(x),
+            number(y),
+            number(options.size() * 0.45),
+            mark.colour().fill(),
+            number(options.size() / 15.0)
+        ));
+    }
+
+    for position in positions {
+        if let Some(piece) = board.occupant(position) {
+            elements.push(piece_text(piece, position, options));
+        }
+    }
+
+    for arrow in markup.arrows() {
+        elements.push(arrow_line(arrow, options));
+    }
+
+    elements.push("</svg>".to_string());
+    elements.join("\n") + "\n"
+}
+
+// The position after `ply` plies of the game, with the move that reached it and its markup
+pub fn ply_svg(game: &Game, ply: usize, options: &SvgOptions) -> Option<String> {
+    let board = game.boards().get(ply)?;
+    let played = ply
+        .checked_sub(1)
+        .and_then(|index| Some((&game.boards()[index], game.pgn().ply().get(index)?)));
+
+    let last_move = played.and_then(|(before, ply)| engine::resolve(before, ply.movement()).ok());
+    let markup = played.map_or_else(Markup::default, |(_, ply)| Markup::from_ply(ply));
+    Some(svg(board, last_move.as_ref(), &markup, options))
+}
+
+fn square(position: Position, fill: &str, opacity: f64, options: &SvgOptions) -> String {
+    let (x, y) = options.origin(position);
+    let size = number(options.size());
+    let opacity = if opacity < 1.0 {
+        format!(" opacity=\"{opacity}\"")
+    } else {
+        String::new()
+    };
+    format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{size}\" height=\"{size}\" fill=\"{}\"{opacity}/>",
+        number(x),
+        number(y),
+        escape(fill)
+    )
+}
+
+// Ranks down the left edge and files along the bottom, in the opposite square colour
+fn coordinates(options: &SvgOptions) -> Vec<String> {
+    let size = options.size();
+    let label = |position: Position, text: char, bottom: bool| {
+        let (x, y) = options.origin(position);
+        let fill = if is_dark(position) {
+            &options.light
+        } else {
+            &options.dark
+        };
+        let (x, y, anchor) = if bottom {
+            (x + size * 0.95, y + size * 0.95, "end")
+        } else {
+            (x + size * 0.05, y + size * 0.25, "start")
+        };
+        format!(
+            "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" \
+             font-weight=\"bold\" text-anchor=\"{anchor}\" fill=\"{}\">{text}</text>",
+            number(x),
+            number(y),
+            number(size * 0.22),
+            escape(fill)
+        )
+    };
+    let (left, bottom) = match options.perspective {
+        PieceColour::White => (0, 0),
+        PieceColour::Black => (MAX_POSITION, MAX_POSITION),
+    };
+
+    let ranks = ROWS
+        .chars()
+        .zip(0..)
+        .map(|(rank, row)| label(Position::new(row, left), rank, false));
+    let files = COLUMNS
+        .chars()
+        .zip(0..)
+        .map(|(file, col)| label(Position::new(bottom, col), file, true));
+    ranks.chain(files).collect()
+}
+
+// Solid figurines for both sides, with white ones filled white and outlined
+fn piece_text(piece: &Piece, position: Position, options: &SvgOptions) -> String {
+    let (x, y) = options.centre(position);
+    let figurine = match piece.piece_type() {
+        PieceType::King => '♚',
+        PieceType::Queen => '♛',
+        PieceType::Rook => '♜',
+        PieceType::Bishop => '♝',
+        PieceType::Knight => '♞',
+        PieceType::Pawn => '♟',
+    };
+    let paint = match piece.colour() {
+        PieceColour::White => format!(
+            "fill=\"#ffffff\" stroke=\"#000000\" stroke-width=\"{}\"",
+            number(options.size() / 30.0)
+        ),
+        PieceColour::Black => "fill=\"#000000\"".to_string(),
+    };
+    format!(
+        "<text x=\"{}\" y=\"{}\" font-family=\"{FONT}\" font-size=\"{}\" text-anchor=\"middle\" \
+         dominant-baseline=\"central\" {paint}>{figurine}{TEXT_PRESENTATION}</text>",
+        number(x),
+        number(y),
+        number(options.size() * 0.8)
+    )
+}
+
+// One arrowhead per colour in use, sized in multiples of the line width
+fn markers(markup: &Markup) -> Vec<String> {
+    let mut colours: Vec<MarkColour> = Vec::new();
+    for arrow in markup.arrows() {
+        if !colours.contains(&arrow.colour()) {
+            colours.push(arrow.colour());
+        }
+    }
+    if colours.is_empty() {
+        return Vec::new();
+    }
+
+    let mut elements = vec!["<defs>".to_string()];
+    elements.extend(colours.into_iter().map(|colour| {
+        format!(
+            "<marker id=\"arrowhead-{}\" markerWidth=\"4\" markerHeight=\"4\" refX=\"2.05\" \
+             refY=\"2\" orient=\"auto\"><path d=\"M0,0 V4 L3,2 Z\" fill=\"{}\"/></marker>",
+            colour.name(),
+            colour.fill()
+        )
+    }));
+    elements.push("</defs>".to_string());
+    elements
+}
+
+// Centre to centre, stopping short so the arrowhead's point lands just before the centre
+fn arrow_line(arrow: &Arrow, options: &SvgOptions) -> String {
+    let (x1, y1) = options.centre(arrow.from());
+    let (x2, y2) = options.centre(arrow.to());
+    let length = (x2 - x1).hypot(y2 - y1);
+    let shortened = if length > 0.0 {
+        (length - options.size() * 0.3).max(0.0) / length
+    } else {
+        0.0
+    };
+    format!(
+        "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"{}\" \
+         stroke-linecap=\"round\" opacity=\"{MARK_OPACITY}\" marker-end=\"url(#arrowhead-{})\"/>",
+        number(x1),
+        number(y1),
+        number(x1 + (x2 - x1) * shortened),
+        number(y1 + (y2 - y1) * shortened),
+        arrow.colour().fill(),
+        number(options.size() * 0.15),
+        arrow.colour().name()
+    )
+}
+
+// a1 is dark
+fn is_dark(position: Position) -> bool {
+    (position.row() + position.col()) % 2 == 0
+}
+
+// Rounded to two decimal places, without a trailing ".0"
+fn number(value: f64) -> String {
+    format!("{}", (value * 100.0).round() / 100.0)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{parse, parse_fen, parse_san, DEFAULT_FEN};
+
+    mod markup_tests {
+        use super::*;
+
+        #[test]
+        fn reads_arrows_and_squares() {
+            let ply = ply("[%cal Ge2e4,Rd8d1] Threatening mate [%csl Yd5]");
+            let markup = Markup::from_ply(&ply);
+            assert_eq!(
+                markup.arrows(),
+                &[
+                    Arrow::new(MarkColour::Green, position("e2"), position("e4")),
+                    Arrow::new(MarkColour::Red, position("d8"), position("d1"))
+                ]
+            );
+            assert_eq!(
+                markup.squares(),
+                &[SquareMark::new(MarkColour::Yellow, position("d5"))]
+            )
+        }
+
+        #[test]
+        fn skips_malformed_entries() {
+            let markup = Markup::from_ply(&ply("[%cal Xe2e4,Ge2,Gb1c3] [%csl Gz9]"));
+            assert_eq!(
+                markup.arrows(),
+                &[Arrow::new(
+                    MarkColour::Green,
+                    position("b1"),
+                    position("c3")
+                )]
+            );
+            assert!(markup.squares().is_empty());
+            assert!(Markup::from_ply(&ply("No markup")).is_empty())
+        }
+
+        fn ply(comment: &str) -> Ply {
+            let movement = parse_san("e4", PieceColour::White).unwrap();
+            Ply::new(1, movement, Some(comment.to_string()))
+        }
+    }
+
+    mod svg_tests {
+        use super::*;
+
+        #[test]
+        fn draws_squares_and_pieces() {
+            let board = board(DEFAULT_FEN);
+            let svg = svg(&board, None, &Markup::default(), &SvgOptions::default());
+            assert!(svg
+                .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 360 360\""));
+            assert_eq!(svg.matches("<rect ").count(), 64);
+            assert_eq!(svg.matches("dominant-baseline").count(), 32);
+            // a1 is dark, in the bottom left corner, with its rank and file labels
+            assert!(svg
+                .contains("<rect x=\"0\" y=\"315\" width=\"45\" height=\"45\" fill=\"#b58863\"/>"));
+            assert!(svg.contains(">1</text>"));
+            assert!(svg.contains(">a</text>"));
+            assert!(!svg.contains("<defs>"));
+            assert!(svg.trim_end().ends_with("</svg>"))
+        }
+
+        #[test]
+        fn flips_and_restyles_the_board() {
+            let board = board("4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+            let options = SvgOptions::default()
+                .perspective(PieceColour::Black)
+                .square_size(10)
+                .dark("<dark>")
+                .coordinates(false);
+            let svg = svg(&board, None, &Markup::default(), &options);
+            // a1 is now in the top right corner
+            assert!(svg.contains(
+                "<rect x=\"70\" y=\"0\" width=\"10\" height=\"10\" fill=\"&lt;dark&gt;\"/>"
+            ));
+            // The white king on e1 is drawn near the top
+            assert!(svg.contains("<text x=\"35\" y=\"5\""));
+            assert!(!svg.contains("font-weight"))
+        }
+
+        #[test]
+        fn highlights_last_move_and_draws_markup() {
+            let board = board(DEFAULT_FEN);
+            let movement = Move::new(
+                Piece::new(PieceColour::White, PieceType::Pawn),
+                position("e2"),
+                position("e4"),
+                None,
+            );
+            let markup = Markup::new(
+                vec![Arrow::new(
+                    MarkColour::Green,
+                    position("g1"),
+                    position("f3"),
+                )],
+                vec![SquareMark::new(MarkColour::Red, position("d5"))],
+            );
+            let svg = svg(&board, Some(&movement), &markup, &SvgOptions::default());
+            assert_eq!(svg.matches("fill=\"#cdd16a\" opacity=\"0.8\"").count(), 2);
+            assert!(svg.contains("<marker id=\"arrowhead-green\""));
+            assert!(svg.contains("marker-end=\"url(#arrowhead-green)\""));
+            assert!(svg.contains("<circle cx=\"157.5\" cy=\"157.5\""));
+            assert!(!svg.contains("arrowhead-red"))
+        }
+
+        #[test]
+        fn draws_plies_of_a_game() {
+            let pgn = parse("[Result \"*\"]\n\n1. e4 {[%cal Gg1f3]} e5 *\n")
+                .unwrap()
+                .remove(0);
+            let boards = engine::execute_moves(pgn.fen().starting_board(), pgn.ply()).unwrap();
+            let game = Game::new(pgn, boards);
+            let options = SvgOptions::default();
+
+            let start = ply_svg(&game, 0, &options).unwrap();
+            assert!(!start.contains("opacity=\"0.8\"/>"));
+            let first = ply_svg(&game, 1, &options).unwrap();
+            assert!(first.contains("arrowhead-green"));
+            assert_eq!(first.matches("fill=\"#cdd16a\"").count(), 2);
+            let second = ply_svg(&game, 2, &options).unwrap();
+            assert!(!second.contains("arrowhead"));
+            assert_eq!(ply_svg(&game, 3, &options), None)
+        }
+    }
+
+    fn board(fen: &str) -> Board {
+        parse_fen(fen).unwrap().starting_board().clone()
+    }
+
+    fn position(square: &str) -> Position {
+        square.parse().unwrap()
+    }
+}
@@ -0,0 +1,224 @@
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::{
+    error::UciError,
+    info::{self, Info},
+};
+
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+const QUIT_TIMEOUT: Duration = Duration::from_millis(200);
+
+pub struct UciEngine {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    searching: bool,
+}
+
+impl UciEngine {
+    pub fn spawn(path: impl AsRef<Path>) -> Result<Self, UciError> {
+        let path = path.as_ref();
+        let mut child = Command::new(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| UciError::new(format!("Failed to start '{}': {e}", path.display())))?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| UciError::new("Failed to open engine stdin"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| UciError::new("Failed to open engine stdout"))?;
+
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut engine = UciEngine {
+            child,
+            stdin,
+            lines,
+            searching: false,
+        };
+        engine.send("uci")?;
+        engine.wait_for("uciok", Some(RESPONSE_TIMEOUT))?;
+        Ok(engine)
+    }
+
+    pub fn set_option(&mut self, name: &str, value: &str) -> Result<(), UciError> {
+        self.send(&format!("setoption name {name} value {value}"))
+    }
+
+    pub fn is_ready(&mut self) -> Result<(), UciError> {
+        self.send("isready")?;
+        self.wait_for("readyok", Some(RESPONSE_TIMEOUT))?;
+        Ok(())
+    }
+
+    // Any search already running is stopped first
+    pub fn go_infinite(&mut self, fen: &str) -> Result<(), UciError> {
+        self.stop()?;
+        self.send(&format!("position fen {fen}"))?;
+        self.send("go infinite")?;
+        self.searching = true;
+        Ok(())
+    }
+
+    // Blocks until the engine reports its best move; returns the deepest line for each multipv
+    pub fn go_depth(&mut self, fen: &str, depth: usize) -> Result<Vec<Info>, UciError> {
+        self.stop()?;
+        self.send(&format!("position fen {fen}"))?;
+        self.send(&format!("go depth {depth}"))?;
+        let infos = self.wait_for("bestmove", None)?;
+        Ok(latest(infos))
+    }
+
+    pub fn stop(&mut self) -> Result<(), UciError> {
+        if self.searching {
+            self.send("stop")?;
+            self.searching = false;
+            self.wait_for("bestmove", Some(RESPONSE_TIMEOUT))?;
+        }
+        Ok(())
+    }
+
+    // Non-blocking; returns whatever analysis has arrived since the last call
+    pub fn poll(&mut self) -> Vec<Info> {
+        self.lines
+            .try_iter()
+            .filter_map(|line| info::parse(&line))
+            .collect()
+    }
+
+    fn send(&mut self, command: &str) -> Result<(), UciError> {
+        writeln!(self.stdin, "{command}")
+            .and_then(|()| self.stdin.flush())
+            .map_err(|e| UciError::new(format!("Failed to send '{command}' to engine: {e}")))
+    }
+
+    fn wait_for(
+        &mut self,
+        response: &str,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<Info>, UciError> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut infos = Vec::new();
+        loop {
+            let line = match deadline {
+                Some(deadline) => self
+                    .lines
+                    .recv_timeout(deadline.saturating_duration_since(Instant::now())),
+                None => self
+                    .lines
+                    .recv()
+                    .map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match line {
+                Ok(line) if line.split_whitespace().next() == Some(response) => return Ok(infos),
+                Ok(line) => infos.extend(info::parse(&line)),
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(UciError::new(format!(
+                        "Timed out waiting for '{response}' from engine"
+                    )))
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(UciError::new(format!(
+                        "Engine exited before sending '{response}'"
+                    )))
+                }
+            }
+        }
+    }
+}
+
+impl Drop for UciEngine {
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        let deadline = Instant::now() + QUIT_TIMEOUT;
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn latest(infos: Vec<Info>) -> Vec<Info> {
+    let mut lines = BTreeMap::new();
+    for info in infos {
+        lines.insert(info.multipv(), info);
+    }
+    lines.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Score;
+    use crate::parse::DEFAULT_FEN;
+
+    const FAKE_ENGINE: &str = "resources/test/engine/fake_engine.sh";
+
+    #[test]
+    fn completes_handshake() {
+        let mut engine = UciEngine::spawn(FAKE_ENGINE).unwrap();
+        engine.set_option("MultiPV", "2").unwrap();
+        assert!(engine.is_ready().is_ok())
+    }
+
+    #[test]
+    fn returns_err_if_engine_does_not_exist() {
+        let result = UciEngine::spawn("resources/test/engine/missing");
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn searches_to_depth() {
+        let mut engine = UciEngine::spawn(FAKE_ENGINE).unwrap();
+        let lines = engine.go_depth(DEFAULT_FEN, 2).unwrap();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].depth(), 2);
+        assert_eq!(lines[0].score(), Score::Centipawns(35));
+        assert_eq!(lines[0].pv(), &["e2e4", "e7e5"]);
+        assert_eq!(lines[1].multipv(), 2);
+        assert_eq!(lines[1].pv(), &["d2d4", "d7d5"])
+    }
+
+    #[test]
+    fn polls_infinite_analysis() {
+        let mut engine = UciEngine::spawn(FAKE_ENGINE).unwrap();
+        engine.go_infinite(DEFAULT_FEN).unwrap();
+
+        let deadline = Instant::now() + RESPONSE_TIMEOUT;
+        let mut infos = Vec::new();
+        while infos.len() < 3 && Instant::now() < deadline {
+            infos.extend(engine.poll());
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(infos.len(), 3);
+
+        engine.go_infinite(DEFAULT_FEN).unwrap();
+        assert!(engine.stop().is_ok())
+    }
+}
@@ -0,0 +1,18 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug)]
+pub struct UciError(String);
+
+impl UciError {
+    pub fn new(message: impl Into<String>) -> Self {
+        UciError(message.into())
+    }
+}
+
+impl Error for UciError {}
+
+impl Display for UciError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
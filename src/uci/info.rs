@@ -0,0 +1,98 @@
+use crate::engine::Score;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Info {
+    depth: usize,
+    multipv: usize,
+    score: Score,
+    pv: Vec<String>,
+}
+
+impl Info {
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn multipv(&self) -> usize {
+        self.multipv
+    }
+
+    // From the point of view of the side to move, as reported by the engine
+    pub fn score(&self) -> Score {
+        self.score
+    }
+
+    pub fn pv(&self) -> &[String] {
+        &self.pv
+    }
+}
+
+// Only lines which carry a score and a principal variation are of interest
+pub fn parse(line: &str) -> Option<Info> {
+    let mut tokens = line.split_whitespace();
+    if tokens.next() != Some("info") {
+        return None;
+    }
+
+    let mut depth = None;
+    let mut multipv = 1;
+    let mut score = None;
+    let mut pv = Vec::new();
+    while let Some(token) = tokens.next() {
+        match token {
+            "depth" => depth = tokens.next().and_then(|value| value.parse().ok()),
+            "multipv" => multipv = tokens.next().and_then(|value| value.parse().ok())?,
+            "score" => {
+                score = match (tokens.next(), tokens.next().map(str::parse)) {
+                    (Some("cp"), Some(Ok(value))) => Some(Score::Centipawns(value)),
+                    (Some("mate"), Some(Ok(value))) => Some(Score::Mate(value)),
+                    _ => None,
+                }
+            }
+            "pv" => pv = tokens.by_ref().map(ToString::to_string).collect(),
+            "string" => return None,
+            _ => {}
+        }
+    }
+
+    if pv.is_empty() {
+        return None;
+    }
+    Some(Info {
+        depth: depth?,
+        multipv,
+        score: score?,
+        pv,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_centipawn_line() {
+        let info = parse(
+            "info depth 18 seldepth 24 multipv 2 score cp -31 nodes 1024 nps 2048 pv d2d4 d7d5 c2c4",
+        )
+        .unwrap();
+        assert_eq!(info.depth(), 18);
+        assert_eq!(info.multipv(), 2);
+        assert_eq!(info.score(), Score::Centipawns(-31));
+        assert_eq!(info.pv(), &["d2d4", "d7d5", "c2c4"]);
+    }
+
+    #[test]
+    fn parses_mate_score_with_bound() {
+        let info = parse("info depth 9 score mate 3 lowerbound pv h5f7").unwrap();
+        assert_eq!(info.multipv(), 1);
+        assert_eq!(info.score(), Score::Mate(3));
+    }
+
+    #[test]
+    fn ignores_lines_without_variation() {
+        assert!(parse("info depth 5 currmove e2e4 currmovenumber 1").is_none());
+        assert!(parse("info string NNUE evaluation enabled pv").is_none());
+        assert!(parse("bestmove e2e4").is_none());
+    }
+}
@@ -0,0 +1,7 @@
+mod client;
+mod error;
+mod info;
+
+pub use self::client::UciEngine;
+pub use self::error::UciError;
+pub use self::info::Info;
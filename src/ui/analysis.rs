@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::engine;
+use crate::model::Board;
+use crate::uci::{Info, UciEngine};
+use crate::write;
+
+use super::error::UiError;
+
+const MULTI_PV: usize = 3;
+
+pub struct Analysis {
+    engine: UciEngine,
+    fen: Option<String>,
+    lines: BTreeMap<usize, Info>,
+}
+
+impl Analysis {
+    pub fn new(mut engine: UciEngine) -> Result<Self, UiError> {
+        engine
+            .set_option("MultiPV", &MULTI_PV.to_string())
+            .and_then(|()| engine.is_ready())
+            .map_err(|e| UiError::new(format!("Failed to configure engine: {e}")))?;
+        Ok(Analysis {
+            engine,
+            fen: None,
+            lines: BTreeMap::new(),
+        })
+    }
+
+    // Restarts the search whenever the board changes
+    pub fn follow(&mut self, board: &Board) -> Result<(), UiError> {
+        let fen = board.fen();
+        if self.fen.as_ref() != Some(&fen) {
+            self.lines.clear();
+            self.engine
+                .go_infinite(&fen)
+                .map_err(|e| UiError::new(format!("Failed to start analysis: {e}")))?;
+            self.fen = Some(fen);
+        }
+        for info in self.engine.poll() {
+            self.lines.insert(info.multipv(), info);
+        }
+        Ok(())
+    }
+}
+
+pub fn render(frame: &mut Frame, analysis: &Analysis, board: &Board, area: Rect) {
+    let colour = *board.active_colour();
+    let depth = analysis.lines.values().map(Info::depth).max();
+
+    let lines: Vec<Line> = analysis
+        .lines
+        .values()
+        .map(|info| {
+            let score = info.score().from_side_to_move(colour);
+            Line::from(vec![
+                Span::styled(
+                    format!(" {score:>6} "),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("d{:<3}", info.depth()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::from(write::write_line(
+                    board.fullmove_clock(),
+                    colour,
+                    &engine::san_line(board, info.pv()),
+                )),
+            ])
+        })
+        .collect();
+
+    let title = match depth {
+        Some(depth) => format!("Analysis (depth {depth})"),
+        None => "Analysis".to_string(),
+    };
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::TOP.union(Borders::BOTTOM).union(Borders::LEFT)),
+    );
+
+    frame.render_widget(paragraph, area);
+}
@@ -1,4 +1,5 @@
 use std::io::Stdout;
+use std::time::Duration;
 
 use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::style::{Modifier, Style};
@@ -6,11 +7,21 @@ use ratatui::text::{Line, Span};
 use ratatui::widgets::block::Title;
 use ratatui::{prelude::CrosstermBackend, Frame, Terminal};
 
-use crate::model::{Board, Game, PieceColour};
+use crate::engine::{self, Motif};
+use crate::find::{self, Match};
+use crate::model::{Board, Game, PieceColour, Variant};
+use crate::opening::Opening;
+use crate::parse::parse_fen;
+use crate::query::{parse_query, Query};
+use crate::structure::{analyse, Structure};
 
-use super::{command::Command, error::UiError};
+use super::command::{Command, Input};
+use super::error::UiError;
+use super::games::Filter;
+use super::hint::Hint;
 
-use super::{board, centre, command, fen, games, ply, tags};
+use super::analysis::{self, Analysis};
+use super::{board, centre, clock, command, games, ply, structure, tactics, tags};
 use crossterm::{
     execute,
     terminal::{disable_raw_mode, LeaveAlternateScreen},
@@ -27,10 +38,26 @@ pub struct App {
     list_state: ListState,
     show_metadata: bool,
     display_fen: bool,
+    message: Option<(&'static str, String)>,
+    analysis: Option<Analysis>,
+    hint: Option<Hint>,
+    shown: Option<(usize, usize)>,
+    motifs: Vec<Motif>,
+    structure: Option<Structure>,
+    filter: Option<Filter>,
+    prompt: Option<String>,
 }
 
+const HINT_SEARCHING: &str = "Searching...";
+const REFRESH: Duration = Duration::from_millis(100);
+const STRUCTURE_HEIGHT: u16 = 6;
+
 impl App {
-    pub fn new(terminal: Terminal<CrosstermBackend<Stdout>>, games: Vec<Game>) -> Self {
+    pub fn new(
+        terminal: Terminal<CrosstermBackend<Stdout>>,
+        games: Vec<Game>,
+        analysis: Option<Analysis>,
+    ) -> Self {
         let current_ply = games.iter().map(|_| 0).collect();
         let max_ply = games.iter().map(|g| g.boards().len() - 1).collect();
         App {
@@ -43,6 +70,14 @@ impl App {
             list_state: ListState::default().with_selected(Some(0)),
             show_metadata: false,
             display_fen: false,
+            message: None,
+            analysis,
+            hint: None,
+            shown: None,
+            motifs: Vec::new(),
+            structure: None,
+            filter: None,
+            prompt: None,
         }
     }
 
@@ -64,7 +99,17 @@ impl App {
 
     fn run(&mut self) -> Result<(), UiError> {
         loop {
-            if let Some(command) = command::read()? {
+            // Redraw regularly while the engine or a hint search is running
+            let timeout = (self.analysis.is_some() || self.hint.is_some()).then_some(REFRESH);
+            if self.prompt.is_some() {
+                if let Some(input) = command::read_input(timeout)? {
+                    self.input(input);
+                }
+            } else if let Some(command) = command::read(timeout)? {
+                if !matches!(command, Command::Hint) {
+                    self.message = None;
+                    self.hint = None;
+                }
                 match command {
                     Command::PlyForwards => {
                         let current_ply = self.current_ply[self.current_game];
@@ -79,15 +124,15 @@ impl App {
                         }
                     }
                     Command::GameForwards => {
-                        if self.current_game < self.games.len() - 1 {
-                            self.current_game += 1;
-                            self.list_state.select(Some(self.current_game));
+                        let selected = self.list_state.selected().unwrap_or_default();
+                        if selected + 1 < self.listed() {
+                            self.select(selected + 1);
                         }
                     }
                     Command::GameBackwards => {
-                        if self.current_game > 0 {
-                            self.current_game -= 1;
-                            self.list_state.select(Some(self.current_game));
+                        let selected = self.list_state.selected().unwrap_or_default();
+                        if selected > 0 {
+                            self.select(selected - 1);
                         }
                     }
                     Command::FlipPerspective => match self.perspective {
@@ -104,12 +149,54 @@ impl App {
                     Command::DisplayFen => {
                         self.display_fen = !self.display_fen;
                     }
+                    Command::Hint => match self.message {
+                        Some(_) => {
+                            self.message = None;
+                            self.hint = None;
+                        }
+                        None => {
+                            self.message = Some(("Hint", HINT_SEARCHING.to_string()));
+                            self.hint = Some(Hint::start(self.current_board()));
+                        }
+                    },
+                    Command::Search => {
+                        self.prompt = Some(String::new());
+                    }
+                    Command::SearchPosition => {
+                        let board = self.current_board().clone();
+                        self.search(&board, "Current position".to_string());
+                    }
+                    Command::ClearSearch => {
+                        if self.filter.take().is_some() {
+                            self.list_state.select(Some(self.current_game));
+                        }
+                    }
                     Command::Quit => break,
                 }
             }
 
+            if let Some(text) = self.hint.as_ref().and_then(Hint::poll) {
+                self.message = Some(("Hint", text));
+                self.hint = None;
+            }
+
             let current_ply = self.current_ply[self.current_game];
 
+            // Only looked for when the board shown changes, rather than on every redraw
+            if self.shown != Some((self.current_game, current_ply)) {
+                self.shown = Some((self.current_game, current_ply));
+                let boards = self.games[self.current_game].boards();
+                // Motifs created by the move which reached the current board
+                self.motifs = current_ply.checked_sub(1).map_or(Vec::new(), |previous| {
+                    engine::motifs(&boards[previous], &boards[current_ply])
+                });
+                self.structure = Some(analyse(&boards[current_ply]));
+            }
+
+            if let Some(analysis) = self.analysis.as_mut() {
+                analysis.follow(&self.games[self.current_game].boards()[current_ply])?;
+            }
+
             self.terminal
                 .draw(|frame| {
                     render(
@@ -121,14 +208,96 @@ impl App {
                         &mut self.list_state,
                         self.show_metadata,
                         self.display_fen,
+                        self.message
+                            .as_ref()
+                            .map(|(title, text)| (*title, text.as_str())),
+                        self.analysis.as_ref(),
+                        &self.motifs,
+                        self.structure.as_ref(),
+                        self.filter.as_ref(),
+                        self.prompt.as_deref(),
                     );
                 })
                 .map_err(|e| UiError::new(format!("Failed to draw frame: {e}")))?;
         }
         Ok(())
     }
+
+    fn input(&mut self, input: Input) {
+        let Some(text) = self.prompt.as_mut() else {
+            return;
+        };
+        match input {
+            Input::Type(c) => text.push(c),
+            Input::Delete => {
+                text.pop();
+            }
+            Input::Cancel => self.prompt = None,
+            Input::Submit => {
+                let text = std::mem::take(text);
+                let text = text.trim();
+                self.prompt = None;
+                // Only FENs contain '/', so anything else is treated as a query
+                let searched = if text.contains('/') {
+                    parse_fen(text)
+                        .map(|fen| self.search(fen.starting_board(), format!("FEN {text}")))
+                        .map_err(|e| e.to_string())
+                } else {
+                    parse_query(text)
+                        .map(|query| self.search_query(&query, format!("Query {text}")))
+                        .map_err(|e| e.to_string())
+                };
+                if let Err(e) = searched {
+                    self.message = Some(("Search", e));
+                }
+            }
+        }
+    }
+
+    fn search(&mut self, board: &Board, description: String) {
+        let matches = find::find_position(&self.games, board);
+        self.filter(description, matches, "No games reach this position");
+    }
+
+    fn search_query(&mut self, query: &Query, description: String) {
+        let matches = find::find_query(&self.games, query);
+        self.filter(description, matches, "No games match this query");
+    }
+
+    fn filter(&mut self, description: String, matches: Vec<Match>, none_found: &str) {
+        if matches.is_empty() {
+            self.message = Some(("Search", none_found.to_string()));
+            return;
+        }
+        self.filter = Some(Filter::new(description, matches));
+        self.select(0);
+    }
+
+    // Selecting a search result jumps to the ply where it matched
+    fn select(&mut self, index: usize) {
+        self.list_state.select(Some(index));
+        match &self.filter {
+            Some(filter) => {
+                let found = filter.matches()[index];
+                self.current_game = found.game();
+                self.current_ply[found.game()] = found.ply();
+            }
+            None => self.current_game = index,
+        }
+    }
+
+    fn listed(&self) -> usize {
+        self.filter
+            .as_ref()
+            .map_or(self.games.len(), |filter| filter.matches().len())
+    }
+
+    fn current_board(&self) -> &Board {
+        &self.games[self.current_game].boards()[self.current_ply[self.current_game]]
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render(
     frame: &mut Frame,
     current_game: usize,
@@ -138,6 +307,12 @@ fn render(
     list_state: &mut ListState,
     show_metadata: bool,
     display_fen: bool,
+    message: Option<(&str, &str)>,
+    analysis: Option<&Analysis>,
+    motifs: &[Motif],
+    structure: Option<&Structure>,
+    filter: Option<&Filter>,
+    prompt: Option<&str>,
 ) {
     let regions = Layout::default()
         .direction(ratatui::layout::Direction::Vertical)
@@ -158,36 +333,126 @@ fn render(
         .constraints(vec![Constraint::Percentage(70), Constraint::Percentage(30)])
         .split(ui_regions[0]);
 
-    let bottom_region = ui_regions[1];
+    let bottom_region = match analysis {
+        Some(_) => Layout::default()
+            .direction(ratatui::layout::Direction::Horizontal)
+            .constraints(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(ui_regions[1]),
+        None => Layout::default()
+            .constraints(vec![Constraint::Percentage(100)])
+            .split(ui_regions[1]),
+    };
 
-    title(frame, regions[0]);
+    title(
+        frame,
+        games[current_game].variant(),
+        games[current_game].opening(),
+        regions[0],
+    );
 
     let pgn = games[current_game].pgn();
+    let tactics_height = match motifs.len() {
+        0 => 0,
+        count => u16::try_from(count + 1).unwrap_or(u16::MAX),
+    };
+    let usage = games[current_game].time_usage();
+    let clock_height = if usage.has_times() {
+        clock::CLOCK_HEIGHT
+    } else {
+        0
+    };
+    let moves_region = Layout::default()
+        .direction(ratatui::layout::Direction::Vertical)
+        .constraints(vec![
+            Constraint::Min(0),
+            Constraint::Length(clock_height),
+            Constraint::Length(tactics_height),
+        ])
+        .split(top_region[0]);
 
-    ply::render(frame, pgn.ply(), current_ply, pgn.result(), top_region[0]);
+    ply::render(
+        frame,
+        pgn.ply(),
+        games[current_game].san(),
+        usage.plies(),
+        current_ply,
+        pgn.result(),
+        moves_region[0],
+    );
+    if usage.has_times() {
+        clock::render(frame, usage, pgn.ply(), moves_region[1]);
+    }
+    if !motifs.is_empty() {
+        tactics::render(frame, motifs, moves_region[2]);
+    }
 
     let current_board = &games[current_game].boards()[current_ply];
     if display_fen {
-        fen_string(frame, regions[1], current_board);
+        popup(frame, regions[1], "FEN string", current_board.fen());
+    }
+    if let Some((title, text)) = message {
+        popup(frame, regions[1], title, text.to_string());
+    }
+    if let Some(prompt) = prompt {
+        popup(
+            frame,
+            regions[1],
+            "Search (FEN or query)",
+            format!("{prompt}_"),
+        );
+    }
+    let board_region = Layout::default()
+        .direction(ratatui::layout::Direction::Vertical)
+        .constraints(vec![
+            Constraint::Min(0),
+            Constraint::Length(STRUCTURE_HEIGHT),
+        ])
+        .split(top_region[1]);
+    board::render(frame, current_board, perspective, board_region[0]);
+    if let Some(structure) = structure {
+        structure::render(frame, structure, board_region[1]);
     }
-    board::render(frame, current_board, perspective, top_region[1]);
 
-    games::render(frame, games, bottom_region, list_state, show_metadata);
+    games::render(
+        frame,
+        games,
+        filter,
+        bottom_region[0],
+        list_state,
+        show_metadata,
+    );
+
+    if let Some(analysis) = analysis {
+        analysis::render(frame, analysis, current_board, bottom_region[1]);
+    }
 
     if show_metadata {
-        tags::render(frame, pgn.tags(), pgn.result(), bottom_region);
+        tags::render(
+            frame,
+            pgn.tags(),
+            pgn.result(),
+            games[current_game].opening(),
+            bottom_region[0],
+        );
     }
 
     command::render(frame, regions[2]);
 }
 
-fn title(frame: &mut Frame, area: Rect) {
-    let title: Vec<Span> = [
+fn title(frame: &mut Frame, variant: Variant, opening: Option<&Opening>, area: Rect) {
+    let mut title: Vec<Span> = vec![
         Span::styled("pigin", Style::default().add_modifier(Modifier::ITALIC)),
         Span::from(" "),
-    ]
-    .into_iter()
-    .collect();
+    ];
+    if variant != Variant::Standard {
+        title.push(Span::styled(
+            format!("| {variant} "),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+    }
+    if let Some(opening) = opening {
+        title.push(Span::from(format!("| {opening} ")));
+    }
 
     let title_block = Block::default()
         .borders(Borders::BOTTOM)
@@ -197,23 +462,20 @@ fn title(frame: &mut Frame, area: Rect) {
     frame.render_widget(title_block, area);
 }
 
-fn fen_string(frame: &mut Frame, area: Rect, board: &Board) {
-    let fen_string = fen::from_board(board)
-        .unwrap_or_else(|_| String::from("Failed to generate FEN string for board"));
-
-    let fen_area = centre::centered_rect(80, 20, area);
-    let span = Span::styled(fen_string, Style::new().add_modifier(Modifier::ITALIC));
-    let padding = Padding::new(0, 0, ((fen_area.height - 1) / 2) - 1, 0);
+fn popup(frame: &mut Frame, area: Rect, title: &str, text: String) {
+    let popup_area = centre::centered_rect(80, 20, area);
+    let span = Span::styled(text, Style::new().add_modifier(Modifier::ITALIC));
+    let padding = Padding::new(0, 0, ((popup_area.height - 1) / 2) - 1, 0);
     let block = Paragraph::new(span)
         .block(
             Block::default()
-                .title("FEN string")
+                .title(title)
                 .title_alignment(ratatui::layout::Alignment::Left)
                 .padding(padding)
                 .borders(Borders::ALL),
         )
         .alignment(ratatui::prelude::Alignment::Center);
 
-    frame.render_widget(Clear, fen_area);
-    frame.render_widget(block, fen_area);
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(block, popup_area);
 }
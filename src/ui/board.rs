@@ -6,7 +6,7 @@ use ratatui::{
     Frame,
 };
 
-use crate::model::{Board, PieceColour, Position, MAX_POSITION};
+use crate::model::{Board, Piece, PieceColour, Position, MAX_POSITION};
 
 pub fn render(frame: &mut Frame, board: &Board, perspective: PieceColour, area: Rect) {
     let positions = |i: i8| {
@@ -18,7 +18,7 @@ pub fn render(frame: &mut Frame, board: &Board, perspective: PieceColour, area:
         (0..=MAX_POSITION).map(move |column| Position::new(row, column))
     };
 
-    let board_text: Vec<Line> = (0..=MAX_POSITION)
+    let mut board_text: Vec<Line> = (0..=MAX_POSITION)
         .map(positions)
         .map(|positions| {
             Line::from(
@@ -29,7 +29,14 @@ pub fn render(frame: &mut Frame, board: &Board, perspective: PieceColour, area:
         })
         .collect();
 
-    let vertical_padding = (area.height - 8) / 2;
+    // Each side's pocket sits on its own edge of the board
+    if board.pocket(perspective).is_some() {
+        board_text.insert(0, pocket(board, perspective.opposite()));
+        board_text.push(pocket(board, perspective));
+    }
+
+    let lines = u16::try_from(board_text.len()).unwrap_or(u16::MAX);
+    let vertical_padding = area.height.saturating_sub(lines) / 2;
 
     let paragraph = Paragraph::new(board_text)
         .alignment(Alignment::Center)
@@ -38,9 +45,32 @@ pub fn render(frame: &mut Frame, board: &Board, perspective: PieceColour, area:
     frame.render_widget(paragraph, area);
 }
 
+fn pocket(board: &Board, colour: PieceColour) -> Line {
+    let pieces: Vec<String> = board
+        .pocket(colour)
+        .map(|pocket| pocket.pieces())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(piece_type, count)| {
+            let piece = Piece::new(colour, piece_type);
+            match count {
+                1 => letter(&piece),
+                count => format!("{}x{count}", letter(&piece)),
+            }
+        })
+        .collect();
+    let held = if pieces.is_empty() {
+        "-".to_string()
+    } else {
+        pieces.join(" ")
+    };
+
+    Line::from(format!("{colour:?} pocket: {held}"))
+}
+
 fn square(position: Position, board: &Board) -> Span {
     let maybe_piece = board.occupant(position);
-    let text = maybe_piece.map_or("   ".to_string(), |piece| format!(" {piece} "));
+    let text = maybe_piece.map_or("   ".to_string(), |piece| format!(" {} ", letter(piece)));
 
     let colour = maybe_piece.map_or(Color::Black, |piece| match piece.colour() {
         PieceColour::White => Color::White,
@@ -55,3 +85,8 @@ fn square(position: Position, board: &Board) -> Span {
         Span::styled(text, style.bg(Color::LightRed))
     }
 }
+
+// Both colours use capitals, told apart by the square's colour
+fn letter(piece: &Piece) -> String {
+    piece.to_string().to_ascii_uppercase()
+}
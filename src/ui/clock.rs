@@ -0,0 +1,82 @@
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Padding, Paragraph, Sparkline},
+    Frame,
+};
+
+use crate::clock::{format_duration, TimeUsage};
+use crate::model::{PieceColour, Ply};
+
+pub const CLOCK_HEIGHT: u16 = 5;
+
+pub fn render(frame: &mut Frame, usage: &TimeUsage, ply: &[Ply], area: Rect) {
+    let block = Block::default()
+        .title("Time usage")
+        .borders(Borders::TOP.union(Borders::RIGHT))
+        .padding(Padding::horizontal(1));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(ratatui::layout::Direction::Vertical)
+        .constraints(vec![Constraint::Length(1); 4])
+        .split(inner);
+
+    for (idx, (colour, bar_colour)) in [
+        (PieceColour::White, Color::White),
+        (PieceColour::Black, Color::DarkGray),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        frame.render_widget(
+            Paragraph::new(summary_line(usage, ply, colour)),
+            rows[idx * 2],
+        );
+
+        // Tenths of a second spent on each of the side's moves
+        let data: Vec<u64> = usage
+            .plies()
+            .iter()
+            .filter(|time| time.colour() == colour)
+            .map(|time| time.spent().map_or(0, |spent| spent.as_millis() / 100))
+            .map(|tenths| u64::try_from(tenths).unwrap_or(u64::MAX))
+            .collect();
+        frame.render_widget(
+            Sparkline::default()
+                .data(&data)
+                .style(Style::default().fg(bar_colour)),
+            rows[idx * 2 + 1],
+        );
+    }
+}
+
+fn summary_line<'a>(usage: &TimeUsage, ply: &[Ply], colour: PieceColour) -> Line<'a> {
+    let summary = usage.summary(colour);
+    let move_number = |idx: usize| ply.get(idx).map_or(0, Ply::move_number);
+
+    let mut spans = vec![
+        Span::styled(
+            format!("{colour:?}: "),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::from(match (summary.average(), summary.longest()) {
+            (Some(average), Some((idx, longest))) => format!(
+                "avg {}, longest {} (move {})",
+                format_duration(average),
+                format_duration(longest),
+                move_number(idx)
+            ),
+            _ => summary.to_string(),
+        }),
+    ];
+    if let Some(idx) = summary.time_trouble() {
+        spans.push(Span::styled(
+            format!(", time trouble from move {}", move_number(idx)),
+            Style::default().fg(Color::LightRed),
+        ));
+    }
+    Line::from(spans)
+}
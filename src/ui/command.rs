@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use crossterm::event::{self, Event, KeyCode, KeyEventKind};
 use ratatui::{
     layout::Rect,
@@ -16,12 +18,18 @@ const NEXT_GAME_KEY: char = 's';
 const FLIP_PERSPECTIVE_KEY: char = 'e';
 const METADATA_KEY: char = 'x';
 const FEN_KEY: char = 'f';
+const HINT_KEY: char = 'h';
+const SEARCH_KEY: char = '/';
+const SEARCH_POSITION_KEY: char = 'p';
 const QUIT_KEY: char = 'q';
 
 const NAVIGATE_LABEL: &str = " Navigate: w a s d ";
 const FLIP_LABEL: &str = " Flip: e ";
 const METADATA_LABEL: &str = " Toggle metadata: x ";
 const FEN_LABEL: &str = " Display FEN string: f ";
+const HINT_LABEL: &str = " Hint: h ";
+const SEARCH_LABEL: &str = " Search: / p ";
+const CLEAR_LABEL: &str = " Clear search: esc ";
 const QUIT_LABEL: &str = " Quit: q ";
 
 pub enum Command {
@@ -32,11 +40,24 @@ pub enum Command {
     FlipPerspective,
     ToggleMetadata,
     DisplayFen,
+    Hint,
+    Search,
+    SearchPosition,
+    ClearSearch,
     Quit,
 }
 
-pub fn read() -> Result<Option<Command>, UiError> {
-    let event = event::read().map_err(|e| UiError::new(format!("Failed to read event: {e}")))?;
+pub enum Input {
+    Type(char),
+    Delete,
+    Submit,
+    Cancel,
+}
+
+pub fn read(timeout: Option<Duration>) -> Result<Option<Command>, UiError> {
+    let Some(event) = next_event(timeout)? else {
+        return Ok(None);
+    };
     if let Event::Key(key) = event {
         if key.kind == KeyEventKind::Press {
             match key.code {
@@ -47,6 +68,10 @@ pub fn read() -> Result<Option<Command>, UiError> {
                 KeyCode::Char(FLIP_PERSPECTIVE_KEY) => Ok(Some(Command::FlipPerspective)),
                 KeyCode::Char(METADATA_KEY) => Ok(Some(Command::ToggleMetadata)),
                 KeyCode::Char(FEN_KEY) => Ok(Some(Command::DisplayFen)),
+                KeyCode::Char(HINT_KEY) => Ok(Some(Command::Hint)),
+                KeyCode::Char(SEARCH_KEY) => Ok(Some(Command::Search)),
+                KeyCode::Char(SEARCH_POSITION_KEY) => Ok(Some(Command::SearchPosition)),
+                KeyCode::Esc => Ok(Some(Command::ClearSearch)),
                 KeyCode::Char(QUIT_KEY) => Ok(Some(Command::Quit)),
                 _ => Ok(None),
             }
@@ -58,13 +83,47 @@ pub fn read() -> Result<Option<Command>, UiError> {
     }
 }
 
+// Keys typed into a prompt rather than interpreted as commands
+pub fn read_input(timeout: Option<Duration>) -> Result<Option<Input>, UiError> {
+    let Some(Event::Key(key)) = next_event(timeout)? else {
+        return Ok(None);
+    };
+    if key.kind != KeyEventKind::Press {
+        return Ok(None);
+    }
+    match key.code {
+        KeyCode::Char(c) => Ok(Some(Input::Type(c))),
+        KeyCode::Backspace => Ok(Some(Input::Delete)),
+        KeyCode::Enter => Ok(Some(Input::Submit)),
+        KeyCode::Esc => Ok(Some(Input::Cancel)),
+        _ => Ok(None),
+    }
+}
+
+// Waits at most `timeout` for input if given, otherwise blocks until an event arrives
+fn next_event(timeout: Option<Duration>) -> Result<Option<Event>, UiError> {
+    if let Some(timeout) = timeout {
+        let ready =
+            event::poll(timeout).map_err(|e| UiError::new(format!("Failed to poll event: {e}")))?;
+        if !ready {
+            return Ok(None);
+        }
+    }
+    event::read()
+        .map(Some)
+        .map_err(|e| UiError::new(format!("Failed to read event: {e}")))
+}
+
 pub fn render(frame: &mut Frame, area: Rect) {
     let title: Vec<Span> = [
         command(NAVIGATE_LABEL, Color::LightGreen),
         command(FLIP_LABEL, Color::LightBlue),
         command(METADATA_LABEL, Color::LightGreen),
         command(FEN_LABEL, Color::LightBlue),
-        command(QUIT_LABEL, Color::LightGreen),
+        command(HINT_LABEL, Color::LightGreen),
+        command(SEARCH_LABEL, Color::LightBlue),
+        command(CLEAR_LABEL, Color::LightGreen),
+        command(QUIT_LABEL, Color::LightBlue),
     ]
     .concat();
 
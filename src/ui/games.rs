@@ -6,22 +6,68 @@ use ratatui::{
     Frame,
 };
 
+use crate::find::Match;
+use crate::model::PieceColour;
 use crate::Game;
 
+pub struct Filter {
+    description: String,
+    matches: Vec<Match>,
+}
+
+impl Filter {
+    pub fn new(description: String, matches: Vec<Match>) -> Self {
+        Filter {
+            description,
+            matches,
+        }
+    }
+
+    pub fn matches(&self) -> &[Match] {
+        &self.matches
+    }
+}
+
 pub fn render(
     frame: &mut Frame,
     games: &[Game],
+    filter: Option<&Filter>,
     area: Rect,
     list_state: &mut ListState,
     dim: bool,
 ) {
-    let list_items: Vec<ListItem> = games
-        .iter()
-        .map(|game| ListItem::new(Line::from(game_description(game))))
-        .collect();
+    let (list_items, block): (Vec<ListItem>, Block) = match filter {
+        Some(filter) => (
+            filter
+                .matches()
+                .iter()
+                .map(|found| {
+                    let mut spans = game_description(&games[found.game()]);
+                    spans.push(Span::styled(
+                        format!(" | ply {}", found.ply()),
+                        Style::default().fg(Color::LightGreen),
+                    ));
+                    ListItem::new(Line::from(spans))
+                })
+                .collect(),
+            Block::default().title(format!(
+                "{} ({} of {} games)",
+                filter.description,
+                filter.matches().len(),
+                games.len()
+            )),
+        ),
+        None => (
+            games
+                .iter()
+                .map(|game| ListItem::new(Line::from(game_description(game))))
+                .collect(),
+            Block::default(),
+        ),
+    };
 
     let list = List::new(list_items)
-        .block(Block::default().borders(Borders::TOP.union(Borders::BOTTOM)))
+        .block(block.borders(Borders::TOP.union(Borders::BOTTOM)))
         .style(if dim {
             Style::default().dim()
         } else {
@@ -36,11 +82,17 @@ pub fn render(
 fn game_description(game: &Game) -> Vec<Span> {
     let tags = game.pgn().tags();
 
-    let white_player = tags.get_or_default("White", "Unknown");
-    let black_player = tags.get_or_default("Black", "Unknown");
+    let player = |name: &str, colour: PieceColour| {
+        let name = tags.get_or_default(name, "Unknown");
+        tags.elo(colour)
+            .map_or_else(|| name.clone(), |elo| format!("{name} ({elo})"))
+    };
+    let white_player = player("White", PieceColour::White);
+    let black_player = player("Black", PieceColour::Black);
 
     let event = tags.get("Event");
-    let date = tags.get("Date");
+    // Dates with every part unknown say nothing
+    let date = tags.date().filter(|date| date.year().is_some());
 
     let style = Style::default();
 
@@ -65,5 +117,9 @@ fn game_description(game: &Game) -> Vec<Span> {
         spans.push(Span::styled(format!(" | {date}"), style));
     }
 
+    if let Some(opening) = game.opening() {
+        spans.push(Span::styled(format!(" | {opening}"), style.fg(Color::Gray)));
+    }
+
     spans
 }
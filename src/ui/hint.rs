@@ -0,0 +1,42 @@
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+use crate::engine::{self, Limit};
+use crate::model::Board;
+
+const HINT_TIME: Duration = Duration::from_secs(1);
+
+// A search on a worker thread, so the UI keeps drawing while it runs
+pub struct Hint {
+    result: Receiver<String>,
+}
+
+impl Hint {
+    pub fn start(board: &Board) -> Self {
+        let board = board.clone();
+        let (sender, result) = mpsc::channel();
+        thread::spawn(move || {
+            // The receiver is dropped if the hint is dismissed before the search finishes
+            let _ = sender.send(hint(&board));
+        });
+        Hint { result }
+    }
+
+    pub fn poll(&self) -> Option<String> {
+        self.result.try_recv().ok()
+    }
+}
+
+fn hint(board: &Board) -> String {
+    let result = engine::search(board, Limit::Time(HINT_TIME));
+    match result.best_move() {
+        Some(best_move) => format!(
+            "Best move: {} ({}, depth {})",
+            engine::san(board, best_move),
+            result.score(),
+            result.depth()
+        ),
+        None => format!("No legal moves ({})", result.score()),
+    }
+}
@@ -1,6 +1,9 @@
 use crate::model::Game;
+use crate::uci::UciEngine;
 
-use self::{app::App, error::UiError};
+use self::{analysis::Analysis, app::App};
+
+pub use self::error::UiError;
 use std::io::{self, Stdout};
 
 use crossterm::{
@@ -9,19 +12,24 @@ use crossterm::{
 };
 use ratatui::prelude::*;
 
+mod analysis;
 mod app;
 mod board;
 mod centre;
+mod clock;
 mod command;
 mod error;
-mod fen;
 mod games;
+mod hint;
 mod ply;
+mod structure;
+mod tactics;
 mod tags;
 
-pub fn launch(games: Vec<Game>) -> Result<(), UiError> {
+pub fn launch(games: Vec<Game>, engine: Option<UciEngine>) -> Result<(), UiError> {
+    let analysis = engine.map(Analysis::new).transpose()?;
     let terminal = setup_terminal()?;
-    let mut app = App::new(terminal, games);
+    let mut app = App::new(terminal, games, analysis);
     app.launch()?;
     Ok(())
 }
@@ -1,5 +1,3 @@
-use std::fmt::Display;
-
 use ratatui::{
     layout::{Alignment, Rect},
     style::{Color, Modifier, Style},
@@ -11,184 +9,55 @@ use ratatui::{
     Frame,
 };
 
-use crate::model::{
-    Check, GameResult, MoveQualifier, Movement, Piece, PieceColour, PieceType, Ply, PlyMovement,
-    Position, COLUMNS, ROWS,
-};
-
-const BLACK_PAWN: &str = "P";
-const BLACK_KNIGHT: &str = "N";
-const BLACK_BISHOP: &str = "B";
-const BLACK_ROOK: &str = "R";
-const BLACK_QUEEN: &str = "Q";
-const BLACK_KING: &str = "K";
-
-const WHITE_PAWN: &str = "P";
-const WHITE_KNIGHT: &str = "N";
-const WHITE_BISHOP: &str = "B";
-const WHITE_ROOK: &str = "R";
-const WHITE_QUEEN: &str = "Q";
-const WHITE_KING: &str = "K";
+use crate::clock::{format_duration, PlyTime};
+use crate::model::{GameResult, PieceColour, Ply};
 
 const AVERAGE_PLY_LENGTH: u16 = 8;
-
-impl Display for Check {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Check::Check => "+".to_string(),
-                Check::Checkmate => "#".to_string(),
-            }
-        )
-    }
-}
-
-impl Display for Piece {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match (self.colour(), self.piece_type()) {
-                // Black pieces
-                (PieceColour::Black, PieceType::Pawn) => BLACK_PAWN,
-                (PieceColour::Black, PieceType::Knight) => BLACK_KNIGHT,
-                (PieceColour::Black, PieceType::Bishop) => BLACK_BISHOP,
-                (PieceColour::Black, PieceType::Rook) => BLACK_ROOK,
-                (PieceColour::Black, PieceType::Queen) => BLACK_QUEEN,
-                (PieceColour::Black, PieceType::King) => BLACK_KING,
-                // White pieces
-                (PieceColour::White, PieceType::Pawn) => WHITE_PAWN,
-                (PieceColour::White, PieceType::Knight) => WHITE_KNIGHT,
-                (PieceColour::White, PieceType::Bishop) => WHITE_BISHOP,
-                (PieceColour::White, PieceType::Rook) => WHITE_ROOK,
-                (PieceColour::White, PieceType::Queen) => WHITE_QUEEN,
-                (PieceColour::White, PieceType::King) => WHITE_KING,
-            }
-        )
-    }
-}
-
-impl Display for Position {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let row = usize::try_from(self.row())
-            .ok()
-            .and_then(|row| ROWS.chars().nth(row))
-            .ok_or(std::fmt::Error)?;
-        let col = usize::try_from(self.col())
-            .ok()
-            .and_then(|col| COLUMNS.chars().nth(col))
-            .ok_or(std::fmt::Error)?;
-
-        write!(f, "{col}{row}")
-    }
-}
-
-impl Display for MoveQualifier {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let text = match self {
-            MoveQualifier::Col(col) => {
-                let col = usize::try_from(*col).map_err(|_| std::fmt::Error)?;
-                COLUMNS.chars().nth(col).ok_or(std::fmt::Error)?.to_string()
-            }
-            MoveQualifier::Row(row) => {
-                let row = usize::try_from(*row).map_err(|_| std::fmt::Error)?;
-                ROWS.chars().nth(row).ok_or(std::fmt::Error)?.to_string()
-            }
-            MoveQualifier::Position(position) => position.to_string(),
-        };
-        write!(f, "{text}")
-    }
-}
-
-impl Display for Ply {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let ply = match self.movement() {
-            PlyMovement::KingsideCastle { colour, check } => {
-                format_castle(self.move_number(), check.as_ref(), *colour, "O-O")
-            }
-            PlyMovement::QueensideCastle { colour, check } => {
-                format_castle(self.move_number(), check.as_ref(), *colour, "O-O-O")
-            }
-            PlyMovement::Move {
-                movement,
-                qualifier,
-                check,
-                capture,
-            } => format_move(
-                self.move_number(),
-                movement,
-                qualifier.as_ref(),
-                check.as_ref(),
-                *capture,
-                None,
-            ),
-            PlyMovement::Promotion {
-                movement,
-                promotes_to,
-                qualifier,
-                check,
-                capture,
-            } => format_move(
-                self.move_number(),
-                movement,
-                qualifier.as_ref(),
-                check.as_ref(),
-                *capture,
-                Some(promotes_to),
-            ),
-        };
-
-        write!(f, "{ply} ")
-    }
-}
-
-impl std::fmt::Display for GameResult {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                GameResult::WhiteWin => "1-0",
-                GameResult::BlackWin => "0-1",
-                GameResult::Draw => "½-½",
-                GameResult::Ongoing => "*",
-            }
-        )
-    }
-}
+const AVERAGE_TIME_LENGTH: u16 = 5;
 
 pub fn render(
     frame: &mut Frame,
     ply: &[Ply],
+    san: &[String],
+    times: &[PlyTime],
     current_ply: usize,
     game_result: GameResult,
     area: Rect,
 ) {
-    let mut spans: Vec<Span> = ply
+    let timed = times.iter().any(|time| time.spent().is_some());
+    let mut plies: Vec<Vec<Span>> = ply
         .iter()
+        .zip(san)
         .enumerate()
-        .map(|(idx, p)| {
-            if idx == current_ply {
-                highlighted_ply(p)
+        .map(|(idx, (p, san))| {
+            let mut spans = vec![if idx == current_ply {
+                highlighted_ply(p, san)
             } else {
-                standard_ply(p)
+                standard_ply(p, san)
+            }];
+            if timed {
+                spans.push(time_spent(times.get(idx)));
             }
+            spans
         })
         .collect();
 
-    spans.push(standard_game_result(&game_result));
+    plies.push(vec![standard_game_result(&game_result)]);
 
-    let spans_per_page: usize = (area.area() / AVERAGE_PLY_LENGTH).into();
-    let current_page = current_ply / spans_per_page;
+    let ply_length = if timed {
+        AVERAGE_PLY_LENGTH + AVERAGE_TIME_LENGTH
+    } else {
+        AVERAGE_PLY_LENGTH
+    };
+    let plies_per_page: usize = (area.area() / ply_length).max(1).into();
+    let current_page = current_ply / plies_per_page;
 
-    let pages = (spans.len() / spans_per_page) + 1;
-    let page = spans
-        .chunks(spans_per_page)
+    let pages = (plies.len() / plies_per_page) + 1;
+    let page = plies
+        .chunks(plies_per_page)
         .nth(current_page)
         .expect("Current page chunk should always be available")
-        .to_vec();
+        .concat();
 
     let title = Title::from(format!("Page {}/{}", current_page + 1, pages))
         .position(block::Position::Bottom)
@@ -206,55 +75,47 @@ pub fn render(
     frame.render_widget(paragraph, area);
 }
 
-fn standard_ply(ply: &Ply) -> Span {
-    Span::styled(format!("{ply}"), Style::default().fg(Color::DarkGray))
+fn standard_ply<'a>(ply: &Ply, san: &str) -> Span<'a> {
+    Span::styled(ply_text(ply, san), Style::default().fg(Color::DarkGray))
+}
+
+fn highlighted_ply<'a>(ply: &Ply, san: &str) -> Span<'a> {
+    Span::styled(ply_text(ply, san), Style::default().fg(Color::Yellow))
 }
 
-fn highlighted_ply(ply: &Ply) -> Span {
-    Span::styled(format!("{ply}"), Style::default().fg(Color::Yellow))
+fn ply_text(ply: &Ply, san: &str) -> String {
+    let move_number = move_number_string(ply.movement().colour(), ply.move_number());
+    format!("{move_number}{san} ")
+}
+
+fn time_spent<'a>(time: Option<&PlyTime>) -> Span<'a> {
+    let colour = if time.is_some_and(PlyTime::is_time_trouble) {
+        Color::LightRed
+    } else {
+        Color::Blue
+    };
+    let text = time.and_then(PlyTime::spent).map_or_else(
+        || "- ".to_string(),
+        |spent| format!("{} ", format_duration(spent)),
+    );
+    Span::styled(text, Style::default().fg(colour))
 }
 
 fn standard_game_result(game_result: &GameResult) -> Span {
     Span::styled(
-        format!("{game_result}"),
+        result_text(game_result),
         Style::default()
             .add_modifier(Modifier::BOLD)
             .fg(Color::LightGreen),
     )
 }
 
-fn format_castle(
-    move_number: i16,
-    check: Option<&Check>,
-    colour: PieceColour,
-    castle_string: &str,
-) -> String {
-    let move_number = move_number_string(colour, move_number);
-    let check_string = check.map_or(String::new(), ToString::to_string);
-    format!("{move_number}{castle_string}{check_string}")
-}
-
-fn format_move(
-    move_number: i16,
-    movement: &Movement,
-    qualifier: Option<&MoveQualifier>,
-    check: Option<&Check>,
-    capture: bool,
-    promotes_to: Option<&PieceType>,
-) -> String {
-    let move_number = move_number_string(*movement.piece().colour(), move_number);
-    let qualifier_string = qualifier.map_or(String::new(), ToString::to_string);
-    let capture_string = if capture { "x" } else { "" };
-    let check_string = check.map_or(String::new(), ToString::to_string);
-    let promotion_string = match promotes_to {
-        None => String::new(),
-        Some(&piece_type) => format!("={}", Piece::new(*movement.piece().colour(), piece_type)),
-    };
-    format!(
-        "{move_number}{}{qualifier_string}{capture_string}{}{promotion_string}{check_string}",
-        format_piece_for_ply(movement.piece()),
-        movement.position(),
-    )
+// As the PGN token, but with a compact draw
+pub fn result_text(game_result: &GameResult) -> String {
+    match game_result {
+        GameResult::Draw => "½-½".to_string(),
+        _ => game_result.to_string(),
+    }
 }
 
 fn move_number_string(colour: PieceColour, move_number: i16) -> String {
@@ -263,21 +124,3 @@ fn move_number_string(colour: PieceColour, move_number: i16) -> String {
         PieceColour::Black => String::new(),
     }
 }
-
-fn format_piece_for_ply(piece: Piece) -> &'static str {
-    match (piece.colour(), piece.piece_type()) {
-        (_, PieceType::Pawn) => "",
-        // Black pieces
-        (PieceColour::Black, PieceType::Knight) => BLACK_KNIGHT,
-        (PieceColour::Black, PieceType::Bishop) => BLACK_BISHOP,
-        (PieceColour::Black, PieceType::Rook) => BLACK_ROOK,
-        (PieceColour::Black, PieceType::Queen) => BLACK_QUEEN,
-        (PieceColour::Black, PieceType::King) => BLACK_KING,
-        // White pieces
-        (PieceColour::White, PieceType::Knight) => WHITE_KNIGHT,
-        (PieceColour::White, PieceType::Bishop) => WHITE_BISHOP,
-        (PieceColour::White, PieceType::Rook) => WHITE_ROOK,
-        (PieceColour::White, PieceType::Queen) => WHITE_QUEEN,
-        (PieceColour::White, PieceType::King) => WHITE_KING,
-    }
-}
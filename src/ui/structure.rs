@@ -0,0 +1,46 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Padding, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::model::PieceColour;
+use crate::structure::Structure;
+
+pub fn render(frame: &mut Frame, structure: &Structure, area: Rect) {
+    let mut lines: Vec<Line> = [PieceColour::White, PieceColour::Black]
+        .into_iter()
+        .map(|colour| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{colour:?}: "),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::from(structure.pawns(colour).to_string()),
+            ])
+        })
+        .collect();
+
+    let formations: Vec<String> = structure
+        .formations()
+        .iter()
+        .map(|(formation, colour)| format!("{formation} ({colour:?})"))
+        .collect();
+    if !formations.is_empty() {
+        lines.push(Line::styled(
+            formations.join(", "),
+            Style::default().fg(Color::LightGreen),
+        ));
+    }
+
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+        Block::default()
+            .title("Pawn structure")
+            .borders(Borders::TOP)
+            .padding(Padding::horizontal(1)),
+    );
+
+    frame.render_widget(paragraph, area);
+}
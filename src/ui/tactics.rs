@@ -0,0 +1,25 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Padding, Paragraph},
+    Frame,
+};
+
+use crate::engine::Motif;
+
+pub fn render(frame: &mut Frame, motifs: &[Motif], area: Rect) {
+    let lines: Vec<Line> = motifs
+        .iter()
+        .map(|motif| Line::styled(motif.to_string(), Style::default().fg(Color::LightRed)))
+        .collect();
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .title("Tactics")
+            .borders(Borders::TOP.union(Borders::RIGHT))
+            .padding(Padding::horizontal(1)),
+    );
+
+    frame.render_widget(paragraph, area);
+}
@@ -6,10 +6,17 @@ use ratatui::{
 };
 
 use crate::model::{GameResult, Tags};
+use crate::opening::Opening;
 
-use super::centre;
+use super::{centre, ply};
 
-pub fn render(frame: &mut Frame, tags: &Tags, result: GameResult, area: Rect) {
+pub fn render(
+    frame: &mut Frame,
+    tags: &Tags,
+    result: GameResult,
+    opening: Option<&Opening>,
+    area: Rect,
+) {
     let header_cells = ["Tag", "Value"].iter().map(|header| Cell::from(*header));
 
     let header = Row::new(header_cells)
@@ -29,9 +36,16 @@ pub fn render(frame: &mut Frame, tags: &Tags, result: GameResult, area: Rect) {
         })
         .collect();
 
+    if let Some(opening) = opening {
+        rows.push(Row::new([
+            Cell::from("Opening"),
+            Cell::from(opening.to_string()),
+        ]));
+    }
+
     rows.push(Row::new([
         Cell::from("Result"),
-        Cell::from(format!("{result}")),
+        Cell::from(ply::result_text(&result)),
     ]));
 
     let table = Table::new(
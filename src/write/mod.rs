@@ -0,0 +1,185 @@
+mod movetext;
+mod tags;
+
+use crate::model::{Game, Pgn};
+
+pub use self::movetext::{line as write_line, Annotation};
+
+const LINE_WIDTH: usize = 80;
+
+pub fn write_pgn(pgn: &Pgn) -> String {
+    write_annotated(pgn, &[])
+}
+
+// Annotations are matched to ply by index; missing entries leave the ply as parsed
+pub fn write_annotated(pgn: &Pgn, annotations: &[Annotation]) -> String {
+    let tags = tags::write(pgn);
+    let movetext = wrap(&movetext::write(pgn, None, annotations));
+    format!("{tags}\n{movetext}\n")
+}
+
+// Export format with the engine's SAN for every move, as written by `pigin fmt`
+pub fn write_canonical(game: &Game) -> String {
+    let pgn = game.pgn();
+    let tags = tags::write(pgn);
+    let movetext = wrap(&movetext::write(pgn, game.boards().first(), &[]));
+    format!("{tags}\n{movetext}\n")
+}
+
+// A rest-of-line comment ends its line, and lines only break between moves
+fn wrap(tokens: &[String]) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in words(tokens) {
+        if !line.is_empty() && line.len() + 1 + word.len() > LINE_WIDTH {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&word);
+        if word.starts_with(';') {
+            lines.push(std::mem::take(&mut line));
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+// Splits tokens at whitespace, keeping each move number together with its move
+fn words(tokens: &[String]) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut move_number: Option<&str> = None;
+    for token in tokens {
+        let split: Vec<&str> = if token.starts_with(';') {
+            vec![token.as_str()]
+        } else {
+            token.split_whitespace().collect()
+        };
+        for word in split {
+            match move_number.take() {
+                Some(move_number) => words.push(format!("{move_number} {word}")),
+                None if is_move_number(word) => move_number = Some(word),
+                None => words.push(word.to_string()),
+            }
+        }
+    }
+    words.extend(move_number.map(String::from));
+    words
+}
+
+fn is_move_number(word: &str) -> bool {
+    let digits = word.trim_start_matches('(').trim_end_matches('.');
+    word.ends_with('.') && !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine;
+    use crate::parse::parse;
+
+    #[test]
+    fn writes_export_format() {
+        let text = "[White \"Anderssen, \\\"Adolf\\\"\"]\n[ECO \"C33\"]\n[Result \"1-0\"]\n\n\
+                    1. e4 e5 2. f4 {King's Gambit} exf4 1-0\n";
+        let pgn = parse(text).unwrap().remove(0);
+        assert_eq!(
+            write_pgn(&pgn),
+            "[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n\
+             [White \"Anderssen, \\\"Adolf\\\"\"]\n[Black \"?\"]\n[Result \"1-0\"]\n[ECO \"C33\"]\n\n\
+             1. e4 e5 2. f4 {King's Gambit} 2... exf4 1-0\n"
+        );
+        let reparsed = parse(&write_pgn(&pgn)).unwrap().remove(0);
+        assert_eq!(reparsed.tags().get("White"), pgn.tags().get("White"));
+        assert_eq!(reparsed.ply(), pgn.ply())
+    }
+
+    #[test]
+    fn writes_fen_only_for_non_standard_start() {
+        let horde = "[Variant \"Horde\"]\n[Result \"*\"]\n\n1. a4 *\n";
+        assert!(!write_pgn(&parse(horde).unwrap().remove(0)).contains("[FEN"));
+
+        let crazyhouse = "[Variant \"Crazyhouse\"]\n[Result \"*\"]\n\n1. e4 *\n";
+        assert!(!write_pgn(&parse(crazyhouse).unwrap().remove(0)).contains("[FEN"));
+
+        let moved = "[Variant \"Crazyhouse\"]\n[FEN \"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR[] w KQkq - 0 9\"]\n[Result \"*\"]\n\n9. e4 *\n";
+        assert!(write_pgn(&parse(moved).unwrap().remove(0)).contains("[FEN"));
+
+        let setup = "[FEN \"4k3/8/8/8/8/8/8/4K3 b - - 0 1\"]\n[Result \"*\"]\n\n1... Kd7 *\n";
+        let written = write_pgn(&parse(setup).unwrap().remove(0));
+        assert!(written.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/8/4K3 b - - 0 1\"]\n"));
+        assert!(written.ends_with("1... Kd7 *\n"))
+    }
+
+    #[test]
+    fn writes_canonical_san() {
+        let text = "[Result \"*\"]\r\n\r\n1. e4 f5 2. Qh5 g6 3. Qxg6 {Mate next} hxg6 4. Bd3 Rxh2 5. Rxh2 *\r\n";
+        let written = write_canonical(&game(text));
+        assert!(written.ends_with(
+            "1. e4 f5 2. Qh5+ g6 3. Qxg6+ {Mate next} 3... hxg6 4. Bd3 Rxh2 5. Rxh2 *\n"
+        ));
+        assert!(!written.contains('\r'));
+        assert_eq!(write_canonical(&game(&written)), written)
+    }
+
+    #[test]
+    fn keeps_nags_comments_and_variations() {
+        let text =
+            "[Result \"*\"]\n\n1. e4 $1 e5 (1... c5 {Sicilian} 2. Nf3 (2. Nc3) d6) 2. Nf3 $14 *\n";
+        let written = write_canonical(&game(text));
+        assert!(written
+            .ends_with("1. e4 $1 e5 (1... c5 {Sicilian} 2. Nf3 (2. Nc3) 2... d6) 2. Nf3 $14 *\n"));
+        assert_eq!(write_canonical(&game(&written)), written)
+    }
+
+    #[test]
+    fn numbers_moves_from_fullmove_clock() {
+        let text = "[Result \"*\"]\n\n1. e4 e5 7. Nf3 (9. Nc3) Nc6 *\n";
+        assert!(write_canonical(&game(text)).ends_with("1. e4 e5 2. Nf3 (2. Nc3) 2... Nc6 *\n"));
+
+        let setup =
+            "[FEN \"4k3/8/8/8/8/8/8/4K3 b - - 0 40\"]\n[Result \"*\"]\n\n1... Kd7 2. Ke2 *\n";
+        assert!(write_canonical(&game(setup)).ends_with("40... Kd7 41. Ke2 *\n"))
+    }
+
+    #[test]
+    fn wraps_at_line_width() {
+        let tokens: Vec<String> = (1..=30)
+            .flat_map(|n| [format!("{n}."), "Nf3".to_string()])
+            .collect();
+        let text = wrap(&tokens);
+
+        assert!(text.lines().all(|line| line.len() <= LINE_WIDTH));
+        assert_eq!(text.split_whitespace().count(), 60);
+        assert!(text
+            .lines()
+            .all(|line| line.starts_with(char::is_numeric) && line.ends_with("Nf3")))
+    }
+
+    #[test]
+    fn ends_line_after_rest_of_line_comment() {
+        let tokens: Vec<String> = ["1.", "e4", "; Threatens {mate}", "1...", "e5", "*"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(wrap(&tokens), "1. e4 ; Threatens {mate}\n1... e5 *")
+    }
+
+    #[test]
+    fn round_trips_comment_with_closing_brace() {
+        let text =
+            "[Result \"*\"]\n\n1. e4 ; Threatens {mate}\n1... e5 (1... c5 ; {Sicilian}\n) *\n";
+        let written = write_canonical(&game(text));
+        assert!(written.ends_with("1. e4 ; Threatens {mate}\n1... e5 (1... c5 ; {Sicilian}\n) *\n"));
+        assert_eq!(write_canonical(&game(&written)), written)
+    }
+
+    fn game(text: &str) -> Game {
+        let pgn = parse(text).unwrap().remove(0);
+        let boards = engine::execute_moves(pgn.fen().starting_board(), pgn.ply()).unwrap();
+        Game::new(pgn, boards)
+    }
+}
@@ -0,0 +1,273 @@
+use crate::engine;
+use crate::model::{Board, Pgn, PieceColour, Ply};
+
+const EVAL: &str = "[%eval ";
+
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Annotation {
+    nag: Option<u8>,
+    comment: Option<String>,
+    variation: Vec<String>,
+}
+
+impl Annotation {
+    pub fn new(nag: Option<u8>, comment: Option<String>, variation: Vec<String>) -> Self {
+        Annotation {
+            nag,
+            comment,
+            variation,
+        }
+    }
+
+    pub fn nag(&self) -> Option<u8> {
+        self.nag
+    }
+
+    pub fn comment(&self) -> Option<&String> {
+        self.comment.as_ref()
+    }
+
+    // SAN moves replacing the annotated ply
+    pub fn variation(&self) -> &[String] {
+        &self.variation
+    }
+}
+
+// With a starting board, each ply is written in the engine's SAN and numbered from the board's
+// fullmove clock; otherwise as parsed
+pub fn write(pgn: &Pgn, board: Option<&Board>, annotations: &[Annotation]) -> Vec<String> {
+    let start = pgn.fen().starting_board();
+    let mut tokens = write_plies(
+        pgn.ply(),
+        *start.active_colour(),
+        start.fullmove_clock(),
+        board.cloned(),
+        annotations,
+    );
+    tokens.push(pgn.result().to_string());
+    tokens
+}
+
+// A ply which can't be resolved is written as parsed, as are all the plies after it
+fn write_plies(
+    plies: &[Ply],
+    colour: PieceColour,
+    move_number: usize,
+    board: Option<Board>,
+    annotations: &[Annotation],
+) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut colour = colour;
+    let mut move_number = move_number;
+    let mut board = board;
+    let mut interrupted = true;
+
+    for (index, ply) in plies.iter().enumerate() {
+        // Variations start from the position before the ply they replace
+        let before = board.clone();
+        if let Some(current) = &board {
+            move_number = current.fullmove_clock();
+        }
+        let resolved = board
+            .as_ref()
+            .and_then(|current| engine::resolve(current, ply.movement()).ok());
+        let san = match (board.as_mut(), resolved) {
+            (Some(current), Some(movement)) => {
+                let san = engine::san(current, &movement);
+                engine::make_move(current, &movement);
+                san
+            }
+            _ => {
+                board = None;
+                ply.movement().to_string()
+            }
+        };
+
+        match colour {
+            PieceColour::White => tokens.push(format!("{move_number}.")),
+            PieceColour::Black if interrupted => tokens.push(format!("{move_number}...")),
+            PieceColour::Black => {}
+        }
+        tokens.push(san);
+        interrupted = false;
+
+        let annotation = annotations.get(index);
+        let nags = ply
+            .nags()
+            .iter()
+            .copied()
+            .chain(annotation.and_then(Annotation::nag));
+        tokens.extend(nags.map(|nag| format!("${nag}")));
+
+        // A fresh evaluation replaces any the ply already carried, so each move has one %eval
+        let evaluated = annotation
+            .and_then(Annotation::comment)
+            .is_some_and(|comment| comment.contains(EVAL));
+        let existing = ply.comment().map(|comment| {
+            if evaluated {
+                without_eval(comment)
+            } else {
+                comment.clone()
+            }
+        });
+        let comment = [annotation.and_then(Annotation::comment), existing.as_ref()]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .filter(|comment| !comment.is_empty())
+            .collect::<Vec<&str>>()
+            .join(" ");
+        if !comment.is_empty() {
+            tokens.push(comment_token(&comment));
+            interrupted = true;
+        }
+
+        for variation in ply.variations() {
+            let mut variation = write_plies(variation, colour, move_number, before.clone(), &[]);
+            if let Some(first) = variation.first_mut() {
+                first.insert(0, '(');
+            }
+            match variation.last_mut() {
+                Some(last) if !last.starts_with(';') => last.push(')'),
+                _ => variation.push(")".to_string()),
+            }
+            tokens.extend(variation);
+            interrupted = true;
+        }
+
+        if let Some(annotation) = annotation.filter(|a| !a.variation().is_empty()) {
+            tokens.push(format!(
+                "({})",
+                line(move_number, colour, annotation.variation())
+            ));
+            interrupted = true;
+        }
+
+        if colour == PieceColour::Black {
+            move_number += 1;
+        }
+        colour = colour.opposite();
+    }
+    tokens
+}
+
+// Drops every [%eval ...] command, tidying the spaces either side
+fn without_eval(comment: &str) -> String {
+    let mut kept = String::new();
+    let mut rest = comment;
+    while let Some(start) = rest.find(EVAL) {
+        kept.push_str(&rest[..start]);
+        rest = rest[start..]
+            .find(']')
+            .map_or("", |end| &rest[start + end + 1..]);
+    }
+    kept.push_str(rest);
+    kept.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+// Brace comments can't contain '}', so those are written as rest-of-line comments instead
+fn comment_token(comment: &str) -> String {
+    if comment.contains('}') {
+        format!("; {}", comment.replace(['\r', '\n'], " "))
+    } else {
+        format!("{{{comment}}}")
+    }
+}
+
+pub fn line(move_number: usize, colour: PieceColour, moves: &[String]) -> String {
+    let mut move_number = move_number;
+    let mut colour = colour;
+    let mut tokens = Vec::new();
+    for (index, san) in moves.iter().enumerate() {
+        match colour {
+            PieceColour::White => tokens.push(format!("{move_number}. {san}")),
+            PieceColour::Black if index == 0 => tokens.push(format!("{move_number}... {san}")),
+            PieceColour::Black => tokens.push(san.clone()),
+        }
+        if colour == PieceColour::Black {
+            move_number += 1;
+        }
+        colour = colour.opposite();
+    }
+    tokens.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse;
+
+    mod write_tests {
+        use super::*;
+
+        #[test]
+        fn writes_move_numbers_and_result() {
+            let pgn = game("1. e4 e5 2. Nf3 {Develops} Nc6 *");
+            assert_eq!(
+                write(&pgn, None, &[]).join(" "),
+                "1. e4 e5 2. Nf3 {Develops} 2... Nc6 *"
+            )
+        }
+
+        #[test]
+        fn writes_annotations() {
+            let pgn = game("1. e4 e5 2. Qh5 *");
+            let annotations = vec![
+                Annotation::default(),
+                Annotation::default(),
+                Annotation::new(
+                    Some(6),
+                    Some("Early".to_string()),
+                    to_strings(&["Nf3", "Nc6"]),
+                ),
+            ];
+            assert_eq!(
+                write(&pgn, None, &annotations).join(" "),
+                "1. e4 e5 2. Qh5 $6 {Early} (2. Nf3 Nc6) *"
+            )
+        }
+
+        #[test]
+        fn writes_comment_with_closing_brace_to_end_of_line() {
+            let pgn = game("1. e4 ; Threatens {mate}\ne5 *");
+            assert_eq!(
+                write(&pgn, None, &[]),
+                to_strings(&["1.", "e4", "; Threatens {mate}", "1...", "e5", "*"])
+            )
+        }
+
+        #[test]
+        fn writes_parsed_nags_and_variations() {
+            let pgn = game("1. e4 $1 e5 (1... c5 $2 {Sicilian} (1... e6) 2. Nf3) 2. Nf3 *");
+            assert_eq!(
+                write(&pgn, None, &[]).join(" "),
+                "1. e4 $1 e5 (1... c5 $2 {Sicilian} (1... e6) 2. Nf3) 2. Nf3 *"
+            )
+        }
+    }
+
+    mod line_tests {
+        use super::*;
+
+        #[test]
+        fn numbers_line_from_white() {
+            let moves = to_strings(&["e4", "e5", "Nf3"]);
+            assert_eq!(line(1, PieceColour::White, &moves), "1. e4 e5 2. Nf3")
+        }
+
+        #[test]
+        fn numbers_line_from_black() {
+            let moves = to_strings(&["e5", "Nf3", "Nc6"]);
+            assert_eq!(line(1, PieceColour::Black, &moves), "1... e5 2. Nf3 Nc6")
+        }
+    }
+
+    fn game(movetext: &str) -> Pgn {
+        let mut pgns = parse(&format!("[Result \"*\"]\n\n{movetext}\n")).unwrap();
+        pgns.remove(0)
+    }
+
+    fn to_strings(moves: &[&str]) -> Vec<String> {
+        moves.iter().map(ToString::to_string).collect()
+    }
+}
@@ -0,0 +1,82 @@
+use crate::model::{Board, Pgn};
+use crate::parse::{parse_fen, DEFAULT_FEN};
+
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+pub fn write(pgn: &Pgn) -> String {
+    let tags = pgn.tags();
+    let result = pgn.result().to_string();
+
+    let mut pairs: Vec<(&str, &str)> = SEVEN_TAG_ROSTER
+        .iter()
+        .map(|&(name, default)| match name {
+            "Result" => (name, result.as_str()),
+            _ => (name, tags.get(name).map_or(default, String::as_str)),
+        })
+        .collect();
+
+    let mut others: Vec<(&str, &str)> = tags
+        .inner()
+        .iter()
+        .filter(|(name, _)| {
+            !SEVEN_TAG_ROSTER
+                .iter()
+                .any(|(roster_name, _)| roster_name == name)
+                && *name != "SetUp"
+        })
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+    others.sort_unstable();
+    pairs.append(&mut others);
+
+    let board = pgn.fen().starting_board();
+    let fen = board.fen();
+    if !is_variant_start(board) {
+        pairs.push(("SetUp", "1"));
+        pairs.push(("FEN", fen.as_str()));
+    }
+
+    pairs
+        .into_iter()
+        .map(|(name, value)| format!("[{name} \"{}\"]\n", escape(value)))
+        .collect()
+}
+
+// Boards are compared rather than FENs, as drop variants write their empty pockets
+fn is_variant_start(board: &Board) -> bool {
+    let fen = board.variant().starting_fen().unwrap_or(DEFAULT_FEN);
+    let Ok(start) = parse_fen(fen) else {
+        return false;
+    };
+    let mut start = start.starting_board().clone();
+    start.update_variant(board.variant());
+
+    *board == start
+        && board.halfmove_clock() == start.halfmove_clock()
+        && board.fullmove_clock() == start.fullmove_clock()
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_quotes_and_backslashes() {
+        assert_eq!(
+            escape(r#"The "Immortal" \ Game"#),
+            r#"The \"Immortal\" \\ Game"#
+        )
+    }
+}
@@ -1,5 +1,6 @@
 use pigin::execute_moves;
 use pigin::parse;
+use pigin::write_pgn;
 use std::fs;
 
 #[test]
@@ -18,3 +19,22 @@ fn parses_games() {
         }
     }
 }
+
+#[test]
+fn round_trips_games() {
+    let test_files = fs::read_dir("./resources/test/acceptance").unwrap();
+    for file in test_files {
+        let path = file.unwrap().path();
+        println!("Writing {:?}", &path);
+        let content = fs::read_to_string(path).unwrap();
+        let pgns = parse(&content).unwrap();
+
+        let written: String = pgns
+            .iter()
+            .map(write_pgn)
+            .collect::<Vec<String>>()
+            .join("\n");
+        assert!(written.lines().all(|line| line.len() <= 80));
+        assert_eq!(parse(&written).unwrap(), pgns)
+    }
+}